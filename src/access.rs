@@ -0,0 +1,122 @@
+//! Memory access control bits changed at runtime
+
+use display_interface::WriteOnlyDataCommand;
+
+use crate::{Command, Ili9342C, PixelFormat, Result};
+
+/// Row address order
+pub(crate) const MY: u8 = 0x80;
+/// Column address order
+pub(crate) const MX: u8 = 0x40;
+/// Row and column exchange
+pub(crate) const MV: u8 = 0x20;
+/// Vertical refresh order
+const ML: u8 = 0x10;
+/// Horizontal refresh order
+const MH: u8 = 0x04;
+
+/// Order in which the panel lines are refreshed from memory
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VerticalRefresh {
+    /// Top to bottom, the default after reset
+    #[default]
+    TopToBottom,
+    /// Bottom to top
+    BottomToTop,
+}
+
+/// Order in which the pixels of a line are refreshed from memory
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HorizontalRefresh {
+    /// Left to right, the default after reset
+    #[default]
+    LeftToRight,
+    /// Right to left
+    RightToLeft,
+}
+
+impl<IFACE, C> Ili9342C<IFACE, C>
+where
+    IFACE: WriteOnlyDataCommand,
+    C: PixelFormat,
+{
+    /// Change the memory access control value sent last
+    fn update_madctl(&mut self, update: impl FnOnce(u8) -> u8) -> Result {
+        let madctl = update(self.state.panel.madctl());
+        self.command(Command::MemoryAccessControl, &[madctl])
+    }
+
+    /// Turn the picture upside down, keeping the width and height
+    ///
+    /// Toggles the row and column address order, so the same coordinates
+    /// address the opposite side of the panel. The memory is not rewritten,
+    /// redraw the content to show it flipped. Setting the orientation again
+    /// undoes the flip.
+    pub fn flip_180(&mut self) -> Result {
+        let before = self.orientation_state();
+        self.update_madctl(|madctl| madctl ^ (MY | MX))?;
+        self.orientation_changed(before);
+        Ok(())
+    }
+
+    /// Address order bits mirroring the x and y axes for a memory access
+    /// control value
+    pub(crate) fn mirror_bits(madctl: u8, x: bool, y: bool) -> u8 {
+        // With rows and columns exchanged, x runs along the rows
+        let (x_bit, y_bit) = if madctl & MV != 0 { (MY, MX) } else { (MX, MY) };
+        (if x { x_bit } else { 0 }) | (if y { y_bit } else { 0 })
+    }
+
+    /// Mirror the picture horizontally and/or vertically, on top of the
+    /// orientation
+    ///
+    /// For modules mounted behind a mirror or with a flex cable flipping one
+    /// axis. The mirroring is kept when the orientation changes. Like
+    /// [Self::flip_180], the memory is not rewritten.
+    pub fn set_mirror(&mut self, x: bool, y: bool) -> Result {
+        let old = self.state.mirror;
+        let new = Self::mirror_bits(self.state.panel.madctl(), x, y);
+        self.state.mirror = new;
+        self.update_madctl(|madctl| madctl ^ old ^ new)
+    }
+
+    /// Horizontal and vertical mirroring, see [Self::set_mirror]
+    pub fn mirror(&self) -> (bool, bool) {
+        let (x_bit, y_bit) = if self.state.panel.madctl() & MV != 0 {
+            (MY, MX)
+        } else {
+            (MX, MY)
+        };
+        (
+            self.state.mirror & x_bit != 0,
+            self.state.mirror & y_bit != 0,
+        )
+    }
+
+    /// Choose the order in which the panel is refreshed from memory
+    ///
+    /// The order is relative to the native panel and independent of the
+    /// orientation, which only changes how memory is written. Scanning in a
+    /// known direction lets band updates race the refresh without tearing.
+    /// The order is kept when the orientation changes.
+    pub fn set_refresh_order(
+        &mut self,
+        vertical: VerticalRefresh,
+        horizontal: HorizontalRefresh,
+    ) -> Result {
+        let old = self.state.refresh;
+        let mut new = 0;
+        if vertical == VerticalRefresh::BottomToTop {
+            new |= ML;
+        }
+        if horizontal == HorizontalRefresh::RightToLeft {
+            new |= MH;
+        }
+        self.state.refresh = new;
+        self.update_madctl(|madctl| madctl ^ old ^ new)
+    }
+}
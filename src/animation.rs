@@ -0,0 +1,155 @@
+//! Time based animations
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_hal::digital::InputPin;
+
+use crate::{tearing::poll_vsync, Ili9342C, Monotonic, PixelFormat, Result};
+
+/// Progress curve of an animation
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Easing {
+    /// Constant speed
+    #[default]
+    Linear,
+    /// Starting slow, quadratic
+    EaseIn,
+    /// Ending slow, quadratic
+    EaseOut,
+    /// Starting and ending slow, cubic
+    EaseInOut,
+}
+
+impl Easing {
+    /// Map a linear progress from 0 to 1 onto the curve
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Self::Linear => t,
+            Self::EaseIn => t * t,
+            Self::EaseOut => t * (2.0 - t),
+            Self::EaseInOut => t * t * (3.0 - 2.0 * t),
+        }
+    }
+
+    /// Value between `from` and `to` at a linear progress from 0 to 1
+    pub fn interpolate(self, from: i32, to: i32, t: f32) -> i32 {
+        let value = from as f32 + (to - from) as f32 * self.apply(t);
+        // Round half away from zero, f32::round needs std
+        if value < 0.0 {
+            (value - 0.5) as i32
+        } else {
+            (value + 0.5) as i32
+        }
+    }
+}
+
+/// Progress of an animation of a fixed duration
+///
+/// The progress follows the clock, so an animation takes the same time
+/// whatever the frame rate, slow frames only skip steps.
+///
+/// ```ignore
+/// let mut ticker = Ticker::new(&Timer, 300, Easing::EaseOut);
+/// ticker.run_vsync(&mut display, &mut te, |display, t| {
+///     let x = Easing::Linear.interpolate(-120, 0, t);
+///     panel.draw_at(display, Point::new(x, 40))
+/// })?;
+/// ```
+pub struct Ticker {
+    clock: &'static dyn Monotonic,
+    start_us: u64,
+    duration_us: u64,
+    easing: Easing,
+}
+
+impl Ticker {
+    /// Start an animation of `duration_ms`
+    pub fn new(clock: &'static dyn Monotonic, duration_ms: u32, easing: Easing) -> Self {
+        Self {
+            clock,
+            start_us: clock.now_us(),
+            duration_us: duration_ms as u64 * 1000,
+            easing,
+        }
+    }
+
+    /// Start the animation again
+    pub fn restart(&mut self) {
+        self.start_us = self.clock.now_us();
+    }
+
+    /// Linear progress from 0 to 1
+    pub fn linear_progress(&self) -> f32 {
+        if self.duration_us == 0 {
+            return 1.0;
+        }
+        let elapsed = self.clock.now_us().saturating_sub(self.start_us);
+        (elapsed as f32 / self.duration_us as f32).min(1.0)
+    }
+
+    /// Progress from 0 to 1 on the easing curve
+    pub fn progress(&self) -> f32 {
+        self.easing.apply(self.linear_progress())
+    }
+
+    /// Whether the duration has passed
+    pub fn is_done(&self) -> bool {
+        self.clock.now_us().saturating_sub(self.start_us) >= self.duration_us
+    }
+
+    /// Call `frame` with the eased progress until the animation is done,
+    /// the last call with a progress of 1
+    pub fn run<IFACE, C, F>(&mut self, display: &mut Ili9342C<IFACE, C>, frame: F) -> Result
+    where
+        IFACE: WriteOnlyDataCommand,
+        C: PixelFormat,
+        F: FnMut(&mut Ili9342C<IFACE, C>, f32) -> Result,
+    {
+        self.run_frames(display, frame, || Ok(()))
+    }
+
+    /// Like [Self::run], drawing each frame right after the TE pin signals
+    /// the vertical blanking
+    ///
+    /// The TE output has to be enabled with [Ili9342C::set_tearing_effect].
+    pub fn run_vsync<IFACE, C, F, TE>(
+        &mut self,
+        display: &mut Ili9342C<IFACE, C>,
+        te: &mut TE,
+        frame: F,
+    ) -> Result
+    where
+        IFACE: WriteOnlyDataCommand,
+        C: PixelFormat,
+        F: FnMut(&mut Ili9342C<IFACE, C>, f32) -> Result,
+        TE: InputPin,
+    {
+        self.run_frames(display, frame, || poll_vsync(te))
+    }
+
+    /// Draw frames until done, calling `wait` before each frame
+    fn run_frames<IFACE, C, F, W>(
+        &mut self,
+        display: &mut Ili9342C<IFACE, C>,
+        mut frame: F,
+        mut wait: W,
+    ) -> Result
+    where
+        IFACE: WriteOnlyDataCommand,
+        C: PixelFormat,
+        F: FnMut(&mut Ili9342C<IFACE, C>, f32) -> Result,
+        W: FnMut() -> Result,
+    {
+        loop {
+            wait()?;
+            let done = self.is_done();
+            let progress = if done { 1.0 } else { self.progress() };
+            frame(display, progress)?;
+            if done {
+                return Ok(());
+            }
+        }
+    }
+}
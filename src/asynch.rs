@@ -0,0 +1,290 @@
+//! Async DMA flushing, see the `async` feature
+
+use core::convert::Infallible;
+use core::future::{poll_fn, Future};
+use core::pin::pin;
+
+use display_interface::DisplayError;
+use embedded_graphics_core::{
+    draw_target::DrawTarget,
+    geometry::{OriginDimensions, Point, Size},
+    pixelcolor::Rgb565,
+    primitives::Rectangle,
+    Pixel,
+};
+use embedded_hal::digital::OutputPin;
+use embedded_hal_async::{digital::Wait, spi::SpiDevice};
+
+use crate::{Command, DisplayState, DmaPixelBuffer, PixelEndian, Result};
+
+/// Async writer of pixel buffers on an [SpiDevice]
+///
+/// Every buffer is sent in a single write, which the HAL can run as one DMA
+/// transfer while the executor runs other tasks. The panel has to be
+/// initialized with [Ili9342C](crate::Ili9342C) first, for example on the
+/// same bus through [SpiInterface](crate::SpiInterface) and
+/// [Ili9342C::release](crate::Ili9342C::release).
+///
+/// Windows are moved by the offset of the released [DisplayState] and have
+/// to be inside its size and clip rectangle. Buffers are sent as they are,
+/// so only [PixelEndian::Big] is supported.
+pub struct AsyncFlusher<SPI, DC> {
+    spi: SPI,
+    dc: DC,
+    offset: (u16, u16),
+    area: Rectangle,
+    endian: PixelEndian,
+}
+
+impl<SPI, DC> AsyncFlusher<SPI, DC>
+where
+    SPI: SpiDevice,
+    DC: OutputPin,
+{
+    /// Create a flusher from a SPI device, the data/command pin and the
+    /// state of the driver that initialized the panel
+    pub fn new(spi: SPI, dc: DC, state: &DisplayState) -> Self {
+        let size = Rectangle::new(
+            Point::zero(),
+            Size::new(state.width as u32, state.height as u32),
+        );
+        Self {
+            spi,
+            dc,
+            offset: state.init.offset,
+            area: state.clip.map_or(size, |clip| clip.intersection(&size)),
+            endian: state.init.endian,
+        }
+    }
+
+    /// Release the SPI device and the data/command pin
+    pub fn release(self) -> (SPI, DC) {
+        (self.spi, self.dc)
+    }
+
+    async fn command(&mut self, cmd: Command, args: &[u8]) -> Result {
+        self.dc.set_low().map_err(|_| DisplayError::DCError)?;
+        self.spi
+            .write(&[cmd as u8])
+            .await
+            .map_err(|_| DisplayError::BusWriteError)?;
+        self.dc.set_high().map_err(|_| DisplayError::DCError)?;
+        if args.is_empty() {
+            return Ok(());
+        }
+        self.spi
+            .write(args)
+            .await
+            .map_err(|_| DisplayError::BusWriteError)
+    }
+
+    /// Set the window, returning [DisplayError::OutOfBoundsError] if it is
+    /// not inside the drawable area and [DisplayError::InvalidFormatError]
+    /// for a little endian byte order
+    async fn set_window(&mut self, x0: u16, y0: u16, x1: u16, y1: u16) -> Result {
+        if self.endian != PixelEndian::Big {
+            return Err(DisplayError::InvalidFormatError);
+        }
+        let inside = x0 <= x1
+            && y0 <= y1
+            && self.area.contains(Point::new(x0 as i32, y0 as i32))
+            && self.area.contains(Point::new(x1 as i32, y1 as i32));
+        if !inside {
+            return Err(DisplayError::OutOfBoundsError);
+        }
+        let (column, row) = self.offset;
+        let [x0h, x0l] = (x0 + column).to_be_bytes();
+        let [x1h, x1l] = (x1 + column).to_be_bytes();
+        let [y0h, y0l] = (y0 + row).to_be_bytes();
+        let [y1h, y1l] = (y1 + row).to_be_bytes();
+        self.command(Command::ColumnAddressSet, &[x0h, x0l, x1h, x1l])
+            .await?;
+        self.command(Command::PageAddressSet, &[y0h, y0l, y1h, y1l])
+            .await
+    }
+
+    /// Write a buffer to a window and wait for the transfer to complete
+    pub async fn flush(
+        &mut self,
+        x0: u16,
+        y0: u16,
+        x1: u16,
+        y1: u16,
+        buffer: &DmaPixelBuffer,
+    ) -> Result {
+        self.set_window(x0, y0, x1, y1).await?;
+        // The whole buffer in one write, so it can be one DMA transfer
+        self.command(Command::MemoryWrite, buffer.as_bytes()).await
+    }
+
+    /// Stream a window line by line through two line buffers, rendering the
+    /// next line while the previous one is sent
+    ///
+    /// `render` is called with the display row and the buffer to fill, its
+    /// first pixels are the line. The HAL has to start the transfer on the
+    /// first poll of the write, as DMA drivers do, for the rendering to run
+    /// during the transfer. Both buffers need the width of the window.
+    ///
+    /// ```ignore
+    /// let mut lines = [DmaPixelBuffer::new(LINE_A), DmaPixelBuffer::new(LINE_B)];
+    /// flusher.render_lines(0, 0, 319, 239, &mut lines, |y, line| {
+    ///     for x in 0..320 {
+    ///         line.set(x, mandelbrot(x, y));
+    ///     }
+    /// }).await?;
+    /// ```
+    pub async fn render_lines<F>(
+        &mut self,
+        x0: u16,
+        y0: u16,
+        x1: u16,
+        y1: u16,
+        lines: &mut [DmaPixelBuffer; 2],
+        mut render: F,
+    ) -> Result
+    where
+        F: FnMut(u16, &mut DmaPixelBuffer),
+    {
+        let width = x1.saturating_sub(x0) as usize + 1;
+        if x1 < x0 || y1 < y0 || lines.iter().any(|line| line.len() < width) {
+            return Err(DisplayError::OutOfBoundsError);
+        }
+        self.set_window(x0, y0, x1, y1).await?;
+        self.command(Command::MemoryWrite, &[]).await?;
+
+        render(y0, &mut lines[0]);
+        for y in y0..=y1 {
+            let [a, b] = lines;
+            let (front, back) = if (y - y0).is_multiple_of(2) {
+                (a, b)
+            } else {
+                (b, a)
+            };
+            let mut transfer = pin!(self.spi.write(&front.as_bytes()[..width * 2]));
+            let mut next = (y < y1).then_some((y + 1, back));
+            poll_fn(|cx| {
+                let done = transfer.as_mut().poll(cx);
+                // Render while the transfer started by the first poll runs
+                if let Some((y, back)) = next.take() {
+                    render(y, back);
+                }
+                done
+            })
+            .await
+            .map_err(|_| DisplayError::BusWriteError)?;
+        }
+        Ok(())
+    }
+}
+
+/// Wait for the start of the vertical blanking on the TE pin
+///
+/// The TE output has to be enabled with
+/// [Ili9342C::set_tearing_effect](crate::Ili9342C::set_tearing_effect).
+/// Writing a frame right after this returns avoids tearing if the transfer
+/// is faster than the panel refresh. Errors of the pin are returned as
+/// [DisplayError::BusWriteError].
+pub async fn wait_for_vsync<TE: Wait>(te: &mut TE) -> Result {
+    te.wait_for_rising_edge()
+        .await
+        .map_err(|_| DisplayError::BusWriteError)
+}
+
+/// Two pixel buffers of one frame, for rendering while the other is flushed
+///
+/// ```ignore
+/// loop {
+///     let (target, front) = buffers.split();
+///     join(flusher.flush(0, 0, 319, 239, front), render(target)).await.0?;
+///     buffers.swap();
+/// }
+/// ```
+pub struct DoubleBuffer {
+    buffers: [DmaPixelBuffer; 2],
+    width: u32,
+    front: usize,
+}
+
+impl DoubleBuffer {
+    /// Use two buffers of `width` pixels wide frames
+    ///
+    /// Returns [DisplayError::OutOfBoundsError] if the buffers have different
+    /// lengths or don't hold whole lines.
+    pub fn new(a: DmaPixelBuffer, b: DmaPixelBuffer, width: u32) -> Result<Self> {
+        if a.len() != b.len() || width == 0 || !a.len().is_multiple_of(width as usize) {
+            return Err(DisplayError::OutOfBoundsError);
+        }
+        Ok(Self {
+            buffers: [a, b],
+            width,
+            front: 0,
+        })
+    }
+
+    /// Buffer being flushed
+    pub fn front(&self) -> &DmaPixelBuffer {
+        &self.buffers[self.front]
+    }
+
+    /// Draw target of the buffer being rendered and the buffer being flushed
+    pub fn split(&mut self) -> (BufferTarget<'_>, &DmaPixelBuffer) {
+        let [a, b] = &mut self.buffers;
+        let (back, front) = if self.front == 0 { (b, a) } else { (a, b) };
+        let height = (back.len() / self.width as usize) as u32;
+        let target = BufferTarget {
+            buffer: back,
+            size: Size::new(self.width, height),
+        };
+        (target, front)
+    }
+
+    /// Swap the buffers, the rendered buffer becomes the front buffer
+    pub fn swap(&mut self) {
+        self.front ^= 1;
+    }
+
+    /// Release the buffers
+    pub fn into_inner(self) -> [DmaPixelBuffer; 2] {
+        self.buffers
+    }
+}
+
+/// [DrawTarget] rendering into the back buffer of a [DoubleBuffer]
+pub struct BufferTarget<'a> {
+    buffer: &'a mut DmaPixelBuffer,
+    size: Size,
+}
+
+impl OriginDimensions for BufferTarget<'_> {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl DrawTarget for BufferTarget<'_> {
+    type Color = Rgb565;
+
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> core::result::Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            if point.x >= 0
+                && point.y >= 0
+                && (point.x as u32) < self.size.width
+                && (point.y as u32) < self.size.height
+            {
+                let index = point.y as usize * self.size.width as usize + point.x as usize;
+                self.buffer.set(index, color);
+            }
+        }
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> core::result::Result<(), Self::Error> {
+        self.buffer.fill(color);
+        Ok(())
+    }
+}
@@ -0,0 +1,100 @@
+//! Sleeping the panel after inactivity
+
+use display_interface::WriteOnlyDataCommand;
+
+use crate::{Ili9342C, ModeState, PixelFormat, Result};
+
+/// Minimum time between Sleep In and Sleep Out, and after Sleep Out
+const SLEEP_SETTLE_US: u64 = 120_000;
+
+/// Inactivity tracked by the driver
+#[derive(Clone, Copy)]
+pub(crate) struct AutoSleep {
+    timeout_us: u64,
+    last_activity: Option<u64>,
+    /// Time of the Sleep In sent by the driver
+    asleep_since: Option<u64>,
+}
+
+impl<IFACE, C> Ili9342C<IFACE, C>
+where
+    IFACE: WriteOnlyDataCommand,
+    C: PixelFormat,
+{
+    /// Put the panel to sleep after `timeout_ms` without drawing, or disable
+    /// it with `None`
+    ///
+    /// Needs the clock set with [Self::set_clock]. Call
+    /// [Self::poll_auto_sleep] periodically to enter sleep. The next memory
+    /// write wakes the panel, busy waiting on the clock for the 120 ms
+    /// between Sleep In and Sleep Out and again after Sleep Out, running the
+    /// hooks of [Self::set_yield_hook] and [Self::set_watchdog_hook] meanwhile.
+    ///
+    /// ```ignore
+    /// display.set_clock(Some(&Timer));
+    /// display.set_auto_sleep(Some(60_000));
+    /// loop {
+    ///     if let Some(event) = input.poll() {
+    ///         ui.handle(event, &mut display)?;
+    ///     }
+    ///     display.poll_auto_sleep()?;
+    /// }
+    /// ```
+    pub fn set_auto_sleep(&mut self, timeout_ms: Option<u32>) {
+        let last_activity = self.state.timing.now();
+        self.state.auto_sleep = timeout_ms.map(|timeout_ms| AutoSleep {
+            timeout_us: timeout_ms as u64 * 1000,
+            last_activity,
+            asleep_since: self.state.auto_sleep.and_then(|state| state.asleep_since),
+        });
+    }
+
+    /// Enter sleep when the auto sleep timeout passed, returning whether the
+    /// panel is asleep from it
+    pub fn poll_auto_sleep(&mut self) -> Result<bool> {
+        let (Some(state), Some(now)) = (self.state.auto_sleep, self.state.timing.now()) else {
+            return Ok(false);
+        };
+        if state.asleep_since.is_some() {
+            return Ok(true);
+        }
+        let last_activity = state.last_activity.unwrap_or(now);
+        if now.saturating_sub(last_activity) < state.timeout_us || self.state.panel.sleeping {
+            return Ok(false);
+        }
+        self.sleep_mode(ModeState::On)?;
+        if let Some(state) = self.state.auto_sleep.as_mut() {
+            state.asleep_since = Some(now);
+        }
+        Ok(true)
+    }
+
+    /// Record drawing activity, waking the panel if it was put to sleep by
+    /// [Self::poll_auto_sleep]
+    pub(crate) fn auto_wake(&mut self) -> Result {
+        let now = self.state.timing.now();
+        let Some(state) = self.state.auto_sleep.as_mut() else {
+            return Ok(());
+        };
+        state.last_activity = now;
+        let Some(since) = state.asleep_since.take() else {
+            return Ok(());
+        };
+        self.wait_until(since + SLEEP_SETTLE_US);
+        self.sleep_mode(ModeState::Off)?;
+        if let Some(now) = self.state.timing.now() {
+            self.wait_until(now + SLEEP_SETTLE_US);
+        }
+        if let Some(state) = self.state.auto_sleep.as_mut() {
+            state.last_activity = self.state.timing.now();
+        }
+        Ok(())
+    }
+
+    /// Busy wait on the clock, running the yield and watchdog hooks
+    fn wait_until(&self, deadline_us: u64) {
+        while self.state.timing.now().is_some_and(|now| now < deadline_us) {
+            self.between_chunks();
+        }
+    }
+}
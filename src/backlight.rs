@@ -0,0 +1,50 @@
+//! Brightness and content adaptive brightness control
+//!
+//! These registers drive the LEDPWM output of the controller, they only have
+//! an effect on modules with the backlight driver connected to that pin.
+
+use display_interface::WriteOnlyDataCommand;
+
+use crate::{Command, Ili9342C, PixelFormat, Result};
+
+/// Brightness control block, dimming and backlight bits of Write CTRL Display
+const CTRL_DISPLAY_ON: u8 = 0x20 | 0x08 | 0x04;
+
+/// Content adaptive brightness control mode
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CabcMode {
+    /// Disabled
+    Off = 0x00,
+    /// Optimized for user interfaces
+    UserInterface = 0x01,
+    /// Optimized for still pictures
+    StillPicture = 0x02,
+    /// Optimized for moving images, saves the most power
+    MovingImage = 0x03,
+}
+
+impl<IFACE, C> Ili9342C<IFACE, C>
+where
+    IFACE: WriteOnlyDataCommand,
+    C: PixelFormat,
+{
+    /// Enable or disable the brightness control block
+    ///
+    /// Brightness and CABC settings are only applied while it is enabled.
+    pub fn set_brightness_control(&mut self, enable: bool) -> Result {
+        let value = if enable { CTRL_DISPLAY_ON } else { 0x00 };
+        self.command(Command::WriteCtrlDisplay, &[value])
+    }
+
+    /// Set the display brightness, 0 is off and 255 is the brightest
+    pub fn set_brightness(&mut self, brightness: u8) -> Result {
+        self.command(Command::SetBrightness, &[brightness])
+    }
+
+    /// Select the content adaptive brightness control mode
+    pub fn set_cabc(&mut self, mode: CabcMode) -> Result {
+        self.command(Command::ContentAdaptiveBrightness, &[mode as u8])
+    }
+}
@@ -0,0 +1,114 @@
+//! Rendering the screen in horizontal bands
+
+use core::convert::Infallible;
+
+use display_interface::{DisplayError, WriteOnlyDataCommand};
+use embedded_graphics_core::{
+    draw_target::DrawTarget,
+    geometry::{OriginDimensions, Point, Size},
+    pixelcolor::Rgb565,
+    primitives::Rectangle,
+    Pixel,
+};
+
+use crate::{Ili9342C, Result, Rgb565Framebuffer};
+
+/// [DrawTarget] of one band of [Ili9342C::render_bands]
+///
+/// Coordinates are the ones of the display and drawing is clipped to the
+/// band, so the whole scene can be drawn for every band.
+pub struct Band<'a, 'b> {
+    framebuffer: &'a mut Rgb565Framebuffer<'b>,
+    area: Rectangle,
+    display_size: Size,
+}
+
+impl Band<'_, '_> {
+    /// Area of the band on the display, drawing outside of it can be skipped
+    pub fn area(&self) -> Rectangle {
+        self.area
+    }
+}
+
+impl OriginDimensions for Band<'_, '_> {
+    fn size(&self) -> Size {
+        self.display_size
+    }
+}
+
+impl DrawTarget for Band<'_, '_> {
+    type Color = Rgb565;
+
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> core::result::Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let top = self.area.top_left.y;
+        self.framebuffer.draw_iter(
+            pixels
+                .into_iter()
+                .map(|Pixel(point, color)| Pixel(Point::new(point.x, point.y - top), color)),
+        )
+    }
+
+    fn clear(&mut self, color: Self::Color) -> core::result::Result<(), Self::Error> {
+        self.framebuffer.clear(color)
+    }
+}
+
+impl<IFACE> Ili9342C<IFACE, Rgb565>
+where
+    IFACE: WriteOnlyDataCommand,
+{
+    /// Render the screen in `bands` horizontal bands through a buffer of one
+    /// band, calling `draw` for each band and writing it to the display
+    ///
+    /// The buffer needs the display width times the band height, the height
+    /// divided by `bands` and rounded up. Drawing is done at full color depth
+    /// with a fraction of the memory of a frame.
+    ///
+    /// ```ignore
+    /// // Four bands of 60 rows take 38 KB
+    /// static mut BAND: [u16; 320 * 60] = [0; 320 * 60];
+    /// display.render_bands(4, unsafe { &mut BAND }, |band| {
+    ///     band.clear(Rgb565::BLACK).ok();
+    ///     scene.draw(band).ok();
+    /// })?;
+    /// ```
+    pub fn render_bands<F>(&mut self, bands: usize, buffer: &mut [u16], mut draw: F) -> Result
+    where
+        F: FnMut(&mut Band<'_, '_>),
+    {
+        if bands == 0 {
+            return Err(DisplayError::OutOfBoundsError);
+        }
+        let (width, height) = (self.state.width, self.state.height);
+        let band_height = height.div_ceil(bands);
+        let mut top = 0;
+        while top < height {
+            let rows = band_height.min(height - top);
+            let mut framebuffer = Rgb565Framebuffer::new(&mut buffer[..], width, rows)?;
+            let mut band = Band {
+                framebuffer: &mut framebuffer,
+                area: Rectangle::new(
+                    Point::new(0, top as i32),
+                    Size::new(width as u32, rows as u32),
+                ),
+                display_size: Size::new(width as u32, height as u32),
+            };
+            draw(&mut band);
+            self.draw_raw_slice(
+                0,
+                top as u16,
+                width as u16 - 1,
+                (top + rows) as u16 - 1,
+                framebuffer.as_slice(),
+                false,
+            )?;
+            top += rows;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+//! BMP encoding of screenshots
+
+use embedded_graphics_core::pixelcolor::{raw::RawU16, Rgb565};
+use embedded_graphics_core::prelude::RawData;
+
+/// Size of the file header, info header and RGB565 bit masks
+const HEADER_SIZE: usize = 14 + 40 + 12;
+
+/// Write a 16-bit BMP image in chunks
+///
+/// `pixels` are given row by row from the top left corner, the image is
+/// stored top down so it can be streamed without buffering rows. Missing
+/// pixels are written black.
+///
+/// ```ignore
+/// framebuffer.screenshot_bmp(&mut |chunk| file.write(chunk).unwrap());
+/// ```
+pub fn encode_bmp<I, F>(width: usize, height: usize, pixels: I, write: &mut F)
+where
+    I: IntoIterator<Item = Rgb565>,
+    F: FnMut(&[u8]) + ?Sized,
+{
+    let row_size = (width * 2).next_multiple_of(4);
+    let image_size = row_size * height;
+    let file_size = (HEADER_SIZE + image_size) as u32;
+
+    let mut header = [0u8; HEADER_SIZE];
+    header[0..2].copy_from_slice(b"BM");
+    header[2..6].copy_from_slice(&file_size.to_le_bytes());
+    header[10..14].copy_from_slice(&(HEADER_SIZE as u32).to_le_bytes());
+    header[14..18].copy_from_slice(&40u32.to_le_bytes());
+    header[18..22].copy_from_slice(&(width as i32).to_le_bytes());
+    // Negative height for rows stored top down
+    header[22..26].copy_from_slice(&(-(height as i32)).to_le_bytes());
+    header[26..28].copy_from_slice(&1u16.to_le_bytes());
+    header[28..30].copy_from_slice(&16u16.to_le_bytes());
+    // BI_BITFIELDS
+    header[30..34].copy_from_slice(&3u32.to_le_bytes());
+    header[34..38].copy_from_slice(&(image_size as u32).to_le_bytes());
+    header[54..58].copy_from_slice(&0xf800u32.to_le_bytes());
+    header[58..62].copy_from_slice(&0x07e0u32.to_le_bytes());
+    header[62..66].copy_from_slice(&0x001fu32.to_le_bytes());
+    write(&header);
+
+    let mut pixels = pixels.into_iter();
+    let mut chunk = [0u8; 64];
+    for _ in 0..height {
+        let mut len = 0;
+        for x in 0..row_size / 2 {
+            let raw = match x < width {
+                true => pixels
+                    .next()
+                    .map_or(0, |pixel| RawU16::from(pixel).into_inner()),
+                false => 0,
+            };
+            chunk[len..len + 2].copy_from_slice(&raw.to_le_bytes());
+            len += 2;
+            if len == chunk.len() {
+                write(&chunk);
+                len = 0;
+            }
+        }
+        if len > 0 {
+            write(&chunk[..len]);
+        }
+    }
+}
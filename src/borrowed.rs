@@ -0,0 +1,43 @@
+//! Driving the display through a borrowed interface
+
+use display_interface::{DataFormat, WriteOnlyDataCommand};
+use embedded_graphics_core::pixelcolor::Rgb565;
+
+use crate::{Ili9342C, Result};
+
+/// Interface borrowed for the lifetime of an [Ili9342CRef]
+///
+/// Lets the same bus be time-shared with other devices: the driver is
+/// attached to the interface while drawing and released afterwards, keeping
+/// the panel state in a [DisplayState](crate::DisplayState).
+///
+/// ```ignore
+/// let (mut iface, mut state) = Ili9342C::<_, Rgb565>::new(iface, &mut delay, mode, size)?.release();
+/// loop {
+///     let mut display = Ili9342CRef::from_parts(BorrowedInterface::new(&mut iface), state);
+///     display.clear(Rgb565::BLACK)?;
+///     state = display.release().1;
+///     // Use the bus for something else
+/// }
+/// ```
+pub struct BorrowedInterface<'a, IFACE>(&'a mut IFACE);
+
+impl<'a, IFACE> BorrowedInterface<'a, IFACE> {
+    /// Borrow an interface
+    pub fn new(interface: &'a mut IFACE) -> Self {
+        Self(interface)
+    }
+}
+
+impl<IFACE: WriteOnlyDataCommand> WriteOnlyDataCommand for BorrowedInterface<'_, IFACE> {
+    fn send_commands(&mut self, cmd: DataFormat<'_>) -> Result {
+        self.0.send_commands(cmd)
+    }
+
+    fn send_data(&mut self, buf: DataFormat<'_>) -> Result {
+        self.0.send_data(buf)
+    }
+}
+
+/// Driver using a [BorrowedInterface]
+pub type Ili9342CRef<'a, IFACE, C = Rgb565> = Ili9342C<BorrowedInterface<'a, IFACE>, C>;
@@ -0,0 +1,264 @@
+//! Builder for displays with non default init settings
+
+use core::marker::PhantomData;
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_graphics_core::pixelcolor::Rgb565;
+use embedded_hal_0_2::blocking::delay::DelayMs;
+
+use crate::{
+    clock::TimingState, debug::PanelState, stats::StatsState, ColorOrder, Command,
+    DisplayFunctionConfig, DisplaySize, DisplayState, DriverTimingA, DriverTimingB, FrameDivision,
+    FrameRate, GammaTables, Ili9342C, InitOptions, Mode, ModeState, PixelEndian, PixelFormat,
+    PowerConfig, PowerOnSequence, PumpRatio, Result,
+};
+
+/// Builder for [Ili9342C], configuring the init sequence
+///
+/// ```ignore
+/// let mut display = Builder::<_, Rgb565>::new(iface)
+///     .gamma(GammaPreset::HighContrast)
+///     .init(&mut delay, Orientation::Landscape, DisplaySize320x240)?;
+/// ```
+pub struct Builder<IFACE, C = Rgb565> {
+    interface: IFACE,
+    settings: InitSettings,
+    max_transfer: Option<usize>,
+    _color: PhantomData<C>,
+}
+
+/// Settings of the init sequence
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub(crate) struct InitSettings {
+    gamma: GammaTables,
+    power: PowerConfig,
+    pump_ratio: Option<PumpRatio>,
+    driver_timing_a: Option<DriverTimingA>,
+    driver_timing_b: Option<DriverTimingB>,
+    power_on_sequence: Option<PowerOnSequence>,
+    display_function: DisplayFunctionConfig,
+    color_order: ColorOrder,
+    inverted: bool,
+    frame_rate: Option<(FrameDivision, FrameRate)>,
+    pub(crate) offset: (u16, u16),
+    pub(crate) endian: PixelEndian,
+}
+
+impl Default for InitSettings {
+    fn default() -> Self {
+        let options = InitOptions::default();
+        Self {
+            gamma: GammaTables::default(),
+            power: PowerConfig::default(),
+            pump_ratio: None,
+            driver_timing_a: None,
+            driver_timing_b: None,
+            power_on_sequence: None,
+            display_function: DisplayFunctionConfig::default(),
+            color_order: options.color_order,
+            inverted: options.inverted,
+            frame_rate: options.frame_rate,
+            offset: options.offset,
+            endian: PixelEndian::Big,
+        }
+    }
+}
+
+impl InitSettings {
+    /// Interface Control parameters, with the ENDIAN bit for
+    /// [PixelEndian::LittleController]
+    pub(crate) fn interface_ctrl(&self) -> [u8; 3] {
+        let endian = if self.endian == PixelEndian::LittleController {
+            0x20
+        } else {
+            0
+        };
+        [0x00, 0x01, 0x01 | endian]
+    }
+
+    /// Memory access control value sent for a mode
+    pub(crate) fn madctl<MODE: Mode>(&self, mode: &MODE) -> u8 {
+        self.color_order.madctl(mode.mode())
+    }
+
+    /// Run the init sequence on a display
+    pub(crate) fn apply<IFACE, C, DELAY, MODE>(
+        &self,
+        ili: &mut Ili9342C<IFACE, C>,
+        delay: &mut DELAY,
+        mode: &MODE,
+    ) -> Result
+    where
+        IFACE: WriteOnlyDataCommand,
+        C: PixelFormat,
+        DELAY: DelayMs<u16>,
+        MODE: Mode,
+    {
+        ili.command(Command::SoftwareReset, &[])?;
+        delay.delay_ms(10);
+        ili.command(Command::ExtC, &[0xff, 0x93, 0x42])?;
+        if let Some(sequence) = self.power_on_sequence {
+            ili.set_power_on_sequence(sequence)?;
+        }
+        if let Some(timing) = self.driver_timing_a {
+            ili.set_driver_timing_a(timing)?;
+        }
+        if let Some(timing) = self.driver_timing_b {
+            ili.set_driver_timing_b(timing)?;
+        }
+        if let Some(ratio) = self.pump_ratio {
+            ili.set_pump_ratio(ratio)?;
+        }
+        ili.set_power_config(&self.power)?;
+        ili.command(Command::RBGInterface, &[0xe0])?;
+        ili.command(Command::InterfaceCtrl, &self.interface_ctrl())?;
+        // Default is 0x80, 0x20, 0x08
+        ili.command(Command::MemoryAccessControl, &[self.madctl(mode)])?;
+        ili.command(Command::PixelFormatSet, &[C::PIXEL_FORMAT_SET])?;
+        if let Some(table) = C::COLOR_SET {
+            ili.set_color_set(table)?;
+        }
+        ili.set_display_function(&self.display_function)?;
+        if let Some((division, rate)) = self.frame_rate {
+            ili.set_frame_rate(division, rate)?;
+        }
+        ili.set_gamma(&self.gamma.positive, &self.gamma.negative)?;
+        ili.sleep_mode(ModeState::Off)?;
+        delay.delay_ms(120);
+        ili.display_mode(ModeState::On)?;
+        if self.inverted {
+            ili.command(Command::InvertOn, &[])?;
+        }
+
+        // Wait 5ms after Sleep Out before sending commands
+        delay.delay_ms(5);
+
+        Ok(())
+    }
+}
+
+impl<IFACE, C> Builder<IFACE, C>
+where
+    IFACE: WriteOnlyDataCommand,
+    C: PixelFormat,
+{
+    /// Start a builder with the default init settings
+    pub fn new(interface: IFACE) -> Self {
+        Self {
+            interface,
+            settings: InitSettings::default(),
+            max_transfer: None,
+            _color: PhantomData,
+        }
+    }
+
+    /// Gamma correction tables written during init, either [GammaTables] or a
+    /// [GammaPreset](crate::GammaPreset)
+    pub fn gamma(mut self, gamma: impl Into<GammaTables>) -> Self {
+        self.settings.gamma = gamma.into();
+        self
+    }
+
+    /// Power control settings written during init
+    pub fn power(mut self, power: PowerConfig) -> Self {
+        self.settings.power = power;
+        self
+    }
+
+    /// Pump ratio written during init, not sent by default
+    pub fn pump_ratio(mut self, ratio: PumpRatio) -> Self {
+        self.settings.pump_ratio = Some(ratio);
+        self
+    }
+
+    /// Driver Timing Control A written during init, not sent by default
+    pub fn driver_timing_a(mut self, timing: DriverTimingA) -> Self {
+        self.settings.driver_timing_a = Some(timing);
+        self
+    }
+
+    /// Driver Timing Control B written during init, not sent by default
+    pub fn driver_timing_b(mut self, timing: DriverTimingB) -> Self {
+        self.settings.driver_timing_b = Some(timing);
+        self
+    }
+
+    /// Power On Sequence Control written during init, not sent by default
+    pub fn power_on_sequence(mut self, sequence: PowerOnSequence) -> Self {
+        self.settings.power_on_sequence = Some(sequence);
+        self
+    }
+
+    /// Display Function Control settings written during init
+    pub fn display_function(mut self, config: DisplayFunctionConfig) -> Self {
+        self.settings.display_function = config;
+        self
+    }
+
+    /// Apply the color order, inversion, gamma, frame rate and offset of
+    /// [InitOptions], the orientation is the one passed to [Self::init]
+    pub fn options(mut self, options: &InitOptions) -> Self {
+        self.settings.gamma = options.gamma.into();
+        self.settings.color_order = options.color_order;
+        self.settings.inverted = options.inverted;
+        self.settings.frame_rate = options.frame_rate;
+        self.settings.offset = options.offset;
+        self
+    }
+
+    /// Byte order of the pixel data, big endian by default
+    pub fn pixel_endian(mut self, endian: PixelEndian) -> Self {
+        self.settings.endian = endian;
+        self
+    }
+
+    /// Maximum number of bytes sent per interface call, see
+    /// [Ili9342C::set_max_transfer_size]
+    pub fn max_transfer_size(mut self, bytes: usize) -> Self {
+        self.max_transfer = Some(bytes);
+        self
+    }
+
+    /// Run the init sequence and return the display
+    pub fn init<DELAY, SIZE, MODE>(
+        self,
+        delay: &mut DELAY,
+        mode: MODE,
+        _display_size: SIZE,
+    ) -> Result<Ili9342C<IFACE, C>>
+    where
+        DELAY: DelayMs<u16>,
+        SIZE: DisplaySize,
+        MODE: Mode,
+    {
+        let state = DisplayState {
+            width: SIZE::WIDTH,
+            height: SIZE::HEIGHT,
+            // The panel is natively landscape
+            landscape: true,
+            init: self.settings,
+            max_transfer: self.max_transfer,
+            yield_hook: None,
+            stats: StatsState::default(),
+            observer: None,
+            panel: PanelState::default(),
+            timing: TimingState::default(),
+            pixel_shift: None,
+            color_lut: None,
+            clip: None,
+            mirror: 0,
+            refresh: 0,
+            watchdog: None,
+            orientation_hook: None,
+            downshift: None,
+            auto_sleep: None,
+        };
+        let mut ili = Ili9342C::from_parts(self.interface, state);
+        self.settings.apply(&mut ili, delay, &mode)?;
+        ili.update_orientation(&mode);
+
+        Ok(ili)
+    }
+}
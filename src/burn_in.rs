@@ -0,0 +1,73 @@
+//! Burn-in mitigation by shifting the content
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_graphics_core::geometry::Point;
+
+use crate::{Ili9342C, PixelFormat};
+
+/// Offsets cycled through, one pixel around the original position
+const OFFSETS: [(i8, i8); 9] = [
+    (0, 0),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+];
+
+/// State of the pixel shifting
+#[derive(Clone, Copy)]
+pub(crate) struct PixelShift {
+    interval_ms: u32,
+    elapsed_ms: u32,
+    step: u8,
+}
+
+impl<IFACE, C> Ili9342C<IFACE, C>
+where
+    IFACE: WriteOnlyDataCommand,
+    C: PixelFormat,
+{
+    /// Periodically shift everything drawn through the
+    /// [DrawTarget](embedded_graphics_core::draw_target::DrawTarget) by one
+    /// pixel, to reduce image retention of static content
+    ///
+    /// The offset changes every `interval_ms` as counted by
+    /// [Self::tick_pixel_shift], `None` disables shifting. Content shifted
+    /// off the screen is clipped, raw drawing methods are not shifted.
+    pub fn set_pixel_shift(&mut self, interval_ms: Option<u32>) {
+        self.state.pixel_shift = interval_ms.map(|interval_ms| PixelShift {
+            interval_ms,
+            elapsed_ms: 0,
+            step: 0,
+        });
+    }
+
+    /// Advance the pixel shift timer
+    ///
+    /// Returns true when the offset changed, the whole screen has to be
+    /// redrawn then.
+    pub fn tick_pixel_shift(&mut self, elapsed_ms: u32) -> bool {
+        let Some(shift) = &mut self.state.pixel_shift else {
+            return false;
+        };
+        shift.elapsed_ms = shift.elapsed_ms.saturating_add(elapsed_ms);
+        if shift.elapsed_ms < shift.interval_ms {
+            return false;
+        }
+        shift.elapsed_ms = 0;
+        shift.step = (shift.step + 1) % OFFSETS.len() as u8;
+        true
+    }
+
+    /// Current offset of the drawn content
+    pub fn pixel_offset(&self) -> Point {
+        self.state.pixel_shift.map_or(Point::zero(), |shift| {
+            let (x, y) = OFFSETS[shift.step as usize];
+            Point::new(x as i32, y as i32)
+        })
+    }
+}
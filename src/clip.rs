@@ -0,0 +1,41 @@
+//! Clipping of all drawing to a window
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_graphics_core::{geometry::Dimensions, primitives::Rectangle};
+
+use crate::{Ili9342C, PixelFormat};
+
+impl<IFACE, C> Ili9342C<IFACE, C>
+where
+    IFACE: WriteOnlyDataCommand,
+    C: PixelFormat,
+{
+    /// Clip everything drawn through the
+    /// [DrawTarget](embedded_graphics_core::draw_target::DrawTarget) to an
+    /// area until [Self::clear_clip] is called
+    ///
+    /// Pixels outside the area are dropped, including by
+    /// [clear](embedded_graphics_core::draw_target::DrawTarget::clear).
+    /// Raw drawing methods are not clipped.
+    pub fn set_clip(&mut self, area: Rectangle) {
+        self.state.clip = Some(area);
+    }
+
+    /// Remove the clipping area
+    pub fn clear_clip(&mut self) {
+        self.state.clip = None;
+    }
+
+    /// Current clipping area, see [Self::set_clip]
+    pub fn clip(&self) -> Option<Rectangle> {
+        self.state.clip
+    }
+
+    /// Area the draw target may write to
+    pub(crate) fn drawable_area(&self) -> Rectangle {
+        match self.state.clip {
+            Some(clip) => clip.intersection(&self.bounding_box()),
+            None => self.bounding_box(),
+        }
+    }
+}
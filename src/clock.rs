@@ -0,0 +1,116 @@
+//! Timing of transfers and frame rate measurement
+
+use display_interface::WriteOnlyDataCommand;
+
+use crate::{Ili9342C, PixelFormat};
+
+/// Source of monotonic time in microseconds
+///
+/// ```ignore
+/// struct Timer;
+///
+/// impl Monotonic for Timer {
+///     fn now_us(&self) -> u64 {
+///         TIMER.now().duration_since_epoch().to_micros()
+///     }
+/// }
+///
+/// display.set_clock(Some(&Timer));
+/// ```
+pub trait Monotonic {
+    /// Current time in microseconds, wrapping is not supported
+    fn now_us(&self) -> u64;
+}
+
+/// Function returning the current time in microseconds
+impl Monotonic for fn() -> u64 {
+    fn now_us(&self) -> u64 {
+        self()
+    }
+}
+
+/// Timing measurements kept by the driver
+#[derive(Clone, Copy, Default)]
+pub(crate) struct TimingState {
+    clock: Option<&'static dyn Monotonic>,
+    transfer_start: Option<u64>,
+    last_transfer_us: Option<u64>,
+    last_frame: Option<u64>,
+    /// Average frame interval in microseconds
+    frame_interval_us: Option<u64>,
+    full_window: bool,
+}
+
+impl TimingState {
+    pub(crate) fn now(&self) -> Option<u64> {
+        self.clock.map(|clock| clock.now_us())
+    }
+
+    pub(crate) fn window(&mut self, full_screen: bool) {
+        self.full_window = full_screen;
+    }
+
+    pub(crate) fn transfer_start(&mut self) {
+        self.transfer_start = self.now();
+        if self.full_window {
+            self.frame();
+        }
+    }
+
+    pub(crate) fn transfer_end(&mut self) {
+        if let (Some(start), Some(now)) = (self.transfer_start.take(), self.now()) {
+            self.last_transfer_us = Some(now.saturating_sub(start));
+        }
+    }
+
+    fn frame(&mut self) {
+        let Some(now) = self.now() else {
+            return;
+        };
+        if let Some(last) = self.last_frame {
+            let interval = now.saturating_sub(last);
+            // Exponential moving average over about 8 frames
+            self.frame_interval_us = Some(match self.frame_interval_us {
+                Some(average) => (average * 7 + interval) / 8,
+                None => interval,
+            });
+        }
+        self.last_frame = Some(now);
+    }
+}
+
+impl<IFACE, C> Ili9342C<IFACE, C>
+where
+    IFACE: WriteOnlyDataCommand,
+    C: PixelFormat,
+{
+    /// Set the clock used to time transfers, or disable timing with `None`
+    pub fn set_clock(&mut self, clock: Option<&'static dyn Monotonic>) {
+        self.state.timing = TimingState {
+            clock,
+            ..TimingState::default()
+        };
+    }
+
+    /// Duration of the last pixel transfer in microseconds, like a
+    /// [Self::clear_screen] or a framebuffer flush
+    pub fn last_transfer_us(&self) -> Option<u64> {
+        self.state.timing.last_transfer_us
+    }
+
+    /// Mark the start of a frame for [Self::fps]
+    ///
+    /// Writes to a window covering the whole screen are marked automatically,
+    /// call this when frames are drawn in parts.
+    pub fn mark_frame(&mut self) {
+        self.state.timing.frame();
+    }
+
+    /// Rolling average of the frame rate, in frames per second
+    pub fn fps(&self) -> Option<f32> {
+        match self.state.timing.frame_interval_us? {
+            0 => None,
+            interval => Some(1_000_000.0 / interval as f32),
+        }
+    }
+}
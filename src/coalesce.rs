@@ -0,0 +1,96 @@
+//! Merging single pixels into runs
+
+use embedded_graphics_core::{
+    draw_target::DrawTarget,
+    geometry::{Dimensions, Point, Size},
+    primitives::Rectangle,
+    Pixel,
+};
+
+/// [DrawTarget] wrapper merging horizontally adjacent pixels into runs of up
+/// to `N` pixels, each written with one window
+///
+/// Primitives without a fast path, like lines, circle outlines and text,
+/// are drawn pixel by pixel, which costs a window and a memory write per
+/// pixel on the display. The run is flushed at the end of every draw call.
+///
+/// ```ignore
+/// let mut target = RunCoalescer::<_, 32>::new(&mut display);
+/// Text::new("Hello", Point::new(10, 20), style).draw(&mut target)?;
+/// ```
+pub struct RunCoalescer<'a, D: DrawTarget, const N: usize = 64> {
+    target: &'a mut D,
+    run: Option<[D::Color; N]>,
+    start: Point,
+    len: usize,
+}
+
+impl<'a, D: DrawTarget, const N: usize> RunCoalescer<'a, D, N> {
+    /// Wrap a draw target
+    pub fn new(target: &'a mut D) -> Self {
+        const { assert!(N > 0, "runs need at least one pixel") };
+        Self {
+            target,
+            run: None,
+            start: Point::zero(),
+            len: 0,
+        }
+    }
+
+    /// Write the buffered run
+    fn flush_run(&mut self) -> Result<(), D::Error> {
+        let len = core::mem::take(&mut self.len);
+        match &self.run {
+            Some(run) if len > 0 => {
+                let area = Rectangle::new(self.start, Size::new(len as u32, 1));
+                self.target
+                    .fill_contiguous(&area, run[..len].iter().copied())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<D: DrawTarget, const N: usize> Dimensions for RunCoalescer<'_, D, N> {
+    fn bounding_box(&self) -> Rectangle {
+        self.target.bounding_box()
+    }
+}
+
+impl<D: DrawTarget, const N: usize> DrawTarget for RunCoalescer<'_, D, N> {
+    type Color = D::Color;
+
+    type Error = D::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            let next = self.start + Point::new(self.len as i32, 0);
+            if self.len == 0 || self.len == N || point != next {
+                self.flush_run()?;
+                self.start = point;
+            }
+            let run = self.run.get_or_insert([color; N]);
+            run[self.len] = color;
+            self.len += 1;
+        }
+        self.flush_run()
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        self.target.fill_contiguous(area, colors)
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        self.target.fill_solid(area, color)
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.target.clear(color)
+    }
+}
@@ -0,0 +1,332 @@
+//! Color conversion adapters
+
+use embedded_graphics_core::{
+    draw_target::DrawTarget,
+    geometry::{Dimensions, Point},
+    pixelcolor::{Gray8, GrayColor, Rgb888, RgbColor},
+    primitives::{PointsIter, Rectangle},
+    Pixel,
+};
+
+use crate::PixelFormat;
+
+/// Standard colors of the 16 color terminal palette
+#[cfg(any(feature = "console", feature = "ratatui"))]
+pub(crate) const ANSI: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// 4x4 Bayer threshold matrix
+const BAYER_4X4: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// Offset a channel by the ordered dithering threshold for a target channel
+/// with `max + 1` levels
+fn dither_channel(value: u8, max: u8, threshold: u8) -> u8 {
+    let step = 256 / (max as i16 + 1);
+    let offset = (threshold as i16 * step) / 16 - step / 2;
+    (value as i16 + offset).clamp(0, 255) as u8
+}
+
+/// Apply 4x4 ordered dithering to a color before it is reduced to the
+/// channel depths of `C`
+pub(crate) fn dither<C: RgbColor>(color: Rgb888, point: Point) -> Rgb888 {
+    let threshold = BAYER_4X4[(point.y & 3) as usize][(point.x & 3) as usize];
+    Rgb888::new(
+        dither_channel(color.r(), C::MAX_R, threshold),
+        dither_channel(color.g(), C::MAX_G, threshold),
+        dither_channel(color.b(), C::MAX_B, threshold),
+    )
+}
+
+/// [DrawTarget] adapter accepting [Rgb888] colors
+///
+/// Colors are converted to the pixel format of the wrapped target on the fly,
+/// so code working in 24-bit color can draw to the display directly.
+///
+/// Optionally the conversion applies 4x4 ordered dithering based on the pixel
+/// coordinates, hiding the banding of gradients without any extra memory.
+pub struct Rgb888Adapter<'a, D> {
+    target: &'a mut D,
+    dithering: bool,
+}
+
+impl<'a, D> Rgb888Adapter<'a, D> {
+    /// Wrap a draw target
+    pub fn new(target: &'a mut D) -> Self {
+        Self {
+            target,
+            dithering: false,
+        }
+    }
+
+    /// Enable or disable ordered dithering
+    pub fn with_dithering(mut self, dithering: bool) -> Self {
+        self.dithering = dithering;
+        self
+    }
+
+    /// Enable or disable ordered dithering
+    pub fn set_dithering(&mut self, dithering: bool) {
+        self.dithering = dithering;
+    }
+}
+
+impl<D: Dimensions> Dimensions for Rgb888Adapter<'_, D> {
+    fn bounding_box(&self) -> Rectangle {
+        self.target.bounding_box()
+    }
+}
+
+impl<D> DrawTarget for Rgb888Adapter<'_, D>
+where
+    D: DrawTarget,
+    D::Color: PixelFormat + RgbColor,
+{
+    type Color = Rgb888;
+
+    type Error = D::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let dithering = self.dithering;
+        self.target
+            .draw_iter(pixels.into_iter().map(|Pixel(point, color)| {
+                let color = if dithering {
+                    dither::<D::Color>(color, point)
+                } else {
+                    color
+                };
+                Pixel(point, D::Color::from_rgb888(color))
+            }))
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        if self.dithering {
+            self.target.fill_contiguous(
+                area,
+                area.points()
+                    .zip(colors)
+                    .map(|(point, color)| D::Color::from_rgb888(dither::<D::Color>(color, point))),
+            )
+        } else {
+            self.target
+                .fill_contiguous(area, colors.into_iter().map(D::Color::from_rgb888))
+        }
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        if self.dithering {
+            // A dithered solid color is a pattern
+            self.fill_contiguous(area, core::iter::repeat(color))
+        } else {
+            self.target.fill_solid(area, D::Color::from_rgb888(color))
+        }
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        if self.dithering {
+            let area = self.target.bounding_box();
+            self.fill_contiguous(&area, core::iter::repeat(color))
+        } else {
+            self.target.clear(D::Color::from_rgb888(color))
+        }
+    }
+}
+
+/// [DrawTarget] adapter accepting [Gray8] colors
+///
+/// The luminance is expanded to the pixel format of the wrapped target on the
+/// fly, so monochrome assets can be drawn to the display without conversion
+/// loops.
+pub struct Gray8Adapter<'a, D> {
+    target: &'a mut D,
+}
+
+impl<'a, D> Gray8Adapter<'a, D> {
+    /// Wrap a draw target
+    pub fn new(target: &'a mut D) -> Self {
+        Self { target }
+    }
+}
+
+/// Expand a luminance value to a color of format `C`
+fn from_gray8<C: PixelFormat>(color: Gray8) -> C {
+    let luma = color.luma();
+    C::from_rgb888(Rgb888::new(luma, luma, luma))
+}
+
+impl<D: Dimensions> Dimensions for Gray8Adapter<'_, D> {
+    fn bounding_box(&self) -> Rectangle {
+        self.target.bounding_box()
+    }
+}
+
+impl<D> DrawTarget for Gray8Adapter<'_, D>
+where
+    D: DrawTarget,
+    D::Color: PixelFormat,
+{
+    type Color = Gray8;
+
+    type Error = D::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        self.target.draw_iter(
+            pixels
+                .into_iter()
+                .map(|Pixel(point, color)| Pixel(point, from_gray8(color))),
+        )
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        self.target
+            .fill_contiguous(area, colors.into_iter().map(from_gray8))
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        self.target.fill_solid(area, from_gray8(color))
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.target.clear(from_gray8(color))
+    }
+}
+
+/// Channel scale of [NightModeAdapter] by default, a warm white
+const NIGHT_SCALE: [u8; 3] = [255, 170, 100];
+
+/// [DrawTarget] adapter reducing blue light
+///
+/// While enabled, every channel is scaled on the way to the wrapped target,
+/// so the same UI can be drawn for day and night mode.
+pub struct NightModeAdapter<'a, D> {
+    target: &'a mut D,
+    enabled: bool,
+    scale: [u8; 3],
+}
+
+impl<'a, D> NightModeAdapter<'a, D> {
+    /// Wrap a draw target, the transform is enabled
+    pub fn new(target: &'a mut D) -> Self {
+        Self {
+            target,
+            enabled: true,
+            scale: NIGHT_SCALE,
+        }
+    }
+
+    /// Scale of the red, green and blue channels, 255 keeps a channel as is
+    pub fn with_scale(mut self, red: u8, green: u8, blue: u8) -> Self {
+        self.scale = [red, green, blue];
+        self
+    }
+
+    /// Enable or disable the transform
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Whether the transform is enabled
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+}
+
+/// Scale the channels of a color
+fn scale_channels<C: PixelFormat + RgbColor>(color: C, scale: [u8; 3]) -> C {
+    let channel = |value: u8, max: u8, scale: u8| (value as u32 * scale as u32 / max as u32) as u8;
+    C::from_rgb888(Rgb888::new(
+        channel(color.r(), C::MAX_R, scale[0]),
+        channel(color.g(), C::MAX_G, scale[1]),
+        channel(color.b(), C::MAX_B, scale[2]),
+    ))
+}
+
+impl<D: Dimensions> Dimensions for NightModeAdapter<'_, D> {
+    fn bounding_box(&self) -> Rectangle {
+        self.target.bounding_box()
+    }
+}
+
+impl<D> DrawTarget for NightModeAdapter<'_, D>
+where
+    D: DrawTarget,
+    D::Color: PixelFormat + RgbColor,
+{
+    type Color = D::Color;
+
+    type Error = D::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        if !self.enabled {
+            return self.target.draw_iter(pixels);
+        }
+        let scale = self.scale;
+        self.target.draw_iter(
+            pixels
+                .into_iter()
+                .map(|Pixel(point, color)| Pixel(point, scale_channels(color, scale))),
+        )
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        if !self.enabled {
+            return self.target.fill_contiguous(area, colors);
+        }
+        let scale = self.scale;
+        self.target.fill_contiguous(
+            area,
+            colors.into_iter().map(|color| scale_channels(color, scale)),
+        )
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let color = if self.enabled {
+            scale_channels(color, self.scale)
+        } else {
+            color
+        };
+        self.target.fill_solid(area, color)
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        let color = if self.enabled {
+            scale_channels(color, self.scale)
+        } else {
+            color
+        };
+        self.target.clear(color)
+    }
+}
@@ -0,0 +1,65 @@
+//! Color Set lookup table of the controller
+
+use display_interface::WriteOnlyDataCommand;
+
+use crate::{Command, Ili9342C, PixelFormat, Result};
+
+/// Color Set (0x2D) tables expanding the channels of the 16 and 12 bit pixel
+/// formats to the 6 bit levels of the panel
+///
+/// The 12 bit format indexes the first 16 entries of each table.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ColorSet {
+    /// Red levels
+    pub red: [u8; 32],
+    /// Green levels
+    pub green: [u8; 64],
+    /// Blue levels
+    pub blue: [u8; 32],
+}
+
+/// Table with `levels` evenly spaced entries, the others at the maximum
+const fn linear<const N: usize>(levels: usize) -> [u8; N] {
+    let mut table = [63; N];
+    let mut i = 0;
+    while i < levels {
+        table[i] = ((i * 63 + (levels - 1) / 2) / (levels - 1)) as u8;
+        i += 1;
+    }
+    table
+}
+
+impl ColorSet {
+    /// Linear tables of the 16 bit format
+    pub const LINEAR_16: Self = Self {
+        red: linear(32),
+        green: linear(64),
+        blue: linear(32),
+    };
+
+    /// Linear tables of the 12 bit format
+    pub const LINEAR_12: Self = Self {
+        red: linear(16),
+        green: linear(16),
+        blue: linear(16),
+    };
+}
+
+impl<IFACE, C> Ili9342C<IFACE, C>
+where
+    IFACE: WriteOnlyDataCommand,
+    C: PixelFormat,
+{
+    /// Write the Color Set lookup table
+    pub fn set_color_set(&mut self, table: &ColorSet) -> Result {
+        let mut args = [0; 128];
+        args[..32].copy_from_slice(&table.red);
+        args[32..96].copy_from_slice(&table.green);
+        args[96..].copy_from_slice(&table.blue);
+        for level in &mut args {
+            *level &= 0x3f;
+        }
+        self.command(Command::ColorSet, &args)
+    }
+}
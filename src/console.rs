@@ -0,0 +1,403 @@
+//! Text console understanding ANSI escape codes, see the `console` feature
+
+use core::fmt;
+
+use display_interface::{DisplayError, WriteOnlyDataCommand};
+use embedded_graphics::{
+    mono_font::{MonoFont, MonoTextStyleBuilder},
+    text::{Baseline, Text},
+    Drawable,
+};
+use embedded_graphics_core::{
+    draw_target::DrawTarget,
+    geometry::{Point, Size},
+    pixelcolor::Rgb888,
+    primitives::Rectangle,
+};
+
+use crate::{color::ANSI, Ili9342C, PixelFormat, Result, Scroller};
+
+/// Most parameters of a control sequence kept, later ones are ignored
+const MAX_PARAMS: usize = 4;
+/// Default foreground palette index, light gray
+const DEFAULT_FG: u8 = 7;
+/// Default background palette index, black
+const DEFAULT_BG: u8 = 0;
+
+/// Position in an escape sequence
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum State {
+    Ground,
+    Escape,
+    Csi,
+}
+
+/// Text console with a subset of the ANSI/VT100 escape codes
+///
+/// Understands the 16 color SGR codes with bold, reverse and reset, cursor
+/// movement (`A`, `B`, `C`, `D`, `H`, `f`), clearing the screen (`J`) and the
+/// line (`K`). Other sequences are dropped. Line feeds also return the
+/// cursor to the first column, so plain log output needs no `\r`.
+///
+/// New lines at the bottom scroll the screen with the hardware scroll, only
+/// the new line is cleared. The display has to be in a landscape orientation,
+/// see [Ili9342C::configure_vertical_scroll].
+///
+/// ```ignore
+/// let mut console = Console::new(&mut display, &FONT_6X10)?;
+/// writeln!(console.writer(&mut display), "\x1b[32mok\x1b[0m booted")?;
+/// ```
+pub struct Console<C> {
+    font: &'static MonoFont<'static>,
+    columns: u16,
+    rows: u16,
+    scroller: Scroller,
+    /// Screen row stored in the first text row of the memory
+    top_row: u16,
+    column: u16,
+    row: u16,
+    fg: u8,
+    bg: u8,
+    bold: bool,
+    reverse: bool,
+    state: State,
+    params: [u16; MAX_PARAMS],
+    param_count: usize,
+    _color: core::marker::PhantomData<C>,
+}
+
+impl<C: PixelFormat> Console<C> {
+    /// Set up the scroll area for the rows of a font and clear the screen
+    pub fn new<IFACE>(
+        display: &mut Ili9342C<IFACE, C>,
+        font: &'static MonoFont<'static>,
+    ) -> Result<Self>
+    where
+        IFACE: WriteOnlyDataCommand,
+    {
+        let Size { width, height } = font.character_size;
+        let columns = (display.width() as u32 / width) as u16;
+        let rows = (display.height() as u32 / height) as u16;
+        if columns == 0 || rows == 0 {
+            return Err(DisplayError::OutOfBoundsError);
+        }
+        // Lines below the last row stay out of the scroll area
+        let unused = display.height() as u16 - rows * height as u16;
+        let mut scroller = display.configure_vertical_scroll(0, unused)?;
+        display.set_scroll_position(&mut scroller, 0)?;
+        let mut console = Self {
+            font,
+            columns,
+            rows,
+            scroller,
+            top_row: 0,
+            column: 0,
+            row: 0,
+            fg: DEFAULT_FG,
+            bg: DEFAULT_BG,
+            bold: false,
+            reverse: false,
+            state: State::Ground,
+            params: [0; MAX_PARAMS],
+            param_count: 0,
+            _color: core::marker::PhantomData,
+        };
+        console.clear_rows(display, 0, rows)?;
+        Ok(console)
+    }
+
+    /// Number of text columns and rows
+    pub fn size(&self) -> (u16, u16) {
+        (self.columns, self.rows)
+    }
+
+    /// Column and row of the cursor
+    pub fn cursor(&self) -> (u16, u16) {
+        (self.column, self.row)
+    }
+
+    /// [fmt::Write] adapter, for `write!` and log formatters
+    pub fn writer<'a, IFACE>(
+        &'a mut self,
+        display: &'a mut Ili9342C<IFACE, C>,
+    ) -> ConsoleWriter<'a, IFACE, C> {
+        ConsoleWriter {
+            console: self,
+            display,
+        }
+    }
+
+    /// Write text with escape codes
+    pub fn write_str<IFACE>(&mut self, display: &mut Ili9342C<IFACE, C>, text: &str) -> Result
+    where
+        IFACE: WriteOnlyDataCommand,
+    {
+        text.chars().try_for_each(|c| self.write_char(display, c))
+    }
+
+    /// Write one character, which can be part of an escape code
+    pub fn write_char<IFACE>(&mut self, display: &mut Ili9342C<IFACE, C>, c: char) -> Result
+    where
+        IFACE: WriteOnlyDataCommand,
+    {
+        match self.state {
+            State::Ground => self.ground(display, c),
+            State::Escape => {
+                if c == '[' {
+                    self.params = [0; MAX_PARAMS];
+                    self.param_count = 0;
+                    self.state = State::Csi;
+                } else {
+                    self.state = State::Ground;
+                }
+                Ok(())
+            }
+            State::Csi => match c {
+                '0'..='9' => {
+                    let index = self.param_count.max(1) - 1;
+                    self.param_count = self.param_count.max(1);
+                    if let Some(param) = self.params.get_mut(index) {
+                        *param = param
+                            .saturating_mul(10)
+                            .saturating_add(c as u16 - '0' as u16);
+                    }
+                    Ok(())
+                }
+                ';' => {
+                    self.param_count = self.param_count.max(1) + 1;
+                    Ok(())
+                }
+                // Private mode prefixes and intermediates of sequences
+                // that are dropped anyway
+                ' '..='?' => Ok(()),
+                _ => {
+                    self.state = State::Ground;
+                    self.control(display, c)
+                }
+            },
+        }
+    }
+
+    /// Handle a character outside of escape codes
+    fn ground<IFACE>(&mut self, display: &mut Ili9342C<IFACE, C>, c: char) -> Result
+    where
+        IFACE: WriteOnlyDataCommand,
+    {
+        match c {
+            '\x1b' => self.state = State::Escape,
+            '\n' => {
+                self.column = 0;
+                return self.line_feed(display);
+            }
+            '\r' => self.column = 0,
+            '\x08' => self.column = self.column.saturating_sub(1),
+            '\t' => self.column = ((self.column / 8 + 1) * 8).min(self.columns - 1),
+            c if c.is_control() => {}
+            c => {
+                if self.column >= self.columns {
+                    self.column = 0;
+                    self.line_feed(display)?;
+                }
+                self.draw_char(display, c)?;
+                self.column += 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// Run the final character of a control sequence
+    fn control<IFACE>(&mut self, display: &mut Ili9342C<IFACE, C>, c: char) -> Result
+    where
+        IFACE: WriteOnlyDataCommand,
+    {
+        let params = self.params;
+        let count = self.param_count;
+        // Missing or zero counts move by one
+        let n = params[0].max(1);
+        match c {
+            'm' => self.select_graphic_rendition(&params[..count.clamp(1, MAX_PARAMS)]),
+            'A' => self.row = self.row.saturating_sub(n),
+            'B' => self.row = self.row.saturating_add(n).min(self.rows - 1),
+            'C' => self.column = self.column.saturating_add(n).min(self.columns - 1),
+            'D' => self.column = self.column.saturating_sub(n),
+            'H' | 'f' => {
+                self.row = (params[0].max(1) - 1).min(self.rows - 1);
+                self.column = (params[1].max(1) - 1).min(self.columns - 1);
+            }
+            'J' => match params[0] {
+                0 => {
+                    self.clear_line_from(display, self.column)?;
+                    self.clear_rows(display, self.row + 1, self.rows)?;
+                }
+                1 => {
+                    self.clear_rows(display, 0, self.row)?;
+                    self.clear_columns(display, 0, self.column + 1)?;
+                }
+                _ => {
+                    self.clear_rows(display, 0, self.rows)?;
+                    self.column = 0;
+                    self.row = 0;
+                }
+            },
+            'K' => match params[0] {
+                0 => self.clear_line_from(display, self.column)?,
+                1 => self.clear_columns(display, 0, self.column + 1)?,
+                _ => self.clear_line_from(display, 0)?,
+            },
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Apply SGR parameters
+    fn select_graphic_rendition(&mut self, params: &[u16]) {
+        for &param in params {
+            match param {
+                0 => {
+                    self.fg = DEFAULT_FG;
+                    self.bg = DEFAULT_BG;
+                    self.bold = false;
+                    self.reverse = false;
+                }
+                1 => self.bold = true,
+                7 => self.reverse = true,
+                22 => self.bold = false,
+                27 => self.reverse = false,
+                30..=37 => self.fg = (param - 30) as u8,
+                39 => self.fg = DEFAULT_FG,
+                40..=47 => self.bg = (param - 40) as u8,
+                49 => self.bg = DEFAULT_BG,
+                90..=97 => self.fg = (param - 90) as u8 + 8,
+                100..=107 => self.bg = (param - 100) as u8 + 8,
+                _ => {}
+            }
+        }
+    }
+
+    /// Foreground and background of the current attributes
+    fn colors(&self) -> (C, C) {
+        // Bold brightens the 8 basic colors
+        let fg = if self.bold && self.fg < 8 {
+            self.fg + 8
+        } else {
+            self.fg
+        };
+        let color = |index: u8| {
+            let (r, g, b) = ANSI[index as usize];
+            C::from_rgb888(Rgb888::new(r, g, b))
+        };
+        let (fg, bg) = (color(fg), color(self.bg));
+        if self.reverse {
+            (bg, fg)
+        } else {
+            (fg, bg)
+        }
+    }
+
+    /// Move the cursor down, scrolling at the bottom
+    fn line_feed<IFACE>(&mut self, display: &mut Ili9342C<IFACE, C>) -> Result
+    where
+        IFACE: WriteOnlyDataCommand,
+    {
+        if self.row + 1 < self.rows {
+            self.row += 1;
+            return Ok(());
+        }
+        self.top_row = (self.top_row + 1) % self.rows;
+        let line = self.top_row * self.font.character_size.height as u16;
+        display.set_scroll_position(&mut self.scroller, line)?;
+        self.clear_line_from(display, 0)
+    }
+
+    /// Top left pixel of a cell in memory
+    fn origin(&self, column: u16, row: u16) -> Point {
+        let Size { width, height } = self.font.character_size;
+        let memory_row = (self.top_row + row) % self.rows;
+        Point::new(
+            (column as u32 * width) as i32,
+            (memory_row as u32 * height) as i32,
+        )
+    }
+
+    fn draw_char<IFACE>(&mut self, display: &mut Ili9342C<IFACE, C>, c: char) -> Result
+    where
+        IFACE: WriteOnlyDataCommand,
+    {
+        let (fg, bg) = self.colors();
+        let style = MonoTextStyleBuilder::new()
+            .font(self.font)
+            .text_color(fg)
+            .background_color(bg)
+            .build();
+        let mut text = [0; 4];
+        let origin = self.origin(self.column, self.row);
+        Text::with_baseline(c.encode_utf8(&mut text), origin, style, Baseline::Top)
+            .draw(display)
+            .map(drop)
+    }
+
+    /// Clear the cursor row from a column to its end
+    fn clear_line_from<IFACE>(&mut self, display: &mut Ili9342C<IFACE, C>, column: u16) -> Result
+    where
+        IFACE: WriteOnlyDataCommand,
+    {
+        self.clear_columns(display, column, self.columns)
+    }
+
+    /// Clear columns `start..end` of the cursor row
+    fn clear_columns<IFACE>(
+        &mut self,
+        display: &mut Ili9342C<IFACE, C>,
+        start: u16,
+        end: u16,
+    ) -> Result
+    where
+        IFACE: WriteOnlyDataCommand,
+    {
+        let end = end.min(self.columns);
+        if start >= end {
+            return Ok(());
+        }
+        let Size { width, height } = self.font.character_size;
+        let size = Size::new((end - start) as u32 * width, height);
+        let area = Rectangle::new(self.origin(start, self.row), size);
+        display.fill_solid(&area, self.colors().1)
+    }
+
+    /// Clear rows `start..end` of the screen
+    fn clear_rows<IFACE>(
+        &mut self,
+        display: &mut Ili9342C<IFACE, C>,
+        start: u16,
+        end: u16,
+    ) -> Result
+    where
+        IFACE: WriteOnlyDataCommand,
+    {
+        let row = self.row;
+        for line in start..end {
+            self.row = line;
+            self.clear_line_from(display, 0)?;
+        }
+        self.row = row;
+        Ok(())
+    }
+}
+
+/// [fmt::Write] adapter of a [Console], created by [Console::writer]
+pub struct ConsoleWriter<'a, IFACE, C> {
+    console: &'a mut Console<C>,
+    display: &'a mut Ili9342C<IFACE, C>,
+}
+
+impl<IFACE, C> fmt::Write for ConsoleWriter<'_, IFACE, C>
+where
+    IFACE: WriteOnlyDataCommand,
+    C: PixelFormat,
+{
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        self.console
+            .write_str(self.display, text)
+            .map_err(|_| fmt::Error)
+    }
+}
@@ -0,0 +1,131 @@
+//! Per-channel color correction
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_graphics_core::pixelcolor::{Rgb888, RgbColor};
+
+use crate::{DigitalGammaTables, Ili9342C, PixelFormat, Result};
+
+/// Correction tables of the red, green and blue channels
+///
+/// Each table maps 32 evenly spaced input levels to 8-bit output levels,
+/// levels in between are interpolated. Used to calibrate panels with a
+/// visible tint, see [Ili9342C::set_color_lut].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ColorLut {
+    /// Red channel table
+    pub red: [u8; 32],
+    /// Green channel table
+    pub green: [u8; 32],
+    /// Blue channel table
+    pub blue: [u8; 32],
+}
+
+/// Output level of entry `i` of an identity table
+const fn identity_level(i: usize) -> u8 {
+    ((i * 255 + 15) / 31) as u8
+}
+
+const fn identity_table() -> [u8; 32] {
+    let mut table = [0; 32];
+    let mut i = 0;
+    while i < 32 {
+        table[i] = identity_level(i);
+        i += 1;
+    }
+    table
+}
+
+impl ColorLut {
+    /// Tables leaving all colors unchanged
+    pub const IDENTITY: Self = Self {
+        red: identity_table(),
+        green: identity_table(),
+        blue: identity_table(),
+    };
+
+    /// Correct a color
+    pub fn apply(&self, color: Rgb888) -> Rgb888 {
+        Rgb888::new(
+            lookup(&self.red, color.r()),
+            lookup(&self.green, color.g()),
+            lookup(&self.blue, color.b()),
+        )
+    }
+
+    /// Digital gamma tables approximating the red and blue tables
+    ///
+    /// The controller can only lower the red and blue channels, by up to 15
+    /// of its 64 gray levels. Green and levels above the identity are left
+    /// unchanged, use [Ili9342C::set_color_lut] for full corrections.
+    pub fn digital_gamma(&self) -> DigitalGammaTables {
+        let offset = |table: &[u8; 32], level: usize| -> u8 {
+            let input = ((level * 255 + 31) / 63) as u8;
+            let reduction = input.saturating_sub(lookup(table, input)) as u32;
+            ((reduction * 63 + 127) / 255).min(0x0f) as u8
+        };
+        let mut control2 = [0; 64];
+        for (level, entry) in control2.iter_mut().enumerate() {
+            *entry = offset(&self.red, level) << 4 | offset(&self.blue, level);
+        }
+        DigitalGammaTables {
+            control1: [0; 16],
+            control2,
+        }
+    }
+}
+
+impl Default for ColorLut {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+/// Interpolate a table at an 8-bit input level
+fn lookup(table: &[u8; 32], value: u8) -> u8 {
+    let position = value as u32 * 31;
+    let index = (position / 255) as usize;
+    let fraction = position % 255;
+    let lower = table[index] as u32;
+    let upper = table[(index + 1).min(31)] as u32;
+    ((lower * (255 - fraction) + upper * fraction + 127) / 255) as u8
+}
+
+/// Apply an optional correction to a color
+pub(crate) fn correct<C: PixelFormat>(lut: &Option<ColorLut>, color: C) -> C {
+    match lut {
+        Some(lut) => C::from_rgb888(lut.apply(color.to_rgb888())),
+        None => color,
+    }
+}
+
+impl<IFACE, C> Ili9342C<IFACE, C>
+where
+    IFACE: WriteOnlyDataCommand,
+    C: PixelFormat,
+{
+    /// Correct colors drawn through the
+    /// [DrawTarget](embedded_graphics_core::draw_target::DrawTarget) in
+    /// software, `None` disables the correction
+    ///
+    /// Raw drawing methods are not corrected.
+    pub fn set_color_lut(&mut self, lut: Option<ColorLut>) {
+        self.state.color_lut = lut;
+    }
+
+    /// Software correction tables, see [Self::set_color_lut]
+    pub fn color_lut(&self) -> Option<&ColorLut> {
+        self.state.color_lut.as_ref()
+    }
+
+    /// Load the red and blue tables into the digital gamma of the controller
+    /// and enable 3-gamma control
+    ///
+    /// Costs no CPU time per pixel and also applies to raw drawing, but is
+    /// limited to what [ColorLut::digital_gamma] can express.
+    pub fn load_color_lut(&mut self, lut: &ColorLut) -> Result {
+        self.set_digital_gamma(&lut.digital_gamma())?;
+        self.set_3gamma(true)
+    }
+}
@@ -0,0 +1,44 @@
+//! CRC32 of frame contents for regression tests
+
+/// Nibble table of the reflected IEEE polynomial 0xEDB88320
+const TABLE: [u32; 16] = [
+    0x00000000, 0x1db71064, 0x3b6e20c8, 0x26d930ac, 0x76dc4190, 0x6b6b51f4, 0x4db26158, 0x5005713c,
+    0xedb88320, 0xf00f9344, 0xd6d6a3e8, 0xcb61b38c, 0x9b64c2b0, 0x86d3d2d4, 0xa00ae278, 0xbdbdf21c,
+];
+
+/// CRC32 (IEEE, as used by zlib and PNG) computed incrementally
+///
+/// The frame CRCs of the framebuffers are computed over the big endian
+/// RGB565 bytes of all pixels, the same bytes a flush sends to the display.
+#[derive(Clone, Copy, Debug)]
+pub struct Crc32 {
+    state: u32,
+}
+
+impl Crc32 {
+    /// Start a CRC
+    pub const fn new() -> Self {
+        Self { state: !0 }
+    }
+
+    /// Add bytes
+    pub fn update(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            let mut crc = self.state ^ byte as u32;
+            crc = (crc >> 4) ^ TABLE[(crc & 0x0f) as usize];
+            crc = (crc >> 4) ^ TABLE[(crc & 0x0f) as usize];
+            self.state = crc;
+        }
+    }
+
+    /// CRC of the bytes added so far
+    pub fn finish(&self) -> u32 {
+        !self.state
+    }
+}
+
+impl Default for Crc32 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,118 @@
+//! Panel state tracked for [Debug](core::fmt::Debug) output and
+//! [Ili9342C::reinit]
+
+use core::fmt;
+
+use crate::{Command, Ili9342C};
+
+/// Panel registers tracked from the commands sent
+#[derive(Clone, Copy)]
+pub(crate) struct PanelState {
+    madctl: u8,
+    pub(crate) inverted: bool,
+    window: [u16; 4],
+    pub(crate) sleeping: bool,
+    pub(crate) display_on: bool,
+    pub(crate) idle: bool,
+    pub(crate) frame_rate: Option<[u8; 2]>,
+    pub(crate) brightness: Option<u8>,
+    pub(crate) ctrl_display: Option<u8>,
+    pub(crate) cabc: Option<u8>,
+    pub(crate) tearing: Option<u8>,
+    pub(crate) scroll_define: Option<[u8; 6]>,
+    pub(crate) scroll_start: Option<[u8; 2]>,
+}
+
+impl Default for PanelState {
+    /// State after reset
+    fn default() -> Self {
+        Self {
+            madctl: 0,
+            inverted: false,
+            window: [0, 0, 0, 0],
+            sleeping: true,
+            display_on: false,
+            idle: false,
+            frame_rate: None,
+            brightness: None,
+            ctrl_display: None,
+            cabc: None,
+            tearing: None,
+            scroll_define: None,
+            scroll_start: None,
+        }
+    }
+}
+
+impl PanelState {
+    /// Update the state for a command being sent
+    pub(crate) fn command(&mut self, cmd: Command, args: &[u8]) {
+        let word = |i: usize| u16::from_be_bytes([args[i], args[i + 1]]);
+        match cmd {
+            Command::SoftwareReset => *self = Self::default(),
+            Command::SleepModeOn => self.sleeping = true,
+            Command::SleepModeOff => self.sleeping = false,
+            Command::DisplayOn => self.display_on = true,
+            Command::DisplayOff => self.display_on = false,
+            Command::InvertOn => self.inverted = true,
+            Command::InvertOff => self.inverted = false,
+            Command::MemoryAccessControl if !args.is_empty() => self.madctl = args[0],
+            Command::ColumnAddressSet if args.len() >= 4 => {
+                self.window[0] = word(0);
+                self.window[2] = word(2);
+            }
+            Command::PageAddressSet if args.len() >= 4 => {
+                self.window[1] = word(0);
+                self.window[3] = word(2);
+            }
+            Command::IdleModeOn => self.idle = true,
+            Command::IdleModeOff => self.idle = false,
+            Command::FrameControl => self.frame_rate = args.try_into().ok(),
+            Command::SetBrightness if !args.is_empty() => self.brightness = Some(args[0]),
+            Command::WriteCtrlDisplay if !args.is_empty() => self.ctrl_display = Some(args[0]),
+            Command::ContentAdaptiveBrightness if !args.is_empty() => self.cabc = Some(args[0]),
+            Command::TearingEffectOn if !args.is_empty() => self.tearing = Some(args[0]),
+            Command::TearingEffectOff => self.tearing = None,
+            Command::VerticalScrollDefine => self.scroll_define = args.try_into().ok(),
+            Command::VerticalScrollAddr => self.scroll_start = args.try_into().ok(),
+            _ => {}
+        }
+    }
+
+    /// Memory access control value sent last
+    pub(crate) fn madctl(&self) -> u8 {
+        self.madctl
+    }
+
+    /// Name of the [Orientation](crate::Orientation) or
+    /// [Rotation](crate::Rotation) matching the memory access control value
+    fn orientation(&self) -> &'static str {
+        match self.madctl & 0xe0 {
+            0x00 => "Landscape",
+            0x20 => "Portrait",
+            0x80 => "LandscapeFlipped",
+            0xe0 => "PortraitFlipped",
+            0x60 => "Deg90",
+            0xc0 => "Deg180",
+            0xa0 => "Deg270",
+            _ => "Custom",
+        }
+    }
+}
+
+impl<IFACE, C> fmt::Debug for Ili9342C<IFACE, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let panel = &self.state.panel;
+        let [x0, y0, x1, y1] = panel.window;
+        f.debug_struct("Ili9342C")
+            .field("width", &self.state.width)
+            .field("height", &self.state.height)
+            .field("orientation", &format_args!("{}", panel.orientation()))
+            .field("madctl", &format_args!("{:#04x}", panel.madctl))
+            .field("inverted", &panel.inverted)
+            .field("window", &format_args!("({x0}, {y0})..=({x1}, {y1})"))
+            .field("sleeping", &panel.sleeping)
+            .field("display_on", &panel.display_on)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,107 @@
+//! Pixel buffers for DMA transfers, see the `dma` feature
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_dma::ReadBuffer;
+use embedded_graphics_core::pixelcolor::{raw::RawU16, Rgb565};
+use embedded_graphics_core::prelude::RawData;
+
+use crate::{Ili9342C, Result};
+
+/// RGB565 pixel buffer stored in the byte order sent to the display
+///
+/// The pixels are kept big endian in memory, so the buffer can be handed to
+/// a DMA transfer as bytes without swapping or copying. It implements
+/// [ReadBuffer] with `u8` words.
+///
+/// ```ignore
+/// static mut PIXELS: [u16; 320 * 20] = [0; 320 * 20];
+/// let mut buffer = DmaPixelBuffer::new(unsafe { &mut PIXELS });
+/// buffer.fill(Rgb565::BLUE);
+/// display.start_memory_write(0, 0, 319, 19)?;
+/// // Start the DMA transfer of `buffer` on the SPI bus, with DC high
+/// ```
+pub struct DmaPixelBuffer {
+    pixels: &'static mut [u16],
+}
+
+impl DmaPixelBuffer {
+    /// Use a static buffer for pixels
+    pub fn new(pixels: &'static mut [u16]) -> Self {
+        Self { pixels }
+    }
+
+    /// Number of pixels in the buffer
+    pub fn len(&self) -> usize {
+        self.pixels.len()
+    }
+
+    /// Whether the buffer holds no pixels
+    pub fn is_empty(&self) -> bool {
+        self.pixels.is_empty()
+    }
+
+    /// Set a pixel, indices outside the buffer are ignored
+    pub fn set(&mut self, index: usize, color: Rgb565) {
+        if let Some(pixel) = self.pixels.get_mut(index) {
+            *pixel = RawU16::from(color).into_inner().to_be();
+        }
+    }
+
+    /// Get a pixel
+    pub fn get(&self, index: usize) -> Option<Rgb565> {
+        let pixel = u16::from_be(*self.pixels.get(index)?);
+        Some(RawU16::new(pixel).into())
+    }
+
+    /// Set all pixels to a color
+    pub fn fill(&mut self, color: Rgb565) {
+        self.pixels.fill(RawU16::from(color).into_inner().to_be());
+    }
+
+    /// Bytes of the buffer, in the order they are sent to the display
+    pub fn as_bytes(&self) -> &[u8] {
+        // SAFETY: u16 has no padding and a stricter alignment than u8
+        unsafe {
+            core::slice::from_raw_parts(self.pixels.as_ptr() as *const u8, self.pixels.len() * 2)
+        }
+    }
+
+    /// CRC32 of the bytes of the buffer, see [Crc32](crate::Crc32)
+    pub fn frame_crc(&self) -> u32 {
+        let mut crc = crate::Crc32::new();
+        crc.update(self.as_bytes());
+        crc.finish()
+    }
+
+    /// Release the static buffer
+    pub fn into_inner(self) -> &'static mut [u16] {
+        self.pixels
+    }
+}
+
+unsafe impl ReadBuffer for DmaPixelBuffer {
+    type Word = u8;
+
+    unsafe fn read_buffer(&self) -> (*const u8, usize) {
+        (self.pixels.as_ptr() as *const u8, self.pixels.len() * 2)
+    }
+}
+
+impl<IFACE> Ili9342C<IFACE, Rgb565>
+where
+    IFACE: WriteOnlyDataCommand,
+{
+    /// Write a buffer to a window through the interface, without converting
+    /// the pixels
+    pub fn draw_dma_buffer(
+        &mut self,
+        x0: u16,
+        y0: u16,
+        x1: u16,
+        y1: u16,
+        buffer: &DmaPixelBuffer,
+    ) -> Result {
+        self.start_memory_write(x0, y0, x1, y1)?;
+        self.write_pixel_bytes(buffer.as_bytes())
+    }
+}
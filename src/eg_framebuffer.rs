@@ -0,0 +1,89 @@
+//! Flushing the framebuffer of embedded-graphics 0.8, see the
+//! `eg-framebuffer` feature
+
+use display_interface::{DisplayError, WriteOnlyDataCommand};
+use embedded_graphics_0_8::{
+    framebuffer::Framebuffer,
+    pixelcolor::{
+        raw::{BigEndian, RawU16},
+        Rgb565 as EgRgb565,
+    },
+};
+use embedded_graphics_core::{
+    geometry::{Point, Size},
+    pixelcolor::Rgb565,
+    primitives::Rectangle,
+};
+
+use crate::{Ili9342C, Result};
+
+/// Big endian RGB565 framebuffer of embedded-graphics 0.8
+pub type EgFramebuffer<const W: usize, const H: usize, const N: usize> =
+    Framebuffer<EgRgb565, RawU16, BigEndian, W, H, N>;
+
+impl<IFACE> Ili9342C<IFACE, Rgb565>
+where
+    IFACE: WriteOnlyDataCommand,
+{
+    /// Write a whole framebuffer with its top left corner at `position`
+    ///
+    /// The buffer is already in the byte order of the display and sent as is,
+    /// without converting the pixels.
+    pub fn flush_framebuffer<const W: usize, const H: usize, const N: usize>(
+        &mut self,
+        framebuffer: &EgFramebuffer<W, H, N>,
+        position: Point,
+    ) -> Result {
+        let area = Rectangle::new(Point::zero(), Size::new(W as u32, H as u32));
+        self.flush_framebuffer_area(framebuffer, position, area)
+    }
+
+    /// Write an area of a framebuffer, in framebuffer coordinates, to the
+    /// same area relative to `position`
+    ///
+    /// Returns [DisplayError::OutOfBoundsError] if the area is not inside the
+    /// framebuffer, or its destination not on the display.
+    pub fn flush_framebuffer_area<const W: usize, const H: usize, const N: usize>(
+        &mut self,
+        framebuffer: &EgFramebuffer<W, H, N>,
+        position: Point,
+        area: Rectangle,
+    ) -> Result {
+        let bounds = Rectangle::new(Point::zero(), Size::new(W as u32, H as u32));
+        let display = Rectangle::new(
+            Point::zero(),
+            Size::new(self.state.width as u32, self.state.height as u32),
+        );
+        let target = Rectangle::new(area.top_left + position, area.size);
+        let (Some(bottom_right), Some(target_corner)) =
+            (area.bottom_right(), target.bottom_right())
+        else {
+            return Ok(());
+        };
+        if !bounds.contains(area.top_left)
+            || !bounds.contains(bottom_right)
+            || !display.contains(target.top_left)
+            || !display.contains(target_corner)
+        {
+            return Err(DisplayError::OutOfBoundsError);
+        }
+        self.start_memory_write(
+            target.top_left.x as u16,
+            target.top_left.y as u16,
+            target_corner.x as u16,
+            target_corner.y as u16,
+        )?;
+        let data = framebuffer.data();
+        let row_len = area.size.width as usize * 2;
+        if area.size.width as usize == W {
+            let start = area.top_left.y as usize * W * 2;
+            return self
+                .write_pixel_bytes(&data[start..start + row_len * area.size.height as usize]);
+        }
+        for y in area.rows() {
+            let start = (y as usize * W + area.top_left.x as usize) * 2;
+            self.write_pixel_bytes(&data[start..start + row_len])?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,29 @@
+//! [defmt] formatting, see the `defmt` feature
+
+use display_interface::DisplayError;
+
+/// [defmt::Format] wrapper of [DisplayError], which is defined in
+/// `display-interface` without defmt support
+///
+/// ```ignore
+/// if let Err(e) = display.clear(Rgb565::BLACK) {
+///     defmt::error!("clear failed: {}", DisplayErrorFormat(&e));
+/// }
+/// ```
+pub struct DisplayErrorFormat<'a>(pub &'a DisplayError);
+
+impl defmt::Format for DisplayErrorFormat<'_> {
+    fn format(&self, f: defmt::Formatter) {
+        let name = match self.0 {
+            DisplayError::InvalidFormatError => "InvalidFormatError",
+            DisplayError::BusWriteError => "BusWriteError",
+            DisplayError::DCError => "DCError",
+            DisplayError::CSError => "CSError",
+            DisplayError::DataFormatNotImplemented => "DataFormatNotImplemented",
+            DisplayError::RSError => "RSError",
+            DisplayError::OutOfBoundsError => "OutOfBoundsError",
+            _ => "Unknown",
+        };
+        defmt::write!(f, "DisplayError::{=str}", name)
+    }
+}
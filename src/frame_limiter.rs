@@ -0,0 +1,74 @@
+//! Pacing frames to a target rate
+
+use embedded_hal_0_2::blocking::delay::DelayUs;
+
+use crate::Monotonic;
+
+/// Paces a render loop to a target frame rate
+///
+/// Call [FrameLimiter::wait] once per frame, before the flush. It delays
+/// until the start of the next frame period, so animations advance at the
+/// same speed whatever the SPI clock, as long as a frame fits in a period.
+/// Frames running late start the next period right away.
+///
+/// ```ignore
+/// let mut limiter = FrameLimiter::new(&Timer, 30);
+/// loop {
+///     ui.draw(&mut framebuffer)?;
+///     limiter.wait(&mut delay);
+///     framebuffer.flush(&mut display)?;
+/// }
+/// ```
+pub struct FrameLimiter {
+    clock: &'static dyn Monotonic,
+    period_us: u64,
+    next: Option<u64>,
+    frame_time_us: Option<u64>,
+}
+
+impl FrameLimiter {
+    /// Pace frames to `fps` frames per second
+    pub fn new(clock: &'static dyn Monotonic, fps: u32) -> Self {
+        let mut limiter = Self {
+            clock,
+            period_us: 0,
+            next: None,
+            frame_time_us: None,
+        };
+        limiter.set_fps(fps);
+        limiter
+    }
+
+    /// Change the target frame rate, 0 disables the pacing
+    pub fn set_fps(&mut self, fps: u32) {
+        self.period_us = match fps {
+            0 => 0,
+            fps => 1_000_000 / fps as u64,
+        };
+    }
+
+    /// Time of the last frame before waiting, in microseconds
+    ///
+    /// Frame times above the period mean the target rate is not reached.
+    pub fn frame_time_us(&self) -> Option<u64> {
+        self.frame_time_us
+    }
+
+    /// Delay until the next frame period starts
+    pub fn wait<DELAY: DelayUs<u32>>(&mut self, delay: &mut DELAY) {
+        let now = self.clock.now_us();
+        let Some(next) = self.next else {
+            self.next = Some(now + self.period_us);
+            return;
+        };
+        let start = next.saturating_sub(self.period_us);
+        self.frame_time_us = Some(now.saturating_sub(start));
+        if now < next {
+            delay.delay_us((next - now).min(u32::MAX as u64) as u32);
+            self.next = Some(next + self.period_us);
+        } else {
+            // Late, start the next period now instead of catching up
+            self.next = Some(now + self.period_us);
+        }
+    }
+}
@@ -0,0 +1,397 @@
+//! Framebuffers kept in RAM and flushed to the display in one transfer
+
+use core::convert::Infallible;
+
+use display_interface::{DisplayError, WriteOnlyDataCommand};
+use embedded_graphics_core::{
+    draw_target::DrawTarget,
+    geometry::{OriginDimensions, Size},
+    pixelcolor::{
+        raw::{RawU16, RawU8},
+        PixelColor, Rgb565, RgbColor,
+    },
+    Pixel,
+};
+
+use embedded_hal::digital::InputPin;
+
+use crate::{bmp::encode_bmp, tearing::poll_vsync, Crc32, Ili9342C, PixelFormat, Result};
+
+/// Color of an [IndexedFramebuffer], an index into its palette
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PaletteIndex(pub u8);
+
+impl PixelColor for PaletteIndex {
+    type Raw = RawU8;
+}
+
+/// Framebuffer storing 4 or 8 bit palette indices
+///
+/// A full RGB565 framebuffer of the display takes 150 KB, a 8 bit indexed one
+/// takes 75 KB and a 4 bit one 38 KB. The indices are expanded to RGB565 with
+/// the palette while flushing.
+///
+/// 4 bit buffers store two pixels per byte, the left pixel in the high nibble.
+pub struct IndexedFramebuffer<'b, const BPP: usize> {
+    buffer: &'b mut [u8],
+    width: usize,
+    height: usize,
+    palette: [Rgb565; 256],
+}
+
+/// Framebuffer storing 4 bit palette indices
+pub type IndexedFramebuffer4<'b> = IndexedFramebuffer<'b, 4>;
+
+/// Framebuffer storing 8 bit palette indices
+pub type IndexedFramebuffer8<'b> = IndexedFramebuffer<'b, 8>;
+
+impl<'b, const BPP: usize> IndexedFramebuffer<'b, BPP> {
+    const PIXELS_PER_BYTE: usize = 8 / BPP;
+    const MASK: u8 = ((1u16 << BPP) - 1) as u8;
+
+    /// Number of bytes needed to store a buffer of the given size
+    pub const fn buffer_len(width: usize, height: usize) -> usize {
+        (width * height * BPP).div_ceil(8)
+    }
+
+    /// Create a framebuffer in the given storage, with an all black palette
+    ///
+    /// Returns [DisplayError::OutOfBoundsError] for an empty size or if the
+    /// storage is smaller than [Self::buffer_len].
+    pub fn new(buffer: &'b mut [u8], width: usize, height: usize) -> Result<Self> {
+        const {
+            assert!(
+                BPP == 4 || BPP == 8,
+                "only 4 and 8 bit indices are supported"
+            )
+        };
+
+        if width == 0 || height == 0 || buffer.len() < Self::buffer_len(width, height) {
+            return Err(DisplayError::OutOfBoundsError);
+        }
+        Ok(Self {
+            buffer,
+            width,
+            height,
+            palette: [Rgb565::BLACK; 256],
+        })
+    }
+
+    /// Get the palette, it has `2^BPP` entries
+    pub fn palette(&self) -> &[Rgb565] {
+        &self.palette[..1 << BPP]
+    }
+
+    /// Get the palette for modification, it has `2^BPP` entries
+    pub fn palette_mut(&mut self) -> &mut [Rgb565] {
+        &mut self.palette[..1 << BPP]
+    }
+
+    /// Replace the start of the palette with the given colors
+    pub fn set_palette(&mut self, colors: &[Rgb565]) {
+        let palette = self.palette_mut();
+        let len = colors.len().min(palette.len());
+        palette[..len].copy_from_slice(&colors[..len]);
+    }
+
+    /// Get the palette index of a pixel
+    pub fn get(&self, x: usize, y: usize) -> Option<PaletteIndex> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let (byte, shift) = self.position(x, y);
+        Some(PaletteIndex((self.buffer[byte] >> shift) & Self::MASK))
+    }
+
+    /// Set the palette index of a pixel, pixels outside the buffer are ignored
+    pub fn set(&mut self, x: usize, y: usize, index: PaletteIndex) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+        let (byte, shift) = self.position(x, y);
+        let byte = &mut self.buffer[byte];
+        *byte = (*byte & !(Self::MASK << shift)) | ((index.0 & Self::MASK) << shift);
+    }
+
+    /// Byte offset and bit shift of a pixel
+    fn position(&self, x: usize, y: usize) -> (usize, usize) {
+        let n = y * self.width + x;
+        let shift = 8 - BPP * (n % Self::PIXELS_PER_BYTE + 1);
+        (n / Self::PIXELS_PER_BYTE, shift)
+    }
+
+    /// Iterate over the palette indices of all pixels
+    fn indices(&self) -> impl Iterator<Item = u8> + '_ {
+        self.buffer
+            .iter()
+            .flat_map(|&byte| {
+                (0..Self::PIXELS_PER_BYTE).map(move |n| (byte >> (8 - BPP * (n + 1))) & Self::MASK)
+            })
+            .take(self.width * self.height)
+    }
+
+    /// Expand the buffer with the palette and write it to the top left corner
+    /// of the display
+    pub fn flush<IFACE>(&self, display: &mut Ili9342C<IFACE, Rgb565>) -> Result
+    where
+        IFACE: WriteOnlyDataCommand,
+    {
+        let palette = &self.palette;
+        display.draw_raw_iter(
+            0,
+            0,
+            self.width as u16 - 1,
+            self.height as u16 - 1,
+            self.indices().map(|index| palette[index as usize].to_raw()),
+        )
+    }
+
+    /// Like [Self::flush], stopping early when `should_cancel` returns true,
+    /// see [Ili9342C::draw_raw_iter_cancellable]
+    pub fn flush_cancellable<IFACE, F>(
+        &self,
+        display: &mut Ili9342C<IFACE, Rgb565>,
+        should_cancel: F,
+    ) -> Result<usize>
+    where
+        IFACE: WriteOnlyDataCommand,
+        F: FnMut() -> bool,
+    {
+        let palette = &self.palette;
+        display.draw_raw_iter_cancellable(
+            0,
+            0,
+            self.width as u16 - 1,
+            self.height as u16 - 1,
+            self.indices().map(|index| palette[index as usize].to_raw()),
+            should_cancel,
+        )
+    }
+
+    /// Like [Self::flush], reporting the progress of the transfer, see
+    /// [Ili9342C::draw_raw_iter_with_progress]
+    pub fn flush_with_progress<IFACE, F>(
+        &self,
+        display: &mut Ili9342C<IFACE, Rgb565>,
+        progress: F,
+    ) -> Result
+    where
+        IFACE: WriteOnlyDataCommand,
+        F: FnMut(usize, usize),
+    {
+        let palette = &self.palette;
+        display.draw_raw_iter_with_progress(
+            0,
+            0,
+            self.width as u16 - 1,
+            self.height as u16 - 1,
+            self.indices().map(|index| palette[index as usize].to_raw()),
+            progress,
+        )
+    }
+
+    /// CRC32 of the expanded pixels, see [Crc32]
+    pub fn frame_crc(&self) -> u32 {
+        let mut crc = Crc32::new();
+        for index in self.indices() {
+            crc.update(&self.palette[index as usize].to_raw().to_be_bytes());
+        }
+        crc.finish()
+    }
+
+    /// Write the buffer as a BMP image in chunks, see [encode_bmp]
+    pub fn screenshot_bmp<F: FnMut(&[u8])>(&self, write: &mut F) {
+        let palette = &self.palette;
+        encode_bmp(
+            self.width,
+            self.height,
+            self.indices().map(|index| palette[index as usize]),
+            write,
+        )
+    }
+}
+
+impl<const BPP: usize> OriginDimensions for IndexedFramebuffer<'_, BPP> {
+    fn size(&self) -> Size {
+        Size::new(self.width as u32, self.height as u32)
+    }
+}
+
+impl<const BPP: usize> DrawTarget for IndexedFramebuffer<'_, BPP> {
+    type Color = PaletteIndex;
+
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> core::result::Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, index) in pixels {
+            if point.x >= 0 && point.y >= 0 {
+                self.set(point.x as usize, point.y as usize, index);
+            }
+        }
+        Ok(())
+    }
+
+    fn clear(&mut self, index: Self::Color) -> core::result::Result<(), Self::Error> {
+        let index = index.0 & Self::MASK;
+        let byte = (0..Self::PIXELS_PER_BYTE).fold(0u16, |byte, _| (byte << BPP) | index as u16);
+        self.buffer.fill(byte as u8);
+        Ok(())
+    }
+}
+
+/// Framebuffer storing RGB565 pixels
+///
+/// The pixels are either stored as native `u16` values, or in the byte order
+/// sent to the display with [Rgb565Framebuffer::new_bus_order]. In bus order
+/// every drawn pixel is swapped once, and [Rgb565Framebuffer::flush] sends the
+/// memory as is without any work per pixel, which suits a DMA capable
+/// interface.
+pub struct Rgb565Framebuffer<'b> {
+    buffer: &'b mut [u16],
+    width: usize,
+    height: usize,
+    bus_order: bool,
+    dirty: bool,
+}
+
+impl<'b> Rgb565Framebuffer<'b> {
+    /// Create a framebuffer storing native pixel values
+    ///
+    /// Returns [DisplayError::OutOfBoundsError] for an empty size or if the
+    /// storage holds fewer than `width * height` pixels.
+    pub fn new(buffer: &'b mut [u16], width: usize, height: usize) -> Result<Self> {
+        if width == 0 || height == 0 || buffer.len() < width * height {
+            return Err(DisplayError::OutOfBoundsError);
+        }
+        Ok(Self {
+            buffer,
+            width,
+            height,
+            bus_order: false,
+            dirty: true,
+        })
+    }
+
+    /// Create a framebuffer storing pixels in the byte order of the bus
+    pub fn new_bus_order(buffer: &'b mut [u16], width: usize, height: usize) -> Result<Self> {
+        let mut framebuffer = Self::new(buffer, width, height)?;
+        framebuffer.bus_order = true;
+        Ok(framebuffer)
+    }
+
+    /// Whether the pixels are stored in the byte order of the bus
+    pub fn is_bus_order(&self) -> bool {
+        self.bus_order
+    }
+
+    /// Stored pixels, as big endian values in bus order
+    pub fn as_slice(&self) -> &[u16] {
+        &self.buffer[..self.width * self.height]
+    }
+
+    /// Get a pixel
+    pub fn get(&self, x: usize, y: usize) -> Option<Rgb565> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let mut raw = self.buffer[y * self.width + x];
+        if self.bus_order {
+            raw = u16::from_be(raw);
+        }
+        Some(RawU16::new(raw).into())
+    }
+
+    /// Set a pixel, pixels outside the buffer are ignored
+    pub fn set(&mut self, x: usize, y: usize, color: Rgb565) {
+        if x < self.width && y < self.height {
+            self.buffer[y * self.width + x] = self.store(color);
+            self.dirty = true;
+        }
+    }
+
+    /// Stored value of a color
+    fn store(&self, color: Rgb565) -> u16 {
+        let raw = color.to_raw();
+        if self.bus_order {
+            raw.to_be()
+        } else {
+            raw
+        }
+    }
+
+    /// Write the buffer to the top left corner of the display
+    ///
+    /// With [Ili9342C::set_idle_downshift] enabled, flushes without changes
+    /// since the last one are skipped and counted by the driver.
+    pub fn flush<IFACE>(&mut self, display: &mut Ili9342C<IFACE, Rgb565>) -> Result
+    where
+        IFACE: WriteOnlyDataCommand,
+    {
+        if !self.dirty && display.unchanged_flush()? {
+            return Ok(());
+        }
+        display.draw_raw_slice(
+            0,
+            0,
+            self.width as u16 - 1,
+            self.height as u16 - 1,
+            self.as_slice(),
+            self.bus_order,
+        )?;
+        self.dirty = false;
+        Ok(())
+    }
+
+    /// Like [Self::flush], waiting for a rising edge on the TE pin first
+    ///
+    /// Enable the pin with [Ili9342C::set_tearing_effect], and move the edge
+    /// with [Ili9342C::set_tear_scanline] to start the transfer while the
+    /// panel refresh is behind the written rows. Busy waits on the pin.
+    pub fn flush_vsync<IFACE, TE>(
+        &mut self,
+        display: &mut Ili9342C<IFACE, Rgb565>,
+        te: &mut TE,
+    ) -> Result
+    where
+        IFACE: WriteOnlyDataCommand,
+        TE: InputPin,
+    {
+        poll_vsync(te)?;
+        self.flush(display)
+    }
+}
+
+impl OriginDimensions for Rgb565Framebuffer<'_> {
+    fn size(&self) -> Size {
+        Size::new(self.width as u32, self.height as u32)
+    }
+}
+
+impl DrawTarget for Rgb565Framebuffer<'_> {
+    type Color = Rgb565;
+
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> core::result::Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            if point.x >= 0 && point.y >= 0 {
+                self.set(point.x as usize, point.y as usize, color);
+            }
+        }
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> core::result::Result<(), Self::Error> {
+        let value = self.store(color);
+        self.buffer.fill(value);
+        self.dirty = true;
+        Ok(())
+    }
+}
@@ -0,0 +1,243 @@
+//! Gamma correction settings
+
+use display_interface::WriteOnlyDataCommand;
+
+use crate::{Command, Ili9342C, PixelFormat, Result};
+
+/// Positive and negative gamma correction tables
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GammaTables {
+    /// Positive Gamma Correction (0xE0) table
+    pub positive: [u8; 15],
+    /// Negative Gamma Correction (0xE1) table
+    pub negative: [u8; 15],
+}
+
+/// The tables written during init
+impl Default for GammaTables {
+    fn default() -> Self {
+        GammaPreset::PanelDefault.tables()
+    }
+}
+
+/// Named gamma correction tables
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GammaPreset {
+    /// Tables of the ILI9342C vendor init, used unless configured otherwise
+    #[default]
+    PanelDefault,
+    /// Tables of the common ILI934x reference init, closer to a 2.2 curve
+    /// and less washed out than the default
+    SrgbLike,
+    /// Steeper tables giving deeper blacks and more saturated colors
+    HighContrast,
+}
+
+impl GammaPreset {
+    /// Get the gamma correction tables of this preset
+    pub fn tables(&self) -> GammaTables {
+        match self {
+            Self::PanelDefault => GammaTables {
+                positive: [
+                    0x00, 0x0c, 0x11, 0x04, 0x11, 0x08, 0x37, 0x89, 0x4c, 0x06, 0x0c, 0x0a, 0x2e,
+                    0x34, 0x0f,
+                ],
+                negative: [
+                    0x00, 0x0b, 0x11, 0x05, 0x13, 0x09, 0x33, 0x67, 0x48, 0x07, 0x0e, 0x0b, 0x2e,
+                    0x33, 0x0f,
+                ],
+            },
+            Self::SrgbLike => GammaTables {
+                positive: [
+                    0x0f, 0x31, 0x2b, 0x0c, 0x0e, 0x08, 0x4e, 0xf1, 0x37, 0x07, 0x10, 0x03, 0x0e,
+                    0x09, 0x00,
+                ],
+                negative: [
+                    0x00, 0x0e, 0x14, 0x03, 0x11, 0x07, 0x31, 0xc1, 0x48, 0x08, 0x0f, 0x0c, 0x31,
+                    0x36, 0x0f,
+                ],
+            },
+            Self::HighContrast => GammaTables {
+                positive: [
+                    0x00, 0x03, 0x09, 0x08, 0x16, 0x0a, 0x3f, 0x78, 0x4c, 0x09, 0x0a, 0x08, 0x16,
+                    0x1a, 0x0f,
+                ],
+                negative: [
+                    0x00, 0x16, 0x19, 0x03, 0x0f, 0x05, 0x32, 0x45, 0x46, 0x04, 0x0e, 0x0d, 0x35,
+                    0x37, 0x0f,
+                ],
+            },
+        }
+    }
+}
+
+impl From<GammaPreset> for GammaTables {
+    fn from(preset: GammaPreset) -> Self {
+        preset.tables()
+    }
+}
+
+/// Digital gamma tables used when 3-gamma control is enabled
+///
+/// The tables adjust the red and blue channels independently of the green
+/// channel, see [Ili9342C::set_digital_gamma].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DigitalGammaTables {
+    /// Digital Gamma Control 1 (0xE2) table, macro adjustment of red and blue
+    pub control1: [u8; 16],
+    /// Digital Gamma Control 2 (0xE3) table, micro adjustment of red and blue
+    pub control2: [u8; 64],
+}
+
+impl DigitalGammaTables {
+    /// Tables leaving all channels unchanged
+    pub const NEUTRAL: Self = Self {
+        control1: [0; 16],
+        control2: [0; 64],
+    };
+
+    /// Tables scaling the red and blue channels relative to green
+    ///
+    /// A gain of 255 leaves a channel unchanged. Each of the 64 gray levels
+    /// is lowered by up to 15 levels through the micro adjustment table, so
+    /// gains below about 195 are clamped at the bright end.
+    pub fn white_point(red_gain: u8, blue_gain: u8) -> Self {
+        let offset = |level: u32, gain: u8| -> u8 {
+            let reduction = (level * (255 - gain as u32) + 127) / 255;
+            reduction.min(0x0f) as u8
+        };
+        let mut control2 = [0; 64];
+        for (level, entry) in control2.iter_mut().enumerate() {
+            *entry = offset(level as u32, red_gain) << 4 | offset(level as u32, blue_gain);
+        }
+        Self {
+            control1: [0; 16],
+            control2,
+        }
+    }
+}
+
+/// Blackbody white points from 2000K to 10000K in 500K steps
+const WHITE_POINTS: [[u8; 3]; 17] = [
+    [255, 137, 14],
+    [255, 161, 72],
+    [255, 180, 107],
+    [255, 196, 137],
+    [255, 209, 163],
+    [255, 219, 186],
+    [255, 228, 206],
+    [255, 236, 224],
+    [255, 243, 239],
+    [255, 249, 253],
+    [245, 243, 255],
+    [235, 238, 255],
+    [227, 233, 255],
+    [220, 229, 255],
+    [214, 225, 255],
+    [208, 222, 255],
+    [204, 219, 255],
+];
+
+/// Red, green and blue gains of a color temperature relative to the native
+/// 6500K white point of the panel
+///
+/// The digital gamma tables can only lower red and blue against green, so
+/// green stays at 255 and the other channels are clamped to it.
+pub(crate) fn white_point_gains(kelvin: u16) -> [u8; 3] {
+    let kelvin = kelvin.clamp(2000, 10000) as u32 - 2000;
+    let index = (kelvin / 500) as usize;
+    let fraction = kelvin % 500;
+    let lower = WHITE_POINTS[index];
+    let upper = WHITE_POINTS[(index + 1).min(WHITE_POINTS.len() - 1)];
+    let native = WHITE_POINTS[9];
+    let mut gains = [0; 3];
+    for (channel, gain) in gains.iter_mut().enumerate() {
+        let value =
+            (lower[channel] as u32 * (500 - fraction) + upper[channel] as u32 * fraction) / 500;
+        *gain = value * 255 / native[channel] as u32;
+    }
+    let [red, green, blue] = gains;
+    let green = green.max(1);
+    [
+        (red * 255 / green).min(255) as u8,
+        255,
+        (blue * 255 / green).min(255) as u8,
+    ]
+}
+
+/// Predefined gamma curves of the Gamma Set (0x26) command
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GammaCurve {
+    /// Gamma 2.2, the default after reset
+    G2_2 = 0x01,
+    /// Gamma 1.8
+    G1_8 = 0x02,
+    /// Gamma 2.5
+    G2_5 = 0x04,
+    /// Gamma 1.0
+    G1_0 = 0x08,
+}
+
+impl<IFACE, C> Ili9342C<IFACE, C>
+where
+    IFACE: WriteOnlyDataCommand,
+    C: PixelFormat,
+{
+    /// Select one of the predefined gamma curves
+    pub fn set_gamma_curve(&mut self, curve: GammaCurve) -> Result {
+        self.command(Command::GammaSet, &[curve as u8])
+    }
+
+    /// Write the positive (0xE0) and negative (0xE1) gamma correction tables
+    pub fn set_gamma(&mut self, positive: &[u8; 15], negative: &[u8; 15]) -> Result {
+        self.command(Command::GammaControlPos1, positive)?;
+        self.command(Command::GammaControlNeg1, negative)
+    }
+
+    /// Enable or disable 3-gamma control (0xF2)
+    ///
+    /// When enabled the digital gamma tables are applied to the red and blue
+    /// channels on top of the analog gamma correction.
+    pub fn set_3gamma(&mut self, enable: bool) -> Result {
+        self.command(Command::Enable3G, &[0x02 | enable as u8])
+    }
+
+    /// Write the digital gamma tables used by 3-gamma control
+    pub fn set_digital_gamma(&mut self, tables: &DigitalGammaTables) -> Result {
+        self.command(Command::DigitalGammaControl1, &tables.control1)?;
+        self.command(Command::DigitalGammaControl2, &tables.control2)
+    }
+
+    /// Warm or cool the white point to a color temperature in kelvin
+    ///
+    /// The red and blue channels are scaled by the digital gamma tables of
+    /// the controller, so no pixel data has to be converted. 6500K is the
+    /// native white point, values are clamped to 2000K..=10000K. `None`
+    /// restores the native white point and disables 3-gamma control.
+    pub fn set_color_temperature(&mut self, kelvin: Option<u16>) -> Result {
+        match kelvin {
+            Some(kelvin) => {
+                let [red, _, blue] = white_point_gains(kelvin);
+                self.set_digital_gamma(&DigitalGammaTables::white_point(red, blue))?;
+                self.set_3gamma(true)
+            }
+            None => {
+                self.set_digital_gamma(&DigitalGammaTables::NEUTRAL)?;
+                self.set_3gamma(false)
+            }
+        }
+    }
+
+    /// Write the gamma correction tables of a preset
+    pub fn set_gamma_preset(&mut self, preset: GammaPreset) -> Result {
+        let tables = preset.tables();
+        self.set_gamma(&tables.positive, &tables.negative)
+    }
+}
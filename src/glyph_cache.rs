@@ -0,0 +1,201 @@
+//! Text drawn from pre-rendered glyph tiles, see the `glyph-cache` feature
+
+use display_interface::{DisplayError, WriteOnlyDataCommand};
+use embedded_graphics::{
+    image::{Image, ImageDrawableExt},
+    mono_font::MonoFont,
+    pixelcolor::BinaryColor,
+    Drawable,
+};
+use embedded_graphics_core::{
+    draw_target::DrawTarget,
+    geometry::{Dimensions, OriginDimensions, Point, Size},
+    pixelcolor::{
+        raw::{RawData, RawU16},
+        Rgb565,
+    },
+    primitives::Rectangle,
+    Pixel,
+};
+
+use crate::{Ili9342C, Result};
+
+/// Glyphs of a mono font rendered once to RGB565 tiles in the byte order
+/// sent to the display
+///
+/// Each character is then drawn with a single window and memory write of its
+/// tile, instead of going through the per pixel path of embedded-graphics.
+/// The character spacing of the font is part of the tiles. Like
+/// [Ili9342C::draw_raw_iter], the tiles bypass the clip, pixel shift and
+/// color lookup table.
+///
+/// ```ignore
+/// // GlyphCache::buffer_len(&FONT_6X10)
+/// static mut TILES: [u8; 11_520] = [0; 11_520];
+/// let cache = GlyphCache::new(&FONT_6X10, Rgb565::WHITE, Rgb565::BLACK, unsafe { &mut TILES })?;
+/// cache.draw_text(&mut display, "Hello", Point::new(10, 10))?;
+/// ```
+pub struct GlyphCache<'b> {
+    font: &'static MonoFont<'static>,
+    tiles: &'b mut [u8],
+}
+
+impl<'b> GlyphCache<'b> {
+    /// Bytes of tiles needed for all glyphs of a font
+    pub fn buffer_len(font: &MonoFont<'_>) -> usize {
+        glyph_count(font) * tile_len(font)
+    }
+
+    /// Render the glyphs of a font in a buffer of at least
+    /// [Self::buffer_len] bytes
+    pub fn new(
+        font: &'static MonoFont<'static>,
+        text_color: Rgb565,
+        background: Rgb565,
+        tiles: &'b mut [u8],
+    ) -> Result<Self> {
+        if tiles.len() < Self::buffer_len(font) {
+            return Err(DisplayError::OutOfBoundsError);
+        }
+        let mut cache = Self { font, tiles };
+        cache.set_colors(text_color, background);
+        Ok(cache)
+    }
+
+    /// Render the glyphs again with other colors
+    pub fn set_colors(&mut self, text_color: Rgb565, background: Rgb565) {
+        let font = self.font;
+        let glyphs_per_row = font.image.size().width / font.character_size.width;
+        let tiles = self.tiles.chunks_exact_mut(tile_len(font));
+        for (index, tile) in tiles.take(glyph_count(font)).enumerate() {
+            let index = index as u32;
+            let glyph = Point::new(
+                ((index % glyphs_per_row) * font.character_size.width) as i32,
+                ((index / glyphs_per_row) * font.character_size.height) as i32,
+            );
+            let mut target = Tile {
+                bytes: tile,
+                size: tile_size(font),
+                on: RawU16::from(text_color).into_inner().to_be_bytes(),
+                off: RawU16::from(background).into_inner().to_be_bytes(),
+            };
+            // Spacing columns show the background
+            target.fill(target.off);
+            let area = Rectangle::new(glyph, font.character_size);
+            // Drawing to a tile cannot fail
+            Image::new(&font.image.sub_image(&area), Point::zero())
+                .draw(&mut target)
+                .ok();
+        }
+    }
+
+    /// Size of a character including the spacing
+    pub fn tile_size(&self) -> Size {
+        tile_size(self.font)
+    }
+
+    /// Draw text with its top left corner at `position`, returning the
+    /// position after the last character
+    ///
+    /// `\n` starts a new line below `position`. Characters not fully on the
+    /// display are skipped.
+    pub fn draw_text<IFACE>(
+        &self,
+        display: &mut Ili9342C<IFACE, Rgb565>,
+        text: &str,
+        position: Point,
+    ) -> Result<Point>
+    where
+        IFACE: WriteOnlyDataCommand,
+    {
+        let size = self.tile_size();
+        let bounds = display.bounding_box();
+        let len = tile_len(self.font);
+        let mut cursor = position;
+        for c in text.chars() {
+            if c == '\n' {
+                cursor = Point::new(position.x, cursor.y + size.height as i32);
+                continue;
+            }
+            let area = Rectangle::new(cursor, size);
+            cursor.x += size.width as i32;
+            let Some(bottom_right) = area
+                .bottom_right()
+                .filter(|&corner| bounds.contains(area.top_left) && bounds.contains(corner))
+            else {
+                continue;
+            };
+            let start = self.font.glyph_mapping.index(c) * len;
+            let Some(tile) = self.tiles.get(start..start + len) else {
+                continue;
+            };
+            display.start_memory_write(
+                area.top_left.x as u16,
+                area.top_left.y as u16,
+                bottom_right.x as u16,
+                bottom_right.y as u16,
+            )?;
+            display.write_pixel_bytes(tile)?;
+        }
+        Ok(cursor)
+    }
+}
+
+/// Number of glyphs in the image of a font
+fn glyph_count(font: &MonoFont<'_>) -> usize {
+    let Size { width, height } = font.image.size();
+    ((width / font.character_size.width) * (height / font.character_size.height)) as usize
+}
+
+/// Size of a glyph tile
+fn tile_size(font: &MonoFont<'_>) -> Size {
+    font.character_size + Size::new(font.character_spacing, 0)
+}
+
+/// Bytes of a glyph tile
+fn tile_len(font: &MonoFont<'_>) -> usize {
+    let Size { width, height } = tile_size(font);
+    (width * height) as usize * 2
+}
+
+/// Draw target rendering a glyph into a tile
+struct Tile<'a> {
+    bytes: &'a mut [u8],
+    size: Size,
+    on: [u8; 2],
+    off: [u8; 2],
+}
+
+impl Tile<'_> {
+    fn fill(&mut self, pixel: [u8; 2]) {
+        for chunk in self.bytes.chunks_exact_mut(2) {
+            chunk.copy_from_slice(&pixel);
+        }
+    }
+}
+
+impl OriginDimensions for Tile<'_> {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl DrawTarget for Tile<'_> {
+    type Color = BinaryColor;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> core::result::Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            if !self.bounding_box().contains(point) {
+                continue;
+            }
+            let index = (point.y as usize * self.size.width as usize + point.x as usize) * 2;
+            let bytes = if color.is_on() { self.on } else { self.off };
+            self.bytes[index..index + 2].copy_from_slice(&bytes);
+        }
+        Ok(())
+    }
+}
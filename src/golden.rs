@@ -0,0 +1,86 @@
+//! Golden command sequences
+//!
+//! The bytes sent by init, orientation changes and drawing are compared to
+//! the sequences in `src/golden`. After an intended change of the command
+//! stream, run the tests with `UPDATE_GOLDEN=1` to rewrite them.
+
+extern crate std;
+
+use embedded_graphics_core::{
+    draw_target::DrawTarget, geometry::Point, pixelcolor::Rgb565, prelude::*,
+    primitives::Rectangle, Pixel,
+};
+
+use crate::{
+    tests::{format, record},
+    CommandRecorder, Orientation,
+};
+
+fn check(name: &str, golden: &str, recorder: &CommandRecorder) {
+    let actual = format(recorder);
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        let path = std::format!("{}/src/golden/{name}.txt", env!("CARGO_MANIFEST_DIR"));
+        std::fs::write(path, &actual).unwrap();
+        return;
+    }
+    assert!(
+        actual == golden,
+        "command stream of `{name}` differs from src/golden/{name}.txt:\n{actual}"
+    );
+}
+
+#[test]
+fn init() {
+    let mut buffer = [0; 1024];
+    let recorder = record(&mut buffer, true, |_| {});
+    check("init", include_str!("golden/init.txt"), &recorder);
+}
+
+#[test]
+fn orientation() {
+    let mut buffer = [0; 1024];
+    let recorder = record(&mut buffer, false, |display| {
+        display.set_orientation(Orientation::Portrait).unwrap();
+        display
+            .draw_iter([Pixel(Point::new(239, 319), Rgb565::RED)])
+            .unwrap();
+        display
+            .set_orientation(Orientation::LandscapeFlipped)
+            .unwrap();
+        display
+            .draw_iter([Pixel(Point::new(319, 239), Rgb565::RED)])
+            .unwrap();
+    });
+    check(
+        "orientation",
+        include_str!("golden/orientation.txt"),
+        &recorder,
+    );
+}
+
+#[test]
+fn drawing() {
+    let mut buffer = [0; 1024];
+    let recorder = record(&mut buffer, false, |display| {
+        display
+            .draw_iter([
+                Pixel(Point::new(1, 2), Rgb565::WHITE),
+                Pixel(Point::new(400, 2), Rgb565::WHITE),
+            ])
+            .unwrap();
+        display
+            .fill_solid(
+                &Rectangle::new(Point::new(10, 20), Size::new(3, 2)),
+                Rgb565::BLUE,
+            )
+            .unwrap();
+        display
+            .fill_contiguous(
+                &Rectangle::new(Point::new(318, 238), Size::new(4, 1)),
+                [Rgb565::RED, Rgb565::GREEN, Rgb565::BLUE, Rgb565::WHITE],
+            )
+            .unwrap();
+        display.draw_raw_iter(5, 6, 6, 6, [0x1234, 0xabcd]).unwrap();
+    });
+    check("drawing", include_str!("golden/drawing.txt"), &recorder);
+}
@@ -0,0 +1,101 @@
+//! Lowering the refresh of static screens
+
+use display_interface::WriteOnlyDataCommand;
+
+use crate::{Command, FrameDivision, FrameRate, Ili9342C, ModeState, PixelFormat, Result};
+
+/// Low power state entered by [Ili9342C::set_idle_downshift]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum IdleDownshift {
+    /// Enter idle mode, which only shows 8 colors at the idle mode frame rate
+    /// set with [Ili9342C::set_idle_frame_rate]
+    IdleMode,
+    /// Lower the normal mode frame rate, keeping all colors
+    FrameRate(FrameDivision, FrameRate),
+}
+
+/// Frame rate control value after reset
+const DEFAULT_FRAME_RATE: [u8; 2] = [FrameDivision::Div1 as u8, FrameRate::Hz79 as u8];
+
+/// Unchanged flushes counted by the driver
+#[derive(Clone, Copy)]
+pub(crate) struct DownshiftState {
+    mode: IdleDownshift,
+    after: u16,
+    unchanged: u16,
+    /// Frame rate control value to restore, set while downshifted
+    restore: Option<[u8; 2]>,
+}
+
+impl<IFACE, C> Ili9342C<IFACE, C>
+where
+    IFACE: WriteOnlyDataCommand,
+    C: PixelFormat,
+{
+    /// Downshift the panel after `after_flushes` framebuffer flushes without
+    /// changes, or disable the policy with `None`
+    ///
+    /// Flushes of an unchanged [Rgb565Framebuffer](crate::Rgb565Framebuffer)
+    /// are skipped and counted. The next memory write, from a flush or any
+    /// drawing, restores normal mode before the pixels are sent. Disabling
+    /// the policy while downshifted restores normal mode.
+    ///
+    /// ```ignore
+    /// display.set_idle_downshift(Some((IdleDownshift::FrameRate(FrameDivision::Div4, FrameRate::Hz61), 30)))?;
+    /// loop {
+    ///     ui.draw(&mut framebuffer)?;
+    ///     framebuffer.flush(&mut display)?;
+    /// }
+    /// ```
+    pub fn set_idle_downshift(&mut self, policy: Option<(IdleDownshift, u16)>) -> Result {
+        self.leave_downshift()?;
+        self.state.downshift = policy.map(|(mode, after)| DownshiftState {
+            mode,
+            after,
+            unchanged: 0,
+            restore: None,
+        });
+        Ok(())
+    }
+
+    /// Whether the panel is downshifted by [Self::set_idle_downshift]
+    pub fn is_downshifted(&self) -> bool {
+        self.state
+            .downshift
+            .is_some_and(|state| state.restore.is_some())
+    }
+
+    /// Count a flush without changes, returning whether it can be skipped
+    pub(crate) fn unchanged_flush(&mut self) -> Result<bool> {
+        let Some(state) = self.state.downshift.as_mut() else {
+            return Ok(false);
+        };
+        state.unchanged = state.unchanged.saturating_add(1);
+        if state.unchanged < state.after || state.restore.is_some() {
+            return Ok(true);
+        }
+        let mode = state.mode;
+        state.restore = Some(self.state.panel.frame_rate.unwrap_or(DEFAULT_FRAME_RATE));
+        match mode {
+            IdleDownshift::IdleMode => self.idle_mode(ModeState::On)?,
+            IdleDownshift::FrameRate(division, rate) => self.set_frame_rate(division, rate)?,
+        }
+        Ok(true)
+    }
+
+    /// Restore normal mode before a memory write
+    pub(crate) fn leave_downshift(&mut self) -> Result {
+        let Some(state) = self.state.downshift.as_mut() else {
+            return Ok(());
+        };
+        state.unchanged = 0;
+        let mode = state.mode;
+        match state.restore.take() {
+            None => Ok(()),
+            Some(_) if mode == IdleDownshift::IdleMode => self.idle_mode(ModeState::Off),
+            Some(restore) => self.command(Command::FrameControl, &restore),
+        }
+    }
+}
@@ -5,11 +5,12 @@
 //!
 //! To control the display you need to set up:
 //!
-//! * Interface for communicating with display ([display-interface-spi crate] for SPI)
+//! * Interface for communicating with display ([SpiInterface] for SPI, or any
+//!   [display-interface] implementation)
 //! * Configuration (reset pin, delay, orientation and size) for display
 //!
 //! ```ignore
-//! let iface = SPIInterface::new(spi, dc, cs);
+//! let iface = SpiInterface::<_, _>::new(spi_device, dc);
 //!
 //! let mut display = Ili9341::new(
 //!     iface,
@@ -23,21 +24,179 @@
 //! display.clear(Rgb565::RED).unwrap()
 //! ```
 //!
-//! [display-interface-spi crate]: https://crates.io/crates/display-interface-spi
+//! [display-interface]: https://crates.io/crates/display-interface
+#[cfg(any(feature = "mock", feature = "simulator"))]
+extern crate alloc;
+
 use embedded_hal_0_2::blocking::delay::DelayMs;
 // use embedded_hal::delay::blocking::DelayUs;
 
 use core::iter::once;
-// use embedded_hal::spi::
-use display_interface::DataFormat::{U16BEIter, U8Iter};
+use core::marker::PhantomData;
+use display_interface::DataFormat::{U8Iter, U8};
 use display_interface::WriteOnlyDataCommand;
 
 // mod graphics_core;
-use embedded_graphics_core::{
-    pixelcolor::{raw::RawU16, Rgb565},
-    prelude::*,
-    primitives::Rectangle,
+use embedded_graphics_core::{pixelcolor::Rgb565, prelude::*, primitives::Rectangle};
+
+mod access;
+mod animation;
+#[cfg(feature = "async")]
+mod asynch;
+mod auto_sleep;
+mod backlight;
+mod band;
+mod bmp;
+mod borrowed;
+mod builder;
+mod burn_in;
+mod clip;
+mod clock;
+mod coalesce;
+mod color;
+mod color_set;
+#[cfg(feature = "console")]
+mod console;
+mod correction;
+mod crc;
+mod debug;
+#[cfg(feature = "dma")]
+mod dma;
+#[cfg(feature = "eg-framebuffer")]
+mod eg_framebuffer;
+#[cfg(feature = "defmt")]
+mod format;
+mod frame_limiter;
+mod framebuffer;
+mod gamma;
+#[cfg(feature = "glyph-cache")]
+mod glyph_cache;
+#[cfg(test)]
+mod golden;
+mod idle;
+#[cfg(feature = "linux")]
+mod linux;
+#[cfg(feature = "lvgl")]
+mod lvgl;
+mod marquee;
+mod mipi_dcs;
+mod mirrored;
+#[cfg(feature = "mock")]
+mod mock;
+mod nonblocking;
+mod observer;
+mod options;
+mod pattern;
+mod pixel_format;
+mod power;
+mod progress;
+#[cfg(feature = "ratatui")]
+mod ratatui;
+mod raw_slice;
+mod readback;
+mod recorder;
+mod region;
+mod reinit;
+mod rotation;
+mod scanline;
+mod screensaver;
+mod scroll;
+mod scroll_list;
+mod scroll_region;
+mod self_test;
+#[cfg(feature = "critical-section")]
+mod shared;
+#[cfg(feature = "simulator")]
+mod simulator;
+#[cfg(feature = "slint")]
+mod slint;
+mod spi;
+mod splash;
+mod stats;
+mod strip_chart;
+mod tearing;
+mod tiled;
+mod timing;
+mod update_queue;
+mod watchdog;
+pub use access::{HorizontalRefresh, VerticalRefresh};
+pub use animation::{Easing, Ticker};
+#[cfg(feature = "async")]
+pub use asynch::{wait_for_vsync, AsyncFlusher, BufferTarget, DoubleBuffer};
+pub use backlight::CabcMode;
+pub use band::Band;
+pub use bmp::encode_bmp;
+pub use borrowed::{BorrowedInterface, Ili9342CRef};
+pub use builder::Builder;
+use builder::InitSettings;
+use burn_in::PixelShift;
+pub use clock::Monotonic;
+use clock::TimingState;
+pub use coalesce::RunCoalescer;
+pub use color::{Gray8Adapter, NightModeAdapter, Rgb888Adapter};
+pub use color_set::ColorSet;
+#[cfg(feature = "console")]
+pub use console::{Console, ConsoleWriter};
+pub use correction::ColorLut;
+pub use crc::Crc32;
+use debug::PanelState;
+#[cfg(feature = "dma")]
+pub use dma::DmaPixelBuffer;
+#[cfg(feature = "eg-framebuffer")]
+pub use eg_framebuffer::EgFramebuffer;
+#[cfg(feature = "defmt")]
+pub use format::DisplayErrorFormat;
+pub use frame_limiter::FrameLimiter;
+pub use framebuffer::{
+    IndexedFramebuffer, IndexedFramebuffer4, IndexedFramebuffer8, PaletteIndex, Rgb565Framebuffer,
+};
+pub use gamma::{DigitalGammaTables, GammaCurve, GammaPreset, GammaTables};
+#[cfg(feature = "glyph-cache")]
+pub use glyph_cache::GlyphCache;
+pub use idle::IdleDownshift;
+#[cfg(feature = "linux")]
+pub use linux::{linux_interface, linux_reset, LinuxDelay, LinuxInterface};
+pub use marquee::{Marquee, MarqueeDirection};
+pub use mipi_dcs::DcsCommand;
+pub use mirrored::{MirrorTransform, MirroredDisplay};
+#[cfg(feature = "mock")]
+pub use mock::{MockInterface, Transfer};
+pub use nonblocking::PendingFlush;
+pub use observer::CommandObserver;
+pub use options::{ColorOrder, InitOptions};
+pub use pattern::Pattern;
+pub use pixel_format::{PixelEndian, PixelFormat, Rgb444};
+pub use power::{hard_reset, PowerConfig, PowerOnSequence, PowerProfile, PumpRatio};
+pub use progress::ProgressBar;
+#[cfg(feature = "ratatui")]
+pub use ratatui::{BackendError, RatatuiBackend};
+pub use readback::{probe, Controller, ReadDataCommand};
+pub use recorder::{CommandRecorder, Recorded, RecordedStream};
+pub use region::Region;
+pub use rotation::{OrientationHook, Rotation};
+pub use screensaver::{Screensaver, ScreensaverState};
+pub use scroll::Scroller;
+pub use scroll_list::{ListItemTarget, ScrollList};
+pub use scroll_region::ScrollRegion;
+pub use self_test::SelfTestReport;
+#[cfg(feature = "critical-section")]
+pub use shared::{SharedError, SharedIli9342C};
+#[cfg(feature = "simulator")]
+pub use simulator::Simulator;
+#[cfg(feature = "slint")]
+pub use slint::SlintLineBuffer;
+pub use spi::{SpiBusInterface, SpiInterface};
+pub use splash::ImageFormat;
+#[cfg(feature = "stats")]
+pub use stats::Stats;
+use stats::StatsState;
+pub use strip_chart::StripChart;
+pub use tearing::TearingEffect;
+pub use tiled::TiledDisplay;
+pub use timing::{
+    DisplayFunctionConfig, DriverTimingA, DriverTimingB, FrameDivision, FrameRate, NonDisplayScan,
 };
+pub use update_queue::{UpdateBudget, UpdateQueue};
 
 pub use embedded_hal::spi::MODE_0 as SPI_MODE;
 
@@ -46,36 +205,36 @@ use embedded_graphics_core::draw_target::DrawTarget;
 
 type Result<T = (), E = DisplayError> = core::result::Result<T, E>;
 
-impl<IFACE> OriginDimensions for Ili9342C<IFACE> {
+impl<IFACE, C> OriginDimensions for Ili9342C<IFACE, C> {
     fn size(&self) -> Size {
         Size::new(self.width() as u32, self.height() as u32)
     }
 }
 
-impl<IFACE> DrawTarget for Ili9342C<IFACE>
+impl<IFACE, C> DrawTarget for Ili9342C<IFACE, C>
 where
     IFACE: display_interface::WriteOnlyDataCommand,
+    C: PixelFormat,
 {
     type Error = display_interface::DisplayError;
 
-    type Color = Rgb565;
+    type Color = C;
 
     fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
     where
         I: IntoIterator<Item = Pixel<Self::Color>>,
     {
+        let offset = self.pixel_offset();
+        let lut = self.state.color_lut;
+        let visible = self.drawable_area();
         for Pixel(point, color) in pixels {
-            if self.bounding_box().contains(point) {
+            let point = point + offset;
+            if visible.contains(point) {
                 let x = point.x as u16;
                 let y = point.y as u16;
 
-                self.draw_raw_iter(
-                    x,
-                    y,
-                    x,
-                    y,
-                    core::iter::once(RawU16::from(color).into_inner()),
-                )?;
+                let color = correction::correct(&lut, color);
+                self.draw_raw_iter(x, y, x, y, core::iter::once(color.to_raw()))?;
             }
         }
         Ok(())
@@ -85,7 +244,9 @@ where
     where
         I: IntoIterator<Item = Self::Color>,
     {
-        let drawable_area = area.intersection(&self.bounding_box());
+        let area = &Rectangle::new(area.top_left + self.pixel_offset(), area.size);
+        let drawable_area = area.intersection(&self.drawable_area());
+        let lut = self.state.color_lut;
 
         if let Some(drawable_bottom_right) = drawable_area.bottom_right() {
             let x0 = drawable_area.top_left.x as u16;
@@ -102,7 +263,7 @@ where
                     y1,
                     area.points()
                         .zip(colors)
-                        .map(|(_, color)| RawU16::from(color).into_inner()),
+                        .map(|(_, color)| correction::correct(&lut, color).to_raw()),
                 )
             } else {
                 // Some pixels are on screen
@@ -114,7 +275,7 @@ where
                     area.points()
                         .zip(colors)
                         .filter(|(point, _)| drawable_area.contains(*point))
-                        .map(|(_, color)| RawU16::from(color).into_inner()),
+                        .map(|(_, color)| correction::correct(&lut, color).to_raw()),
                 )
             }
         } else {
@@ -124,7 +285,10 @@ where
     }
 
     fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
-        self.clear_screen(RawU16::from(color).into_inner())
+        if self.state.clip.is_some() {
+            return self.fill_solid(&self.bounding_box(), color);
+        }
+        self.clear_screen(correction::correct(&self.state.color_lut, color).to_raw())
     }
 }
 
@@ -153,6 +317,9 @@ pub trait Mode {
 /// The default implementation of the Mode trait from above
 /// Should work for most (but not all) boards
 #[allow(unused)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Orientation {
     Portrait,
     PortraitFlipped,
@@ -184,6 +351,7 @@ impl Mode for Orientation {
 }
 
 /// Specify state of specific mode of operation
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum ModeState {
     On,
     Off,
@@ -192,105 +360,225 @@ pub enum ModeState {
 /// There are two method for drawing to the screen:
 /// [Ili9341::draw_raw_iter] and [Ili9341::draw_raw_slice]
 ///
-/// In both cases the expected pixel format is rgb565.
+/// In both cases the expected pixel format is the raw value of `C`.
 ///
 /// The hardware makes it efficient to draw rectangles on the screen.
 ///
 /// What happens is the following:
 ///
 /// - A drawing window is prepared (with the 2 opposite corner coordinates)
-/// - The starting point for drawint is the top left corner of this window
-/// - Every pair of bytes received is intepreted as a pixel value in rgb565
+/// - The starting point for drawing is the top left corner of this window
+/// - The bytes received are interpreted as pixels in the [PixelFormat] of the
+///   display, e.g. every pair of bytes is a pixel in [Rgb565]
 /// - As soon as a pixel is received, an internal counter is incremented,
-///   and the next word will fill the next pixel (the adjacent on the right, or
-///   the first of the next row if the row ended)
-#[allow(unused)]
-pub struct Ili9342C<IFACE> {
+///   and the next pixel received fills the adjacent one on the right, or the
+///   first of the next row if the row ended
+///
+/// The pixel format is selected with the `C` type parameter, [Rgb565] by
+/// default. [Rgb666](embedded_graphics_core::pixelcolor::Rgb666) sends 3 bytes
+/// per pixel and gives smoother gradients, [Rgb444] sends two pixels in 3
+/// bytes for less bus traffic.
+pub struct Ili9342C<IFACE, C = Rgb565> {
     interface: IFACE,
+    state: DisplayState,
+    _color: PhantomData<C>,
+}
+
+/// Panel state of a released driver, see [Ili9342C::release]
+///
+/// The panel keeps its configuration while the interface is used elsewhere,
+/// [Ili9342C::from_parts] resumes driving it without running the init
+/// sequence again.
+#[derive(Clone, Copy)]
+pub struct DisplayState {
     width: usize,
     height: usize,
     landscape: bool,
+    init: InitSettings,
+    max_transfer: Option<usize>,
+    yield_hook: Option<fn()>,
+    stats: StatsState,
+    observer: Option<&'static dyn CommandObserver>,
+    panel: PanelState,
+    timing: TimingState,
+    pixel_shift: Option<PixelShift>,
+    color_lut: Option<ColorLut>,
+    clip: Option<Rectangle>,
+    mirror: u8,
+    refresh: u8,
+    watchdog: Option<watchdog::Watchdog>,
+    orientation_hook: Option<OrientationHook>,
+    downshift: Option<idle::DownshiftState>,
+    auto_sleep: Option<auto_sleep::AutoSleep>,
 }
 
-impl<IFACE> Ili9342C<IFACE>
+impl<IFACE, C> Ili9342C<IFACE, C>
 where
     IFACE: WriteOnlyDataCommand,
+    C: PixelFormat,
 {
+    /// Initialize the display with the default settings, see [Builder] for
+    /// other settings
     pub fn new<DELAY, SIZE, MODE>(
         interface: IFACE,
         delay: &mut DELAY,
         mode: MODE,
-        _display_size: SIZE,
+        display_size: SIZE,
     ) -> Result<Self>
     where
         DELAY: DelayMs<u16>,
         SIZE: DisplaySize,
         MODE: Mode,
     {
-        let mut ili = Ili9342C {
+        Builder::new(interface).init(delay, mode, display_size)
+    }
+
+    /// Resume driving a panel released with [Self::release]
+    ///
+    /// The panel must not have been reset or reconfigured in between.
+    pub fn from_parts(interface: IFACE, state: DisplayState) -> Self {
+        Self {
             interface,
-            width: SIZE::WIDTH,
-            height: SIZE::HEIGHT,
-            landscape: false,
-        };
-        ili.command(Command::SoftwareReset, &[])?;
-        let _ = delay.delay_ms(10);
-        ili.command(Command::ExtC, &[0xff, 0x93, 0x42])?;
-        ili.command(Command::PowerControl1, &[0x12, 0x12])?;
-        ili.command(Command::PowerControl2, &[0x03])?;
-        ili.command(Command::RBGInterface, &[0xe0])?;
-        ili.command(Command::InterfaceCtrl, &[0x00, 0x01, 0x01])?;
-        // Default is 0x80, 0x20, 0x08
-        ili.command(Command::MemoryAccessControl, &[mode.mode()])?;
-        //     Orientation::Landscape => mode.mode(),
-        //     Orientation::Portrait => mode.mode(),
-        //     Orientation::LandscapeFlipped => mode.mode(),
-        //     Orientation::PortraitFlipped => mode.mode(),
-        // };
-        // ili.command(Command::MemoryAccessControl, &[0x40 | 0x20 | 0x08])?;
-        ili.command(Command::PixelFormatSet, &[0x55])?;
-        ili.command(Command::DisplayFunctionControl, &[0x08, 0x82, 0x27])?;
-        ili.command(
-            Command::GammaControlPos1,
-            &[
-                0x00, 0x0c, 0x11, 0x04, 0x11, 0x08, 0x37, 0x89, 0x4c, 0x06, 0x0c, 0x0a, 0x2e, 0x34,
-                0x0f,
-            ],
-        )?;
-        ili.command(
-            Command::GammaControlNeg1,
-            &[
-                0x00, 0x0b, 0x11, 0x05, 0x13, 0x09, 0x33, 0x67, 0x48, 0x07, 0x0e, 0x0b, 0x2e, 0x33,
-                0x0f,
-            ],
-        )?;
-        ili.sleep_mode(ModeState::Off)?;
-        let _ = delay.delay_ms(120);
-        ili.display_mode(ModeState::On)?;
-        ili.command(Command::InvertOn, &[])?;
+            state,
+            _color: PhantomData,
+        }
+    }
 
-        // Wait 5ms after Sleep Out before sending commands
-        let _ = delay.delay_ms(5);
+    /// Release the interface, keeping the panel state for [Self::from_parts]
+    ///
+    /// No commands are sent, the panel keeps showing its current content.
+    pub fn release(self) -> (IFACE, DisplayState) {
+        (self.interface, self.state)
+    }
 
-        Ok(ili)
+    /// Release the interface, dropping the panel state
+    pub fn into_inner(self) -> IFACE {
+        self.interface
     }
 }
 
-impl<IFACE> Ili9342C<IFACE>
+impl<IFACE, C> Ili9342C<IFACE, C>
 where
     IFACE: WriteOnlyDataCommand,
+    C: PixelFormat,
 {
     fn command(&mut self, cmd: Command, args: &[u8]) -> Result {
-        self.interface.send_commands(U8Iter(&mut once(cmd as u8)))?;
+        self.state.panel.command(cmd, args);
+        if matches!(cmd, Command::MemoryWrite) {
+            self.auto_wake()?;
+            self.leave_downshift()?;
+            self.state.timing.transfer_start();
+        }
+        self.send_command(cmd as u8, args)
+    }
+
+    /// Send a command and its parameters as is
+    ///
+    /// Low level access for vendor specific registers of a module, or any
+    /// MIPI DCS command not wrapped by the driver. The driver does not track
+    /// what the command changes: memory access control, pixel format or
+    /// address window commands make later drawing wrong, and [Self::reinit]
+    /// does not restore any of them. The command is still counted in the
+    /// statistics and reported to the [CommandObserver].
+    ///
+    /// ```ignore
+    /// // Vendor gate control register of a module
+    /// display.send_command(0xfd, &[0x06, 0x08])?;
+    /// display.send_command(Command::PartialModeOn, &[])?;
+    /// ```
+    pub fn send_command(&mut self, command: impl Into<u8>, args: &[u8]) -> Result {
+        let command = command.into();
+        self.state.stats.command(command, args.len());
+        if let Some(observer) = self.state.observer {
+            observer.command(command, args.len());
+        }
+        self.interface.send_commands(U8Iter(&mut once(command)))?;
         self.interface.send_data(U8Iter(&mut args.iter().cloned()))
     }
 
-    fn write_iter<I: IntoIterator<Item = u16>>(&mut self, data: I) -> Result {
+    fn write_iter<I: IntoIterator<Item = C::RawValue>>(&mut self, data: I) -> Result {
+        self.write_chunked(data, |_| true).map(drop)
+    }
+
+    /// Write pixels split into chunks of the maximum transfer size
+    ///
+    /// `next_chunk` is called with the number of pixels written before each
+    /// chunk after the first one, the transfer stops when it returns false.
+    /// Returns the number of pixels written.
+    fn write_chunked<I, F>(&mut self, data: I, mut next_chunk: F) -> Result<usize>
+    where
+        I: IntoIterator<Item = C::RawValue>,
+        F: FnMut(usize) -> bool,
+    {
         self.command(Command::MemoryWrite, &[])?;
-        self.interface.send_data(U16BEIter(&mut data.into_iter()))
+        let Some(bytes) = self.transfer_limit() else {
+            let mut written = 0;
+            pixel_format::send_pixels::<C, _, _>(
+                &mut self.interface,
+                self.state.init.endian,
+                data.into_iter().inspect(|_| written += 1),
+            )?;
+            self.pixels_sent(C::data_len(written));
+            self.state.timing.transfer_end();
+            return Ok(written);
+        };
+        let chunk = (bytes / C::BYTES_PER_PACK).max(1) * C::PIXELS_PER_PACK;
+        let mut data = data.into_iter().peekable();
+        let mut written = 0;
+        while data.peek().is_some() {
+            if written > 0 {
+                self.between_chunks();
+                if !next_chunk(written) {
+                    break;
+                }
+            }
+            let mut sent = 0;
+            pixel_format::send_pixels::<C, _, _>(
+                &mut self.interface,
+                self.state.init.endian,
+                data.by_ref().take(chunk).inspect(|_| sent += 1),
+            )?;
+            self.pixels_sent(C::data_len(sent));
+            written += sent;
+        }
+        self.state.timing.transfer_end();
+        Ok(written)
+    }
+
+    /// Write pixel data in the big endian byte order of the pixel format,
+    /// split on whole pixels like [Self::write_chunked]
+    ///
+    /// 16 bit words are swapped for a little endian [PixelEndian].
+    pub(crate) fn write_pixel_bytes(&mut self, bytes: &[u8]) -> Result {
+        let chunk = self
+            .transfer_limit()
+            .map_or(bytes.len(), |max| max - max % C::BYTES_PER_PACK)
+            .max(C::BYTES_PER_PACK);
+        for (n, chunk) in bytes.chunks(chunk).enumerate() {
+            if n > 0 {
+                self.between_chunks();
+            }
+            if self.state.init.endian == PixelEndian::Big || C::BYTES_PER_PACK != 2 {
+                self.interface.send_data(U8(chunk))?;
+            } else {
+                let mut swapped = chunk.chunks_exact(2).flat_map(|word| [word[1], word[0]]);
+                self.interface.send_data(U8Iter(&mut swapped))?;
+            }
+            self.pixels_sent(chunk.len());
+        }
+        self.state.timing.transfer_end();
+        Ok(())
     }
 
     fn set_window(&mut self, x0: u16, y0: u16, x1: u16, y1: u16) -> Result {
+        let full_screen = x0 == 0
+            && y0 == 0
+            && x1 as usize + 1 == self.state.width
+            && y1 as usize + 1 == self.state.height;
+        self.state.stats.window(full_screen);
+        self.state.timing.window(full_screen);
+        let (column, row) = self.state.init.offset;
+        let (x0, x1, y0, y1) = (x0 + column, x1 + column, y0 + row, y1 + row);
         self.command(
             Command::ColumnAddressSet,
             &[
@@ -311,60 +599,17 @@ where
         )
     }
 
-    // /// Configures the screen for hardware-accelerated vertical scrolling.
-    // pub fn configure_vertical_scroll(
-    //     &mut self,
-    //     fixed_top_lines: u16,
-    //     fixed_bottom_lines: u16,
-    // ) -> Result<Scroller> {
-    //     let height = if self.landscape {
-    //         self.width
-    //     } else {
-    //         self.height
-    //     } as u16;
-    //     let scroll_lines = height as u16 - fixed_top_lines - fixed_bottom_lines;
-
-    //     self.command(
-    //         Command::VerticalScrollDefine,
-    //         &[
-    //             (fixed_top_lines >> 8) as u8,
-    //             (fixed_top_lines & 0xff) as u8,
-    //             (scroll_lines >> 8) as u8,
-    //             (scroll_lines & 0xff) as u8,
-    //             (fixed_bottom_lines >> 8) as u8,
-    //             (fixed_bottom_lines & 0xff) as u8,
-    //         ],
-    //     )?;
-
-    //     Ok(Scroller::new(fixed_top_lines, fixed_bottom_lines, height))
-    // }
-
-    // pub fn scroll_vertically(&mut self, scroller: &mut Scroller, num_lines: u16) -> Result {
-    //     scroller.top_offset += num_lines;
-    //     if scroller.top_offset > (scroller.height - scroller.fixed_bottom_lines) {
-    //         scroller.top_offset = scroller.fixed_top_lines
-    //             + (scroller.top_offset + scroller.fixed_bottom_lines - scroller.height)
-    //     }
-
-    //     self.command(
-    //         Command::VerticalScrollAddr,
-    //         &[
-    //             (scroller.top_offset >> 8) as u8,
-    //             (scroller.top_offset & 0xff) as u8,
-    //         ],
-    //     )
-    // }
-
     /// Draw a rectangle on the screen, represented by top-left corner (x0, y0)
     /// and bottom-right corner (x1, y1).
     ///
     /// The border is included.
     ///
-    /// This method accepts an iterator of rgb565 pixel values.
+    /// This method accepts an iterator of raw pixel values in the current
+    /// pixel format.
     ///
     /// The iterator is useful to avoid wasting memory by holding a buffer for
     /// the whole screen when it is not necessary.
-    pub fn draw_raw_iter<I: IntoIterator<Item = u16>>(
+    pub fn draw_raw_iter<I: IntoIterator<Item = C::RawValue>>(
         &mut self,
         x0: u16,
         y0: u16,
@@ -376,24 +621,183 @@ where
         self.write_iter(data)
     }
 
+    /// Set the drawing window and start a memory write
+    ///
+    /// The following data bytes fill the window, for example a DMA transfer
+    /// on the bus with the data/command pin high.
+    pub fn start_memory_write(&mut self, x0: u16, y0: u16, x1: u16, y1: u16) -> Result {
+        self.set_window(x0, y0, x1, y1)?;
+        self.command(Command::MemoryWrite, &[])
+    }
+
+    /// Like [Self::draw_raw_iter], stopping early when `should_cancel`
+    /// returns true
+    ///
+    /// `should_cancel` is polled between the chunks of
+    /// [Self::set_max_transfer_size]. Returns the number of pixels written.
+    pub fn draw_raw_iter_cancellable<I, F>(
+        &mut self,
+        x0: u16,
+        y0: u16,
+        x1: u16,
+        y1: u16,
+        data: I,
+        mut should_cancel: F,
+    ) -> Result<usize>
+    where
+        I: IntoIterator<Item = C::RawValue>,
+        F: FnMut() -> bool,
+    {
+        self.set_window(x0, y0, x1, y1)?;
+        self.write_chunked(data, |_| !should_cancel())
+    }
+
+    /// Like [Self::draw_raw_iter], reporting the progress of the transfer
+    ///
+    /// `progress` is called with the number of pixels written and the number
+    /// of pixels in the window, after each chunk of
+    /// [Self::set_max_transfer_size].
+    pub fn draw_raw_iter_with_progress<I, F>(
+        &mut self,
+        x0: u16,
+        y0: u16,
+        x1: u16,
+        y1: u16,
+        data: I,
+        mut progress: F,
+    ) -> Result
+    where
+        I: IntoIterator<Item = C::RawValue>,
+        F: FnMut(usize, usize),
+    {
+        let total = (x1.saturating_sub(x0) as usize + 1) * (y1.saturating_sub(y0) as usize + 1);
+        self.set_window(x0, y0, x1, y1)?;
+        let written = self.write_chunked(data, |written| {
+            progress(written, total);
+            true
+        })?;
+        progress(written, total);
+        Ok(())
+    }
+
+    /// Switch to a different pixel format, changing the type of colors
+    /// accepted by the driver
+    pub fn into_pixel_format<C2: PixelFormat>(mut self) -> Result<Ili9342C<IFACE, C2>> {
+        self.command(Command::PixelFormatSet, &[C2::PIXEL_FORMAT_SET])?;
+        let (interface, state) = self.release();
+        Ok(Ili9342C::from_parts(interface, state))
+    }
+
+    /// Limit the number of bytes sent per interface call
+    ///
+    /// Pixel data is split into interface calls of at most this many bytes,
+    /// rounded down to whole pixels. `None` sends each drawing operation in a
+    /// single call, which is the default.
+    pub fn set_max_transfer_size(&mut self, bytes: Option<usize>) {
+        self.state.max_transfer = bytes;
+    }
+
+    /// Maximum number of bytes sent per interface call
+    pub fn max_transfer_size(&self) -> Option<usize> {
+        self.state.max_transfer
+    }
+
+    /// Change the byte order of the pixel data, see [Builder::pixel_endian]
+    pub fn set_pixel_endian(&mut self, endian: PixelEndian) -> Result {
+        self.state.init.endian = endian;
+        let args = self.state.init.interface_ctrl();
+        self.command(Command::InterfaceCtrl, &args)
+    }
+
+    /// Byte order of the pixel data
+    pub fn pixel_endian(&self) -> PixelEndian {
+        self.state.init.endian
+    }
+
+    /// Register a function called between the interface calls of a transfer
+    ///
+    /// Long transfers are only split with [Self::set_max_transfer_size] or
+    /// [Self::set_watchdog_hook], the hook lets cooperative schedulers run in
+    /// between.
+    pub fn set_yield_hook(&mut self, hook: Option<fn()>) {
+        self.state.yield_hook = hook;
+    }
+
     /// Change the orientation of the screen
     pub fn set_orientation<MODE>(&mut self, mode: MODE) -> Result
     where
         MODE: Mode,
     {
-        self.command(Command::MemoryAccessControl, &[mode.mode()])?;
+        let before = self.orientation_state();
+        let (x, y) = self.mirror();
+        let mirror = Self::mirror_bits(mode.mode(), x, y);
+        let madctl = self.state.init.madctl(&mode);
+        self.command(
+            Command::MemoryAccessControl,
+            &[madctl ^ mirror ^ self.state.refresh],
+        )?;
+        self.state.mirror = mirror;
+        self.update_orientation(&mode);
+        self.orientation_changed(before);
+        Ok(())
+    }
 
-        if self.landscape ^ mode.is_landscape() {
-            core::mem::swap(&mut self.height, &mut self.width);
+    /// Update the screen dimensions after the orientation was changed
+    fn update_orientation<MODE: Mode>(&mut self, mode: &MODE) {
+        if self.state.landscape ^ mode.is_landscape() {
+            core::mem::swap(&mut self.state.height, &mut self.state.width);
         }
-        self.landscape = mode.is_landscape();
-        Ok(())
+        self.state.landscape = mode.is_landscape();
+    }
+
+    /// Fill entire screen with specfied raw color value
+    pub fn clear_screen(&mut self, color: C::RawValue) -> Result {
+        let color = core::iter::repeat_n(color, self.state.width * self.state.height);
+        self.draw_raw_iter(
+            0,
+            0,
+            self.state.width as u16 - 1,
+            self.state.height as u16 - 1,
+            color,
+        )
+    }
+
+    /// Like [Self::clear_screen], stopping early when `should_cancel` returns
+    /// true, see [Self::draw_raw_iter_cancellable]
+    pub fn clear_screen_cancellable<F>(
+        &mut self,
+        color: C::RawValue,
+        should_cancel: F,
+    ) -> Result<usize>
+    where
+        F: FnMut() -> bool,
+    {
+        let color = core::iter::repeat_n(color, self.state.width * self.state.height);
+        self.draw_raw_iter_cancellable(
+            0,
+            0,
+            self.state.width as u16 - 1,
+            self.state.height as u16 - 1,
+            color,
+            should_cancel,
+        )
     }
 
-    /// Fill entire screen with specfied color u16 value
-    pub fn clear_screen(&mut self, color: u16) -> Result {
-        let color = core::iter::repeat(color).take(self.width * self.height);
-        self.draw_raw_iter(0, 0, self.width as u16, self.height as u16, color)
+    /// Like [Self::clear_screen], reporting the progress of the transfer, see
+    /// [Self::draw_raw_iter_with_progress]
+    pub fn clear_screen_with_progress<F>(&mut self, color: C::RawValue, progress: F) -> Result
+    where
+        F: FnMut(usize, usize),
+    {
+        let color = core::iter::repeat_n(color, self.state.width * self.state.height);
+        self.draw_raw_iter_with_progress(
+            0,
+            0,
+            self.state.width as u16 - 1,
+            self.state.height as u16 - 1,
+            color,
+            progress,
+        )
     }
 
     /// Control the screen sleep mode:
@@ -404,6 +808,24 @@ where
         }
     }
 
+    /// Enter sleep mode, waiting until the panel accepts the next command
+    ///
+    /// Unlike [Self::sleep_mode] this waits 120 ms, so a following [Self::wake]
+    /// can never violate the minimum time between Sleep In and Sleep Out.
+    pub fn sleep<DELAY: DelayMs<u16>>(&mut self, delay: &mut DELAY) -> Result {
+        self.sleep_mode(ModeState::On)?;
+        delay.delay_ms(120);
+        Ok(())
+    }
+
+    /// Leave sleep mode, waiting the 120 ms the panel needs before it accepts
+    /// most commands
+    pub fn wake<DELAY: DelayMs<u16>>(&mut self, delay: &mut DELAY) -> Result {
+        self.sleep_mode(ModeState::Off)?;
+        delay.delay_ms(120);
+        Ok(())
+    }
+
     /// Control the screen display mode
     pub fn display_mode(&mut self, mode: ModeState) -> Result {
         match mode {
@@ -411,58 +833,1821 @@ where
             ModeState::Off => self.command(Command::DisplayOff, &[]),
         }
     }
+
+    /// Control the screen idle mode, which only shows 8 colors and uses the
+    /// idle mode frame rate to save power
+    pub fn idle_mode(&mut self, mode: ModeState) -> Result {
+        match mode {
+            ModeState::On => self.command(Command::IdleModeOn, &[]),
+            ModeState::Off => self.command(Command::IdleModeOff, &[]),
+        }
+    }
 }
 
-impl<IFACE> Ili9342C<IFACE> {
+impl<IFACE, C> Ili9342C<IFACE, C> {
+    /// Get a [DrawTarget] accepting 24-bit colors, see [Rgb888Adapter]
+    pub fn as_rgb888(&mut self) -> Rgb888Adapter<'_, Self> {
+        Rgb888Adapter::new(self)
+    }
+
+    /// Get a [DrawTarget] accepting grayscale colors, see [Gray8Adapter]
+    pub fn as_gray8(&mut self) -> Gray8Adapter<'_, Self> {
+        Gray8Adapter::new(self)
+    }
+
     /// Get the current screen width. It can change based on the current orientation
     pub fn width(&self) -> usize {
-        self.width
+        self.state.width
     }
 
     /// Get the current screen heighth. It can change based on the current orientation
     pub fn height(&self) -> usize {
-        self.height
+        self.state.height
     }
 }
 
-#[derive(Clone, Copy)]
-#[allow(dead_code)]
-enum Command {
+/// Registers of the ILI9342C, for [Ili9342C::send_command]
+///
+/// Commands are in address order. The level 1 commands are the MIPI DCS ones
+/// up to [Command::ReadCabcMinimumBrightness] and [Command::ReadId1] to
+/// [Command::ReadId3]. The other commands from [Command::RBGInterface] on are
+/// extension commands, only accepted after [Command::ExtC] was sent with
+/// `0xff, 0x93, 0x42`, as the init sequence does.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Command {
+    /// No operation
+    Nop = 0x00,
     SoftwareReset = 0x01,
+    /// Read Display Identification Information
+    ReadDisplayId = 0x04,
+    ReadDisplayStatus = 0x09,
+    ReadPowerMode = 0x0a,
+    ReadMemoryAccessControl = 0x0b,
+    ReadPixelFormat = 0x0c,
+    ReadImageFormat = 0x0d,
+    ReadSignalMode = 0x0e,
+    ReadSelfDiagnostic = 0x0f,
+    /// Sleep In
     SleepModeOn = 0x10,
+    /// Sleep Out
     SleepModeOff = 0x11,
+    PartialModeOn = 0x12,
+    /// Normal Display Mode On, leaving partial mode
+    NormalModeOn = 0x13,
     InvertOff = 0x20,
     InvertOn = 0x21,
+    GammaSet = 0x26,
     DisplayOff = 0x28,
     DisplayOn = 0x29,
     ColumnAddressSet = 0x2a,
     PageAddressSet = 0x2b,
     MemoryWrite = 0x2c,
-    PixelFormatSet = 0x3a,
+    /// Color Set, the lookup table of the 16 and 12 bit formats
+    ColorSet = 0x2d,
+    MemoryRead = 0x2e,
+    PartialArea = 0x30,
     VerticalScrollDefine = 0x33,
+    TearingEffectOff = 0x34,
+    TearingEffectOn = 0x35,
     MemoryAccessControl = 0x36,
     VerticalScrollAddr = 0x37,
     IdleModeOff = 0x38,
     IdleModeOn = 0x39,
+    PixelFormatSet = 0x3a,
+    WriteMemoryContinue = 0x3c,
+    ReadMemoryContinue = 0x3e,
+    SetTearScanline = 0x44,
+    GetScanline = 0x45,
+    /// Write Display Brightness
     SetBrightness = 0x51,
+    ReadBrightness = 0x52,
+    WriteCtrlDisplay = 0x53,
+    ReadCtrlDisplay = 0x54,
+    /// Write Content Adaptive Brightness Control
     ContentAdaptiveBrightness = 0x55,
+    ReadContentAdaptiveBrightness = 0x56,
+    WriteCabcMinimumBrightness = 0x5e,
+    ReadCabcMinimumBrightness = 0x5f,
+    /// RGB Interface Signal Control
     RBGInterface = 0xb0,
+    /// Frame Rate Control in normal mode
     FrameControl = 0xb1,
+    /// Frame Rate Control in idle mode
     IdleModeFrameRate = 0xb2,
+    /// Frame Rate Control in partial mode
+    PartialModeFrameRate = 0xb3,
+    DisplayInversionControl = 0xb4,
+    BlankingPorchControl = 0xb5,
     DisplayFunctionControl = 0xb6,
+    EntryModeSet = 0xb7,
+    BacklightControl1 = 0xb8,
+    BacklightControl2 = 0xb9,
+    BacklightControl3 = 0xba,
+    BacklightControl4 = 0xbb,
+    BacklightControl5 = 0xbc,
+    BacklightControl6 = 0xbd,
+    BacklightControl7 = 0xbe,
+    BacklightControl8 = 0xbf,
     PowerControl1 = 0xc0,
     PowerControl2 = 0xc1,
+    /// Power Control 3, for normal mode
+    PowerControl3 = 0xc2,
+    /// Power Control 4, for idle mode
+    PowerControl4 = 0xc3,
+    /// Power Control 5, for partial mode
+    PowerControl5 = 0xc4,
+    VcomControl1 = 0xc5,
+    VcomControl2 = 0xc7,
+    /// Set EXTC, enabling the extension commands
     ExtC = 0xc8,
+    PowerControlA = 0xcb,
+    PowerControlB = 0xcf,
+    NvMemoryWrite = 0xd0,
+    NvMemoryProtectionKey = 0xd1,
+    NvMemoryStatusRead = 0xd2,
+    ReadId4 = 0xd3,
+    ReadId1 = 0xda,
+    ReadId2 = 0xdb,
+    ReadId3 = 0xdc,
     GammaControlPos1 = 0xe0,
     GammaControlNeg1 = 0xe1,
+    DigitalGammaControl1 = 0xe2,
+    DigitalGammaControl2 = 0xe3,
+    DriverTimingControlA = 0xe8,
+    DriverTimingControlB = 0xea,
+    PowerOnSequenceControl = 0xed,
+    Enable3G = 0xf2,
     InterfaceCtrl = 0xf6,
+    PumpRatioControl = 0xf7,
+}
+
+impl From<Command> for u8 {
+    fn from(command: Command) -> Self {
+        command as u8
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    extern crate std;
+
+    use std::{fmt::Write as _, string::String};
+
+    use embedded_graphics_core::{pixelcolor::Rgb565, prelude::*, Pixel};
+    use embedded_hal_0_2::blocking::delay::DelayMs;
+
+    use super::*;
+
+    pub(crate) struct NoDelay;
+
+    impl DelayMs<u16> for NoDelay {
+        fn delay_ms(&mut self, _ms: u16) {}
+    }
+
+    /// One line per command or data transfer, as hex bytes
+    pub(crate) fn format(recorder: &CommandRecorder) -> String {
+        let mut text = String::new();
+        for entry in recorder.entries() {
+            let (kind, bytes) = match entry {
+                Recorded::Command(bytes) => ('C', bytes),
+                Recorded::Data(bytes) => ('D', bytes),
+            };
+            text.push(kind);
+            for byte in bytes {
+                write!(text, " {byte:02x}").unwrap();
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Initialized display with the init removed from the recorder
+    fn blank(buffer: &mut [u8]) -> Ili9342C<CommandRecorder<'_>> {
+        let mut display = Ili9342C::new(
+            CommandRecorder::new(buffer),
+            &mut NoDelay,
+            Orientation::Landscape,
+            DisplaySize320x240,
+        )
+        .unwrap();
+        display.interface.clear();
+        display
+    }
+
+    /// Record the stream of a function working on an initialized display, the
+    /// init itself is only recorded if `with_init` is set
+    pub(crate) fn record<'b>(
+        buffer: &'b mut [u8],
+        with_init: bool,
+        f: impl FnOnce(&mut Ili9342C<CommandRecorder<'b>>),
+    ) -> CommandRecorder<'b> {
+        let mut display = Ili9342C::new(
+            CommandRecorder::new(buffer),
+            &mut NoDelay,
+            Orientation::Landscape,
+            DisplaySize320x240,
+        )
+        .unwrap();
+        if !with_init {
+            display.interface.clear();
+        }
+        f(&mut display);
+        display.into_inner()
+    }
+
     #[test]
     fn it_works() {
         let result = 2 + 2;
         assert_eq!(result, 4);
     }
+
+    #[test]
+    fn indexed_framebuffer_packs_nibbles() {
+        let mut buffer = [0u8; 2];
+        let mut fb = IndexedFramebuffer4::new(&mut buffer, 3, 1).unwrap();
+        fb.set(0, 0, PaletteIndex(0x1));
+        fb.set(1, 0, PaletteIndex(0x2));
+        fb.set(2, 0, PaletteIndex(0xf));
+        assert_eq!(fb.get(1, 0), Some(PaletteIndex(0x2)));
+        assert_eq!(fb.get(3, 0), None);
+        assert_eq!(buffer, [0x12, 0xf0]);
+        assert!(IndexedFramebuffer4::new(&mut buffer, 0, 1).is_err());
+        assert!(Rgb565Framebuffer::new(&mut [0u16; 4], 2, 0).is_err());
+    }
+
+    #[test]
+    fn rgb444_packs_two_pixels_in_three_bytes() {
+        let mut buffer = [0u8; 16];
+        let mut recorder = CommandRecorder::new(&mut buffer);
+        let pixels = [0x123, 0x456, 0xabc];
+        Rgb444::send_pixels(&mut recorder, pixels.into_iter()).unwrap();
+        let entries = [Recorded::Data(&[0x12, 0x34, 0x56, 0xab, 0xc0])];
+        assert!(recorder.entries().eq(entries));
+        assert_eq!(Rgb444::data_len(pixels.len()), 5);
+    }
+
+    #[test]
+    fn easing_curves_end_at_targets() {
+        for easing in [
+            Easing::Linear,
+            Easing::EaseIn,
+            Easing::EaseOut,
+            Easing::EaseInOut,
+        ] {
+            assert_eq!(easing.interpolate(-120, 40, 0.0), -120);
+            assert_eq!(easing.interpolate(-120, 40, 1.0), 40);
+        }
+        assert_eq!(Easing::EaseInOut.interpolate(0, 100, 0.5), 50);
+        assert_eq!(Easing::EaseIn.interpolate(0, 100, 0.5), 25);
+    }
+
+    #[test]
+    fn default_display_function_matches_vendor_init() {
+        assert_eq!(
+            DisplayFunctionConfig::default().to_bytes(),
+            [0x08, 0x82, 0x27]
+        );
+    }
+
+    #[test]
+    fn recorder_replays_stream() {
+        let mut buffer = [0u8; 32];
+        let mut recorder = CommandRecorder::new(&mut buffer);
+        recorder.send_commands(U8Iter(&mut once(0x2c))).unwrap();
+        recorder
+            .send_data(display_interface::DataFormat::U16BEIter(
+                &mut [0x1234u16, 0xabcd].into_iter(),
+            ))
+            .unwrap();
+        let entries = [
+            Recorded::Command(&[0x2c]),
+            Recorded::Data(&[0x12, 0x34, 0xab, 0xcd]),
+        ];
+        assert!(recorder.entries().eq(entries));
+
+        let mut copy = [0u8; 32];
+        let mut replayed = CommandRecorder::new(&mut copy);
+        recorder.replay(&mut replayed).unwrap();
+        assert_eq!(replayed.as_bytes(), recorder.as_bytes());
+
+        let mut serialized = [0u8; 36];
+        let mut len = 0;
+        recorder.serialize(&mut |chunk: &[u8]| {
+            serialized[len..len + chunk.len()].copy_from_slice(chunk);
+            len += chunk.len();
+        });
+        let stream = RecordedStream::from_bytes(&serialized[..len]).unwrap();
+        assert!(stream.entries().eq(entries));
+        assert!(RecordedStream::from_bytes(&serialized[..len - 1]).is_err());
+    }
+
+    #[test]
+    fn color_temperature_gains() {
+        assert_eq!(gamma::white_point_gains(6500), [255, 255, 255]);
+        // 3000K is 255/180/107 against 255/249/253, blue is 58% of green
+        assert_eq!(gamma::white_point_gains(3000), [255, 255, 148]);
+        // 9000K is 214/225/255, red is 93% of green
+        assert_eq!(gamma::white_point_gains(9000), [237, 255, 255]);
+        assert_eq!(
+            DigitalGammaTables::white_point(255, 255),
+            DigitalGammaTables::NEUTRAL
+        );
+    }
+
+    #[test]
+    fn identity_color_lut() {
+        use embedded_graphics_core::pixelcolor::Rgb888;
+        for value in 0..=255u8 {
+            let color = Rgb888::new(value, value, value);
+            assert_eq!(ColorLut::IDENTITY.apply(color), color);
+        }
+        assert_eq!(
+            ColorLut::IDENTITY.digital_gamma(),
+            DigitalGammaTables::NEUTRAL
+        );
+    }
+
+    #[test]
+    fn splash_rle_decodes_runs() {
+        let image = [2, 0xf8, 0x00, 1, 0x00, 0x1f, 3];
+        assert!(splash::decode_rle(&image).eq([0xf800, 0xf800, 0x001f]));
+    }
+
+    #[test]
+    fn splash_length_must_match_the_screen() {
+        let mut buffer = std::vec![0u8; 1 << 20];
+        let mut display = blank(&mut buffer);
+        let raw = std::vec![0x12u8; 320 * 240 * 2].leak();
+        let short: &'static [u8] = &raw[1..];
+        assert!(matches!(
+            display.show_splash(short, ImageFormat::Rgb565),
+            Err(DisplayError::OutOfBoundsError)
+        ));
+        // 301 full runs and one of 45 pixels cover the screen
+        let mut rle = std::vec::Vec::new();
+        for _ in 0..301 {
+            rle.extend_from_slice(&[255, 0xf8, 0x00]);
+        }
+        rle.extend_from_slice(&[45, 0x00, 0x1f]);
+        let rle = rle.leak();
+        let short: &'static [u8] = &rle[3..];
+        assert!(matches!(
+            display.show_splash(short, ImageFormat::Rgb565Rle),
+            Err(DisplayError::OutOfBoundsError)
+        ));
+        assert_eq!(display.interface.entries().count(), 0);
+
+        display.show_splash(raw, ImageFormat::Rgb565).unwrap();
+        display.show_splash(rle, ImageFormat::Rgb565Rle).unwrap();
+        assert_eq!(windows(&display.interface), [[0, 319, 0, 239]; 2]);
+        let pixels = pixels(&display.interface);
+        assert!(pixels[0].iter().all(|&byte| byte == 0x12));
+        assert_eq!(pixels[1].len(), 320 * 240 * 2);
+        assert!(pixels[1][..301 * 255 * 2]
+            .chunks(2)
+            .all(|pixel| pixel == [0xf8, 0x00]));
+        assert!(pixels[1][301 * 255 * 2..]
+            .chunks(2)
+            .all(|pixel| pixel == [0x00, 0x1f]));
+    }
+
+    #[test]
+    fn test_pattern_colors() {
+        use embedded_graphics_core::pixelcolor::Rgb888;
+
+        let bars = |x, width| Pattern::ColorBars.color(x, 0, width, 240);
+        assert_eq!(bars(0, 320), Rgb888::WHITE);
+        assert_eq!(bars(39, 320), Rgb888::WHITE);
+        assert_eq!(bars(40, 320), Rgb888::YELLOW);
+        assert_eq!(bars(279, 320), Rgb888::BLUE);
+        assert_eq!(bars(280, 320), Rgb888::BLACK);
+        assert_eq!(bars(319, 320), Rgb888::BLACK);
+        // One pixel per bar
+        assert_eq!(bars(2, 8), Rgb888::CYAN);
+
+        let gray = |x, width| Pattern::GrayRamp.color(x, 0, width, 240);
+        assert_eq!(gray(0, 1), Rgb888::BLACK);
+        assert_eq!(gray(0, 320), Rgb888::BLACK);
+        assert_eq!(gray(319, 320), Rgb888::WHITE);
+
+        let alignment = |x, y| Pattern::Alignment.color(x, y, 320, 240);
+        assert_eq!(alignment(0, 0), Rgb888::WHITE);
+        assert_eq!(alignment(1, 1), Rgb888::RED);
+        assert_eq!(alignment(318, 1), Rgb888::GREEN);
+        assert_eq!(alignment(1, 238), Rgb888::BLUE);
+        assert_eq!(alignment(318, 238), Rgb888::YELLOW);
+        assert_eq!(alignment(160, 100), Rgb888::WHITE);
+        assert_eq!(alignment(100, 100), Rgb888::BLACK);
+    }
+
+    #[test]
+    fn screensaver_dims_then_turns_off() {
+        let mut buffer = [0u8; 1024];
+        let mut display = blank(&mut buffer);
+        let sent = |display: &mut Ili9342C<CommandRecorder<'_>>| {
+            let sent = commands(&display.interface);
+            display.interface.clear();
+            sent
+        };
+        let mut saver = Screensaver::new(255, 30, 100, 200);
+
+        saver.tick(&mut display, 99).unwrap();
+        assert_eq!(saver.state(), ScreensaverState::Active);
+        assert!(sent(&mut display).is_empty());
+        saver.tick(&mut display, 1).unwrap();
+        assert_eq!(saver.state(), ScreensaverState::Dimmed);
+        assert_eq!(sent(&mut display), [(0x51, std::vec![30])]);
+        saver.tick(&mut display, 50).unwrap();
+        assert!(sent(&mut display).is_empty());
+
+        // Activity while dimmed only restores the brightness
+        saver.notify_activity(&mut display, &mut NoDelay).unwrap();
+        assert_eq!(
+            (saver.state(), saver.idle_ms()),
+            (ScreensaverState::Active, 0)
+        );
+        assert_eq!(sent(&mut display), [(0x51, std::vec![255])]);
+        saver.notify_activity(&mut display, &mut NoDelay).unwrap();
+        assert!(sent(&mut display).is_empty());
+
+        // A long tick goes straight to off
+        saver.tick(&mut display, 200).unwrap();
+        assert_eq!(saver.state(), ScreensaverState::Off);
+        assert_eq!(
+            sent(&mut display),
+            [
+                (0x51, std::vec![0]),
+                (0x28, std::vec![]),
+                (0x10, std::vec![])
+            ]
+        );
+        saver.tick(&mut display, 1000).unwrap();
+        assert!(sent(&mut display).is_empty());
+
+        saver.notify_activity(&mut display, &mut NoDelay).unwrap();
+        assert_eq!(saver.state(), ScreensaverState::Active);
+        assert_eq!(
+            sent(&mut display),
+            [
+                (0x11, std::vec![]),
+                (0x29, std::vec![]),
+                (0x51, std::vec![255])
+            ]
+        );
+    }
+
+    #[test]
+    fn bmp_rows_are_padded() {
+        let mut bytes = [0u8; 128];
+        let mut len = 0;
+        let pixels = [Rgb565::RED, Rgb565::GREEN, Rgb565::BLUE];
+        encode_bmp(3, 2, pixels, &mut |chunk: &[u8]| {
+            bytes[len..len + chunk.len()].copy_from_slice(chunk);
+            len += chunk.len();
+        });
+        // 66 byte header, two rows of 3 pixels padded to 8 bytes
+        assert_eq!(len, 82);
+        assert_eq!(&bytes[..2], b"BM");
+        assert_eq!(u32::from_le_bytes(bytes[2..6].try_into().unwrap()), 82);
+        assert_eq!(&bytes[66..74], &[0x00, 0xf8, 0xe0, 0x07, 0x1f, 0x00, 0, 0]);
+        // Missing pixels are black
+        assert!(bytes[74..82].iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn crc32_check_value() {
+        let mut crc = Crc32::new();
+        crc.update(b"1234");
+        crc.update(b"56789");
+        assert_eq!(crc.finish(), 0xcbf43926);
+    }
+
+    #[cfg(feature = "simulator")]
+    #[test]
+    fn simulator_decodes_stream() {
+        use embedded_graphics_core::pixelcolor::Rgb888;
+
+        let mut display = Ili9342C::<_, Rgb565>::new(
+            crate::Simulator::new(),
+            &mut NoDelay,
+            Orientation::Portrait,
+            DisplaySize320x240,
+        )
+        .unwrap();
+        display
+            .fill_solid(
+                &Rectangle::new(Point::new(10, 300), Size::new(2, 2)),
+                Rgb565::RED,
+            )
+            .unwrap();
+        let simulator = display.into_inner();
+        assert_eq!(simulator.size(), Size::new(240, 320));
+        assert_eq!(simulator.pixel(11, 301), Some(Rgb888::RED));
+        assert_eq!(simulator.pixel(12, 301), Some(Rgb888::BLACK));
+    }
+
+    #[cfg(feature = "mock")]
+    #[test]
+    fn self_test_reads_id() {
+        use crate::MockInterface;
+
+        let mut iface = MockInterface::new();
+        iface.respond(0xd3, &[0xff, 0x00, 0x93, 0x42]);
+        let mut display = Ili9342C::<_, Rgb565>::new(
+            iface,
+            &mut NoDelay,
+            Orientation::Portrait,
+            DisplaySize320x240,
+        )
+        .unwrap();
+        let report = display.self_test_with_id(&mut NoDelay).unwrap();
+        assert_eq!(report.id, Some([0x00, 0x93, 0x42]));
+        assert!(!report.fills_within(u64::MAX));
+        // The init sequence is repeated in the current orientation
+        let commands = display.into_inner().commands();
+        let madctl: alloc::vec::Vec<_> = commands.iter().filter(|(cmd, _)| *cmd == 0x36).collect();
+        assert_eq!(madctl.len(), 2);
+        assert_eq!(madctl[0], madctl[1]);
+    }
+
+    #[cfg(feature = "mock")]
+    #[test]
+    fn probe_detects_controller() {
+        use crate::{probe, Controller, MockInterface};
+
+        let mut iface = MockInterface::new();
+        iface.respond(0xd3, &[0x00, 0x00, 0x93, 0x41]);
+        assert_eq!(probe(&mut iface).unwrap(), Controller::Ili9341);
+        iface.respond(0xd3, &[0x00, 0x12, 0x34, 0x56]);
+        assert_eq!(
+            probe(&mut iface).unwrap(),
+            Controller::Unknown([0x12, 0x34, 0x56])
+        );
+    }
+
+    #[cfg(feature = "glyph-cache")]
+    #[test]
+    fn glyph_cache_writes_one_window_per_char() {
+        use embedded_graphics::mono_font::ascii::FONT_6X10;
+
+        use crate::GlyphCache;
+
+        let mut tiles = std::vec![0u8; GlyphCache::buffer_len(&FONT_6X10)];
+        let cache = GlyphCache::new(&FONT_6X10, Rgb565::WHITE, Rgb565::BLACK, &mut tiles).unwrap();
+        let mut buffer = [0u8; 512];
+        let mut end = Point::zero();
+        let recorder = record(&mut buffer, false, |display| {
+            end = cache.draw_text(display, "ab", Point::new(314, 0)).unwrap();
+        });
+        // The second character would leave the screen
+        assert_eq!(end, Point::new(326, 0));
+        let entries: std::vec::Vec<_> = recorder.entries().collect();
+        assert_eq!(entries.len(), 6);
+        assert_eq!(entries[1], Recorded::Data(&[0x01, 0x3a, 0x01, 0x3f]));
+        let Recorded::Data(pixels) = entries[5] else {
+            panic!("no pixel data");
+        };
+        assert_eq!(pixels.len(), 6 * 10 * 2);
+        // Glyphs have a blank top row and some lit pixels
+        assert!(pixels[..12].iter().all(|&b| b == 0));
+        assert!(pixels.contains(&0xff));
+    }
+
+    #[test]
+    fn bus_order_framebuffer_flushes_same_bytes() {
+        use crate::Rgb565Framebuffer;
+
+        let flush = |bus_order: bool| {
+            let mut pixels = [0u16; 6];
+            let mut framebuffer = if bus_order {
+                Rgb565Framebuffer::new_bus_order(&mut pixels, 3, 2)
+            } else {
+                Rgb565Framebuffer::new(&mut pixels, 3, 2)
+            }
+            .unwrap();
+            framebuffer.clear(Rgb565::BLUE).unwrap();
+            framebuffer.set(1, 1, Rgb565::new(0x12, 0x34, 0x05));
+            assert_eq!(framebuffer.get(1, 1), Some(Rgb565::new(0x12, 0x34, 0x05)));
+            let mut buffer = [0u8; 1024];
+            let recorder = record(&mut buffer, false, |display| {
+                framebuffer.flush(display).unwrap()
+            });
+            format(&recorder)
+        };
+        assert_eq!(flush(false), flush(true));
+    }
+
+    #[test]
+    fn idle_downshift_after_unchanged_flushes() {
+        use crate::{IdleDownshift, Rgb565Framebuffer};
+
+        let mut pixels = [0u16; 4];
+        let mut framebuffer = Rgb565Framebuffer::new(&mut pixels, 2, 2).unwrap();
+        let mut buffer = [0u8; 1024];
+        let recorder = record(&mut buffer, false, |display| {
+            display
+                .set_idle_downshift(Some((IdleDownshift::IdleMode, 2)))
+                .unwrap();
+            for _ in 0..4 {
+                framebuffer.flush(display).unwrap();
+            }
+            assert!(display.is_downshifted());
+            framebuffer.set(0, 0, Rgb565::RED);
+            framebuffer.flush(display).unwrap();
+            assert!(!display.is_downshifted());
+        });
+        let commands: std::vec::Vec<u8> = recorder
+            .entries()
+            .filter_map(|entry| match entry {
+                Recorded::Command(bytes) => Some(bytes[0]),
+                Recorded::Data(_) => None,
+            })
+            .collect();
+        assert_eq!(commands, [0x2a, 0x2b, 0x2c, 0x39, 0x2a, 0x2b, 0x38, 0x2c]);
+    }
+
+    #[cfg(feature = "eg-framebuffer")]
+    #[test]
+    fn eg_framebuffer_area_is_sent_row_by_row() {
+        use embedded_graphics_0_8::{
+            framebuffer::buffer_size,
+            geometry::Point as EgPoint,
+            pixelcolor::{Rgb565 as EgRgb565, RgbColor as _},
+        };
+
+        use crate::EgFramebuffer;
+
+        let mut framebuffer = EgFramebuffer::<4, 2, { buffer_size::<EgRgb565>(4, 2) }>::new();
+        framebuffer.set_pixel(EgPoint::new(1, 0), EgRgb565::RED);
+        framebuffer.set_pixel(EgPoint::new(2, 1), EgRgb565::BLUE);
+        let mut buffer = [0u8; 256];
+        let recorder = record(&mut buffer, false, |display| {
+            let area = Rectangle::new(Point::new(1, 0), Size::new(2, 2));
+            display
+                .flush_framebuffer_area(&framebuffer, Point::new(10, 20), area)
+                .unwrap();
+        });
+        let entries = [
+            Recorded::Command(&[0x2a]),
+            Recorded::Data(&[0, 11, 0, 12]),
+            Recorded::Command(&[0x2b]),
+            Recorded::Data(&[0, 20, 0, 21]),
+            Recorded::Command(&[0x2c]),
+            Recorded::Data(&[0xf8, 0x00, 0x00, 0x00]),
+            Recorded::Data(&[0x00, 0x00, 0x00, 0x1f]),
+        ];
+        assert!(recorder.entries().eq(entries), "{}", format(&recorder));
+    }
+
+    #[test]
+    fn render_scanlines_streams_each_row() {
+        let mut buffer = [0u8; 256];
+        let recorder = record(&mut buffer, false, |display| {
+            let area = Rectangle::new(Point::new(4, 6), Size::new(2, 2));
+            display
+                .render_scanlines(area, |y, row| {
+                    row[0] = y;
+                    row[1] = 0x100 | y;
+                })
+                .unwrap();
+        });
+        let entries = [
+            Recorded::Command(&[0x2a]),
+            Recorded::Data(&[0, 4, 0, 5]),
+            Recorded::Command(&[0x2b]),
+            Recorded::Data(&[0, 6, 0, 7]),
+            Recorded::Command(&[0x2c]),
+            Recorded::Data(&[0x00, 0x06, 0x01, 0x06, 0x00, 0x07, 0x01, 0x07]),
+        ];
+        assert!(recorder.entries().eq(entries), "{}", format(&recorder));
+    }
+
+    #[test]
+    fn run_coalescer_merges_adjacent_pixels() {
+        use crate::RunCoalescer;
+
+        let mut buffer = [0u8; 256];
+        let recorder = record(&mut buffer, false, |display| {
+            let mut target = RunCoalescer::<_, 4>::new(display);
+            let pixels = (0..5).map(|x| Pixel(Point::new(x, 3), Rgb565::new(0, 0, x as u8)));
+            target.draw_iter(pixels).unwrap();
+        });
+        let windows = recorder
+            .entries()
+            .filter(|entry| *entry == Recorded::Command(&[0x2c]))
+            .count();
+        assert_eq!(windows, 2);
+    }
+
+    #[test]
+    fn update_queue_carries_over_budget() {
+        use crate::{UpdateBudget, UpdateQueue};
+
+        let mut queue = UpdateQueue::<4>::new();
+        queue.push(Rectangle::new(Point::new(0, 0), Size::new(10, 10)));
+        queue.push(Rectangle::new(Point::new(2, 2), Size::new(4, 4)));
+        queue.push(Rectangle::new(Point::new(20, 0), Size::new(10, 10)));
+        assert_eq!(queue.len(), 2);
+
+        let mut buffer = [0u8; 1024];
+        record(&mut buffer, false, |display| {
+            let mut redrawn = 0;
+            let budget = UpdateBudget::Bytes(250);
+            let flushed = queue
+                .flush(display, budget, |display, area| {
+                    redrawn += 1;
+                    display.fill_solid(&area, Rgb565::RED)
+                })
+                .unwrap();
+            assert_eq!((flushed, redrawn), (1, 1));
+        });
+        assert_eq!(
+            queue.areas(),
+            [Rectangle::new(Point::new(20, 0), Size::new(10, 10))]
+        );
+    }
+
+    #[test]
+    fn update_queue_merges_when_full() {
+        use crate::UpdateQueue;
+
+        let mut queue = UpdateQueue::<2>::new();
+        queue.push(Rectangle::new(Point::new(0, 0), Size::new(10, 10)));
+        queue.push(Rectangle::new(Point::new(20, 0), Size::new(10, 10)));
+        // Covered by a queued rectangle
+        queue.push(Rectangle::new(Point::new(22, 2), Size::new(2, 2)));
+        assert_eq!(queue.len(), 2);
+        // No free slot, merged with the last one
+        queue.push(Rectangle::new(Point::new(25, 50), Size::new(10, 5)));
+        assert_eq!(
+            queue.areas(),
+            [
+                Rectangle::new(Point::new(0, 0), Size::new(10, 10)),
+                Rectangle::with_corners(Point::new(20, 0), Point::new(34, 54))
+            ]
+        );
+        // Covering a queued rectangle replaces it
+        queue.push(Rectangle::new(Point::new(0, 0), Size::new(12, 12)));
+        assert_eq!(
+            queue.areas(),
+            [
+                Rectangle::with_corners(Point::new(20, 0), Point::new(34, 54)),
+                Rectangle::new(Point::new(0, 0), Size::new(12, 12))
+            ]
+        );
+        // Empty rectangles are ignored
+        queue.clear();
+        queue.push(Rectangle::new(Point::new(5, 5), Size::zero()));
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn update_queue_flush_budget() {
+        use crate::{UpdateBudget, UpdateQueue};
+
+        let area = |x| Rectangle::new(Point::new(x, 0), Size::new(10, 10));
+        let mut buffer = [0u8; 4096];
+        let mut display = blank(&mut buffer);
+        let mut queue = UpdateQueue::<4>::new();
+        let mut flush = |queue: &mut UpdateQueue<4>, budget| {
+            queue
+                .flush(&mut display, budget, |display, area| {
+                    display.fill_solid(&area, Rgb565::RED)
+                })
+                .unwrap()
+        };
+        for x in [0, 20, 40] {
+            queue.push(area(x));
+        }
+        // 200 bytes per rectangle
+        assert_eq!(flush(&mut queue, UpdateBudget::Bytes(400)), 2);
+        assert_eq!(queue.areas(), [area(40)]);
+        // At least one rectangle per flush
+        queue.push(area(60));
+        assert_eq!(flush(&mut queue, UpdateBudget::Bytes(0)), 1);
+        assert_eq!(queue.areas(), [area(60)]);
+        // Without a clock the time budget is never used up
+        queue.push(area(80));
+        assert_eq!(flush(&mut queue, UpdateBudget::Micros(0)), 2);
+        assert!(queue.is_empty());
+        assert_eq!(flush(&mut queue, UpdateBudget::Bytes(400)), 0);
+        assert_eq!(
+            windows(&display.interface),
+            [0, 20, 40, 60, 80].map(|x| [x, x + 9, 0, 9])
+        );
+    }
+
+    #[test]
+    fn progress_bar_fills_only_the_change() {
+        let mut buffer = [0u8; 4096];
+        let mut display = blank(&mut buffer);
+        let mut bar = ProgressBar::<Rgb565>::new(10, 20, 100, 5, 0xffff, 0x0000);
+        for percent in [50, 75, 30, 30, 200] {
+            bar.set_progress(&mut display, percent).unwrap();
+        }
+        assert_eq!(bar.progress(), 100);
+        assert_eq!(
+            windows(&display.interface),
+            [
+                [10, 59, 20, 24],
+                [60, 84, 20, 24],
+                [40, 84, 20, 24],
+                [40, 109, 20, 24]
+            ]
+        );
+        let fills: std::vec::Vec<_> = pixels(&display.interface)
+            .into_iter()
+            .map(|data| (data.len() / 2, data[0]))
+            .collect();
+        // Growing fills the color, shrinking the background
+        assert_eq!(fills, [(250, 0xff), (125, 0xff), (225, 0x00), (350, 0xff)]);
+    }
+
+    #[test]
+    fn marquee_redraws_the_wrapped_strip() {
+        use embedded_graphics_core::{pixelcolor::raw::RawU16, primitives::Rectangle};
+
+        let mut buffer = [0u8; 1024];
+        let mut display = blank(&mut buffer);
+        let area = Rectangle::new(Point::new(10, 10), Size::new(4, 1));
+        let mut marquee = Marquee::new(area, MarqueeDirection::Horizontal, 6, 2);
+        let content = |point: Point| Rgb565::from(RawU16::new(point.x as u16));
+        marquee.draw(&mut display, content).unwrap();
+        marquee.step(&mut display, content).unwrap();
+        marquee.step(&mut display, content).unwrap();
+        assert_eq!(marquee.position(), 4);
+        assert_eq!(windows(&display.interface), [[10, 13, 10, 10]; 3]);
+        let strips: std::vec::Vec<_> = pixels(&display.interface)
+            .into_iter()
+            .map(|data| {
+                data.chunks(2)
+                    .map(|pixel| pixel[1])
+                    .collect::<std::vec::Vec<_>>()
+            })
+            .collect();
+        assert_eq!(strips, [[0, 1, 2, 3], [2, 3, 4, 5], [4, 5, 0, 1]]);
+    }
+
+    #[test]
+    fn marquee_hardware_scroll_draws_new_lines() {
+        use embedded_graphics_core::pixelcolor::raw::RawU16;
+
+        let mut buffer = std::vec![0u8; 1 << 20];
+        let mut display = blank(&mut buffer);
+        assert!(matches!(
+            Marquee::with_hardware_scroll(&mut display, 20, 20, 400, 201),
+            Err(DisplayError::OutOfBoundsError)
+        ));
+        display.interface.clear();
+
+        let mut marquee = Marquee::with_hardware_scroll(&mut display, 20, 20, 400, 4).unwrap();
+        let content = |point: Point| Rgb565::from(RawU16::new(point.y as u16));
+        marquee.step(&mut display, content).unwrap();
+        let sent = commands(&display.interface);
+        assert_eq!(
+            sent[..3],
+            [
+                (0x33, std::vec![0, 20, 0, 200, 0, 20]),
+                (0x37, std::vec![0, 20]),
+                (0x37, std::vec![0, 24])
+            ]
+        );
+        // The 4 lines scrolled in at the bottom reuse the memory lines that
+        // left at the top, showing lines 200 to 203 of the strip
+        assert_eq!(windows(&display.interface), [[0, 319, 20, 23]]);
+        let (_, pixels) = sent.last().unwrap();
+        assert_eq!(pixels.len(), 320 * 4 * 2);
+        assert_eq!(pixels[..2], [0, 200]);
+        assert_eq!(pixels[pixels.len() - 2..], [0, 203]);
+    }
+
+    #[test]
+    fn strip_chart_hardware_scroll_writes_the_leaving_column() {
+        let mut buffer = [0u8; 4096];
+        let mut display = Ili9342C::<_, Rgb565>::new(
+            CommandRecorder::new(&mut buffer),
+            &mut NoDelay,
+            Orientation::Portrait,
+            DisplaySize320x240,
+        )
+        .unwrap();
+        let mut chart = StripChart::with_hardware_scroll(
+            &mut display,
+            10,
+            10,
+            0,
+            100,
+            Rgb565::WHITE,
+            Rgb565::BLACK,
+        )
+        .unwrap();
+        display.interface.clear();
+        chart.push(&mut display, 100).unwrap();
+        chart.push(&mut display, 0).unwrap();
+        let sent = commands(&display.interface);
+        let scrolls: std::vec::Vec<_> = sent.iter().filter(|(cmd, _)| *cmd == 0x37).collect();
+        assert_eq!(*scrolls[0], (0x37, std::vec![0, 11]));
+        assert_eq!(*scrolls[1], (0x37, std::vec![0, 12]));
+        assert_eq!(
+            windows(&display.interface),
+            [[10, 10, 0, 319], [11, 11, 0, 319]]
+        );
+        let columns = pixels(&display.interface);
+        // The maximum is the top row, the next sample connects down to the
+        // bottom row
+        assert_eq!(columns[0][..4], [0xff, 0xff, 0, 0]);
+        assert!(columns[0][2..].iter().all(|&byte| byte == 0));
+        assert!(columns[1].iter().all(|&byte| byte == 0xff));
+    }
+
+    #[test]
+    fn scroll_list_clips_items_to_ring() {
+        let mut buffer = std::vec![0u8; 1 << 20];
+        let recorder = record(&mut buffer, false, |display| {
+            // Scroll area on the memory lines 10..230, one item taller than it
+            let mut list = ScrollList::new(display, 10, 10, 500, 1, Rgb565::BLACK).unwrap();
+            display.interface.clear();
+            let draw = |_, item: &mut ListItemTarget<'_, _, _>| {
+                let column = Rectangle::new(Point::zero(), Size::new(1, 500));
+                item.fill_contiguous(&column, core::iter::repeat(Rgb565::RED))
+            };
+            // Cut off at the bottom, then at both edges
+            list.redraw(display, draw).unwrap();
+            list.scroll_to(display, 240, draw).unwrap();
+        });
+        let windows = windows(&recorder);
+        assert!(!windows.is_empty());
+        for [_, _, top, bottom] in windows {
+            assert!(
+                (10..230).contains(&top) && (top..230).contains(&bottom),
+                "rows {top}..={bottom} outside of the scroll area"
+            );
+        }
+    }
+
+    #[test]
+    fn builder_init_follows_orientation() {
+        for (mode, size) in [
+            (Orientation::Landscape, (320, 240)),
+            (Orientation::LandscapeFlipped, (320, 240)),
+            (Orientation::Portrait, (240, 320)),
+            (Orientation::PortraitFlipped, (240, 320)),
+        ] {
+            let mut buffer = [0u8; 1024];
+            let mut display = Builder::<_, Rgb565>::new(CommandRecorder::new(&mut buffer))
+                .init(&mut NoDelay, mode, DisplaySize320x240)
+                .unwrap();
+            assert_eq!((display.width(), display.height()), size);
+            // Changing to the same orientation keeps the size
+            display.set_orientation(mode).unwrap();
+            assert_eq!((display.width(), display.height()), size);
+        }
+    }
+
+    /// Output pin ignoring all writes
+    struct NoPin;
+
+    impl embedded_hal_0_2::digital::v2::OutputPin for NoPin {
+        type Error = core::convert::Infallible;
+
+        fn set_low(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn set_high(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    impl embedded_hal::digital::ErrorType for NoPin {
+        type Error = core::convert::Infallible;
+    }
+
+    impl embedded_hal::digital::OutputPin for NoPin {
+        fn set_low(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn set_high(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    /// Data sent after each command
+    fn commands(recorder: &CommandRecorder) -> std::vec::Vec<(u8, std::vec::Vec<u8>)> {
+        let mut commands = std::vec::Vec::new();
+        for entry in recorder.entries() {
+            match entry {
+                Recorded::Command(bytes) => commands.push((bytes[0], std::vec::Vec::new())),
+                Recorded::Data(bytes) => {
+                    if let Some((_, data)) = commands.last_mut() {
+                        data.extend_from_slice(bytes);
+                    }
+                }
+            }
+        }
+        commands
+    }
+
+    #[test]
+    fn deep_standby_wake_keeps_mirroring() {
+        let mut buffer = [0u8; 2048];
+        let mut mirrored = 0;
+        let recorder = record(&mut buffer, false, |display| {
+            display.set_mirror(true, false).unwrap();
+            mirrored = display.state.panel.madctl();
+            display.enter_deep_standby(&mut NoDelay).unwrap();
+            display.interface.clear();
+            display
+                .wake_from_deep_standby(&mut NoPin, &mut NoDelay)
+                .unwrap();
+        });
+        let commands = commands(&recorder);
+        let madctl: std::vec::Vec<_> = commands.iter().filter(|(cmd, _)| *cmd == 0x36).collect();
+        assert_eq!(madctl.last().unwrap().1, [mirrored]);
+        // Left awake and on
+        let last = |cmd| commands.iter().rposition(|(c, _)| *c == cmd);
+        assert!(last(0x10) < last(0x11));
+        assert!(last(0x28) < last(0x29));
+    }
+
+    #[test]
+    fn shutdown_sequence() {
+        use core::cell::RefCell;
+        use display_interface::DataFormat;
+        use std::{rc::Rc, vec::Vec};
+
+        #[derive(Debug, PartialEq)]
+        enum Event {
+            Command(u8),
+            Delay(u16),
+            Backlight,
+        }
+
+        type Log = Rc<RefCell<Vec<Event>>>;
+
+        struct Logged(Log);
+
+        impl WriteOnlyDataCommand for Logged {
+            fn send_commands(&mut self, cmd: DataFormat<'_>) -> Result {
+                if let DataFormat::U8Iter(iter) = cmd {
+                    self.0.borrow_mut().extend(iter.map(Event::Command));
+                }
+                Ok(())
+            }
+
+            fn send_data(&mut self, _buf: DataFormat<'_>) -> Result {
+                Ok(())
+            }
+        }
+
+        impl DelayMs<u16> for Logged {
+            fn delay_ms(&mut self, ms: u16) {
+                self.0.borrow_mut().push(Event::Delay(ms));
+            }
+        }
+
+        /// Backlight pin failing after logging the write
+        struct Backlight(Log, bool);
+
+        impl embedded_hal_0_2::digital::v2::OutputPin for Backlight {
+            type Error = ();
+
+            fn set_low(&mut self) -> Result<(), Self::Error> {
+                self.0.borrow_mut().push(Event::Backlight);
+                if self.1 {
+                    Err(())
+                } else {
+                    Ok(())
+                }
+            }
+
+            fn set_high(&mut self) -> Result<(), Self::Error> {
+                Ok(())
+            }
+        }
+
+        for fails in [false, true] {
+            let log = Log::default();
+            let display = Ili9342C::<_, Rgb565>::new(
+                Logged(log.clone()),
+                &mut NoDelay,
+                Orientation::Landscape,
+                DisplaySize320x240,
+            )
+            .unwrap();
+            log.borrow_mut().clear();
+            let mut backlight = Backlight(log.clone(), fails);
+            let result = display.shutdown_with_backlight(&mut Logged(log.clone()), &mut backlight);
+            // The interface comes back either way
+            match result {
+                Ok(_) => assert!(!fails),
+                Err((_, error)) => {
+                    assert!(fails && matches!(error, DisplayError::BusWriteError))
+                }
+            }
+            assert_eq!(
+                *log.borrow(),
+                [
+                    Event::Backlight,
+                    Event::Command(0x28),
+                    Event::Command(0x10),
+                    Event::Delay(120)
+                ]
+            );
+        }
+    }
+
+    /// Pixel data of each RAMWR
+    fn pixels(recorder: &CommandRecorder) -> std::vec::Vec<std::vec::Vec<u8>> {
+        commands(recorder)
+            .into_iter()
+            .filter(|(cmd, _)| *cmd == 0x2c)
+            .map(|(_, data)| data)
+            .collect()
+    }
+
+    /// Windows set with CASET and PASET, as start and end of both axes
+    fn windows(recorder: &CommandRecorder) -> std::vec::Vec<[u16; 4]> {
+        let word = |data: &[u8], i: usize| u16::from_be_bytes([data[i], data[i + 1]]);
+        let mut windows = std::vec::Vec::new();
+        let mut columns = None;
+        for (cmd, data) in commands(recorder) {
+            match cmd {
+                0x2a => columns = Some([word(&data, 0), word(&data, 2)]),
+                0x2b => {
+                    let [x0, x1] = columns.take().unwrap();
+                    windows.push([x0, x1, word(&data, 0), word(&data, 2)]);
+                }
+                _ => {}
+            }
+        }
+        windows
+    }
+
+    #[test]
+    fn clear_screen_covers_the_screen() {
+        let mut buffer = std::vec![0u8; 1 << 20];
+        let recorder = record(&mut buffer, false, |display| {
+            display.clear_screen(0).unwrap();
+            display.clear_screen_with_progress(0, |_, _| {}).unwrap();
+            let written = display.clear_screen_cancellable(0, || false).unwrap();
+            assert_eq!(written, 320 * 240);
+        });
+        assert_eq!(windows(&recorder), [[0, 319, 0, 239]; 3]);
+    }
+
+    #[cfg(feature = "lvgl")]
+    #[test]
+    fn lvgl_flush_rejects_short_buffers() {
+        let mut buffer = [0u8; 256];
+        let recorder = record(&mut buffer, false, |display| {
+            let result = display.lvgl_flush(0, 0, 1, 1, &[0; 3], false);
+            assert!(matches!(result, Err(DisplayError::OutOfBoundsError)));
+        });
+        assert_eq!(recorder.entries().count(), 0);
+    }
+
+    #[cfg(feature = "lvgl")]
+    #[test]
+    fn lvgl_flush_writes_the_area() {
+        let mut buffer = [0u8; 256];
+        let recorder = record(&mut buffer, false, |display| {
+            let colors = [0x1234, 0xabcd, 0xffff];
+            display.lvgl_flush(2, 1, 3, 1, &colors, false).unwrap();
+            display.lvgl_flush(2, 1, 3, 1, &colors, true).unwrap();
+            let result = display.lvgl_flush(319, 0, 320, 0, &colors, false);
+            assert!(matches!(result, Err(DisplayError::OutOfBoundsError)));
+        });
+        assert_eq!(windows(&recorder), [[2, 3, 1, 1]; 2]);
+        // Swapped buffers are sent in memory order
+        let swapped = [0x1234u16, 0xabcd].map(u16::to_ne_bytes).concat();
+        assert_eq!(
+            pixels(&recorder),
+            [std::vec![0x12, 0x34, 0xab, 0xcd], swapped]
+        );
+    }
+
+    #[cfg(feature = "slint")]
+    #[test]
+    fn slint_lines_are_streamed_to_their_window() {
+        use ::slint::platform::software_renderer::{LineBufferProvider, Rgb565Pixel};
+
+        let mut buffer = [0u8; 256];
+        let mut display = blank(&mut buffer);
+        let mut lines = SlintLineBuffer::<_, 320>::new(&mut display);
+        (&mut lines).process_line(5, 2..5, |line| line.fill(Rgb565Pixel(0x1234)));
+        (&mut lines).process_line(6, 4..4, |_| {});
+        (&mut lines).process_line(7, 0..1, |line| line.fill(Rgb565Pixel(0xf800)));
+        lines.finish().unwrap();
+        assert_eq!(windows(&display.interface), [[2, 4, 5, 5], [0, 0, 7, 7]]);
+        assert_eq!(
+            pixels(&display.interface),
+            [
+                std::vec![0x12, 0x34, 0x12, 0x34, 0x12, 0x34],
+                std::vec![0xf8, 0x00]
+            ]
+        );
+    }
+
+    #[cfg(feature = "console")]
+    #[test]
+    fn console_moves_the_cursor_and_scrolls() {
+        use embedded_graphics::mono_font::ascii::FONT_6X10;
+        use embedded_graphics_core::pixelcolor::{raw::RawU16, Rgb888};
+
+        let mut buffer = std::vec![0u8; 1 << 20];
+        let mut display = blank(&mut buffer);
+        let mut console = Console::new(&mut display, &FONT_6X10).unwrap();
+        assert_eq!(console.size(), (53, 24));
+        assert_eq!(
+            commands(&display.interface)[..2],
+            [
+                (0x33, std::vec![0, 0, 0, 240, 0, 0]),
+                (0x37, std::vec![0, 0])
+            ]
+        );
+        let rows: std::vec::Vec<_> = (0..24)
+            .map(|row| [0, 317, row * 10, row * 10 + 9])
+            .collect();
+        assert_eq!(windows(&display.interface), rows);
+
+        display.interface.clear();
+        write!(console.writer(&mut display), "ab\nc").unwrap();
+        assert_eq!(console.cursor(), (1, 1));
+        // One window per glyph
+        assert_eq!(
+            windows(&display.interface),
+            [[0, 5, 0, 9], [6, 11, 0, 9], [0, 5, 10, 19]]
+        );
+        for (sequence, cursor) in [
+            ("\x1b[5;10H", (9, 4)),
+            ("\x1b[3A\x1b[20C", (29, 1)),
+            ("\x1b[99B\x1b[D", (28, 23)),
+            ("\x1b[999C", (52, 23)),
+        ] {
+            console.write_str(&mut display, sequence).unwrap();
+            assert_eq!(console.cursor(), cursor, "{sequence:?}");
+        }
+
+        // Clearing uses the background of the current attributes
+        let blue = RawU16::from(Rgb565::from_rgb888(Rgb888::new(0, 0, 238))).into_inner();
+        let is_blue = |data: &[u8]| data.chunks(2).all(|pixel| pixel == blue.to_be_bytes());
+        display.interface.clear();
+        console.write_str(&mut display, "\x1b[44m\x1b[2K").unwrap();
+        assert_eq!(windows(&display.interface), [[0, 317, 230, 239]]);
+        assert!(is_blue(&pixels(&display.interface)[0]));
+
+        // A line feed on the last row scrolls by one row and clears the
+        // memory row coming in at the bottom
+        display.interface.clear();
+        console.write_str(&mut display, "\n").unwrap();
+        assert_eq!(console.cursor(), (0, 23));
+        assert_eq!(commands(&display.interface)[0], (0x37, std::vec![0, 10]));
+        assert_eq!(windows(&display.interface), [[0, 317, 0, 9]]);
+        assert!(is_blue(&pixels(&display.interface)[0]));
+
+        display.interface.clear();
+        console.write_str(&mut display, "\x1b[0m\x1b[2J").unwrap();
+        assert_eq!(console.cursor(), (0, 0));
+        // Rows are in memory order after the scroll
+        let rows: std::vec::Vec<_> = (1..24)
+            .chain([0])
+            .map(|row| [0, 317, row * 10, row * 10 + 9])
+            .collect();
+        assert_eq!(windows(&display.interface), rows);
+    }
+
+    #[cfg(feature = "ratatui")]
+    #[test]
+    fn ratatui_backend_draws_cells() {
+        use ratatui_core::{
+            backend::{Backend, ClearType},
+            buffer::Cell,
+            layout::{Position, Size as CellSize},
+            style::{Color, Modifier},
+        };
+
+        let mut buffer = [0u8; 4096];
+        let mut backend = RatatuiBackend::new(blank(&mut buffer));
+        assert_eq!(backend.size().unwrap(), CellSize::new(53, 24));
+        assert_eq!(
+            backend.window_size().unwrap().pixels,
+            CellSize::new(320, 240)
+        );
+
+        let mut reversed = Cell::new(" ");
+        reversed.set_bg(Color::Blue).set_style(Modifier::REVERSED);
+        let plain = Cell::new("A");
+        // Cells outside of the grid are skipped
+        let cells = [(2, 1, &reversed), (53, 0, &plain), (0, 24, &plain)];
+        backend.draw(cells.into_iter()).unwrap();
+        backend.set_cursor_position(Position::new(50, 23)).unwrap();
+        assert_eq!(
+            backend.get_cursor_position().unwrap(),
+            Position::new(50, 23)
+        );
+        backend.clear_region(ClearType::UntilNewLine).unwrap();
+
+        let display = backend.into_inner();
+        assert_eq!(
+            windows(&display.interface),
+            [[12, 17, 10, 19], [300, 317, 230, 239]]
+        );
+        let pixels = pixels(&display.interface);
+        // The default foreground is white, shown as background when reversed
+        assert!(pixels[0].iter().all(|&byte| byte == 0xff));
+        assert!(pixels[1].iter().all(|&byte| byte == 0));
+    }
+
+    #[cfg(feature = "slint")]
+    #[test]
+    fn slint_lines_longer_than_the_buffer_fail() {
+        use ::slint::platform::software_renderer::LineBufferProvider;
+
+        let mut buffer = [0u8; 256];
+        let mut display = blank(&mut buffer);
+        let mut lines = SlintLineBuffer::<_, 4>::new(&mut display);
+        (&mut lines).process_line(0, 0..2, |_| {});
+        (&mut lines).process_line(1, 2..6, |_| panic!("rendered past the buffer"));
+        assert!(matches!(
+            lines.finish(),
+            Err(DisplayError::OutOfBoundsError)
+        ));
+    }
+
+    #[test]
+    fn auto_wake_runs_the_yield_hook() {
+        use core::sync::atomic::{AtomicU64, Ordering};
+
+        static TIME: AtomicU64 = AtomicU64::new(0);
+        static YIELDS: AtomicU64 = AtomicU64::new(0);
+
+        /// Clock advancing by 1 ms on each read
+        struct Ticking;
+
+        impl Monotonic for Ticking {
+            fn now_us(&self) -> u64 {
+                TIME.fetch_add(1000, Ordering::Relaxed)
+            }
+        }
+
+        let mut buffer = [0u8; 1024];
+        record(&mut buffer, false, |display| {
+            display.set_clock(Some(&Ticking));
+            display.set_yield_hook(Some(|| {
+                YIELDS.fetch_add(1, Ordering::Relaxed);
+            }));
+            display.set_auto_sleep(Some(1));
+            display.poll_auto_sleep().unwrap();
+            assert!(display.poll_auto_sleep().unwrap());
+            display.draw_raw_iter(0, 0, 0, 0, [0]).unwrap();
+        });
+        // Two waits of 120 ms on a clock advancing 1 ms per read
+        assert!(YIELDS.load(Ordering::Relaxed) >= 200);
+    }
+
+    #[test]
+    fn tiled_fills_are_split_at_tile_edges() {
+        let (mut a, mut b) = ([0u8; 1024], [0u8; 1024]);
+        let mut wall = TiledDisplay::new([blank(&mut a), blank(&mut b)], 2).unwrap();
+        let area = Rectangle::new(Point::new(318, 0), Size::new(4, 2));
+        let colors = (0..8).map(|blue| Rgb565::new(0, 0, blue));
+        wall.fill_contiguous(&area, colors).unwrap();
+        wall.fill_solid(&area, Rgb565::RED).unwrap();
+        let [left, right] = wall.into_inner().map(Ili9342C::into_inner);
+        assert_eq!(
+            windows(&left),
+            [[318, 319, 0, 0], [318, 319, 1, 1], [318, 319, 0, 1]]
+        );
+        assert_eq!(windows(&right), [[0, 1, 0, 0], [0, 1, 1, 1], [0, 1, 0, 1]]);
+        // Each tile gets the colors of its columns
+        let pixels = |recorder: &CommandRecorder| -> std::vec::Vec<u8> {
+            commands(recorder)
+                .into_iter()
+                .filter(|(cmd, _)| *cmd == 0x2c)
+                .take(2)
+                .flat_map(|(_, data)| data)
+                .collect()
+        };
+        assert_eq!(pixels(&left), [0, 0, 0, 1, 0, 4, 0, 5]);
+        assert_eq!(pixels(&right), [0, 2, 0, 3, 0, 6, 0, 7]);
+    }
+
+    #[test]
+    fn tiled_fill_contiguous_crossing_rows_of_tiles() {
+        let (mut a, mut b) = ([0u8; 1024], [0u8; 1024]);
+        let mut wall = TiledDisplay::new([blank(&mut a), blank(&mut b)], 1).unwrap();
+        let area = Rectangle::new(Point::new(10, 238), Size::new(3, 4));
+        wall.fill_contiguous(&area, core::iter::repeat(Rgb565::RED))
+            .unwrap();
+        let [top, bottom] = wall.into_inner().map(Ili9342C::into_inner);
+        assert_eq!(windows(&top), [[10, 12, 238, 239]]);
+        assert_eq!(windows(&bottom), [[10, 12, 0, 1]]);
+    }
+
+    #[test]
+    fn mirror_transforms_map_corners() {
+        use MirrorTransform::*;
+
+        let size = Size::new(4, 3);
+        let corners = [Point::new(0, 0), Point::new(3, 0), Point::new(0, 2)];
+        for (transform, mapped) in [
+            (Identity, [(0, 0), (3, 0), (0, 2)]),
+            (FlipX, [(3, 0), (0, 0), (3, 2)]),
+            (FlipY, [(0, 2), (3, 2), (0, 0)]),
+            (Rotate180, [(3, 2), (0, 2), (3, 0)]),
+            (Rotate90, [(2, 0), (2, 3), (0, 0)]),
+            (Rotate270, [(0, 3), (0, 0), (2, 3)]),
+        ] {
+            let points = corners.map(|corner| transform.point(corner, size));
+            assert_eq!(
+                points,
+                mapped.map(|(x, y)| Point::new(x, y)),
+                "{transform:?}"
+            );
+            let secondary = match transform {
+                Rotate90 | Rotate270 => Size::new(3, 4),
+                _ => size,
+            };
+            let full = Rectangle::new(Point::zero(), size);
+            assert_eq!(
+                transform.rectangle(&full, size),
+                Some(Rectangle::new(Point::zero(), secondary))
+            );
+        }
+    }
+
+    #[test]
+    fn mirrored_runs_keep_their_pixels() {
+        use MirrorTransform::*;
+
+        for transform in [Identity, FlipX, FlipY, Rotate180, Rotate90, Rotate270] {
+            let (mut a, mut b) = ([0u8; 1024], [0u8; 1024]);
+            let mut secondary = blank(&mut b);
+            if matches!(transform, Rotate90 | Rotate270) {
+                secondary.set_orientation(Orientation::Portrait).unwrap();
+                secondary.interface.clear();
+            }
+            let mut both = MirroredDisplay::new(blank(&mut a), secondary, transform).unwrap();
+            let run = Rectangle::new(Point::new(1, 2), Size::new(3, 1));
+            let colors = (0..3).map(|blue| Rgb565::new(0, 0, blue));
+            both.fill_contiguous(&run, colors).unwrap();
+            let size = both.size();
+            let recorder = both.into_inner().1.into_inner();
+
+            // Place the received colors by the window they were written to
+            let [[x0, x1, y0, _]] = windows(&recorder)[..] else {
+                panic!("{transform:?} wrote more than one window");
+            };
+            let (_, data) = commands(&recorder).pop().unwrap();
+            assert_eq!(data.len(), 6);
+            let width = (x1 - x0 + 1) as i32;
+            for (index, color) in data.chunks(2).enumerate() {
+                let offset = Point::new(index as i32 % width, index as i32 / width);
+                let primary = Point::new(1 + color[1] as i32, 2);
+                assert_eq!(
+                    Point::new(x0 as i32, y0 as i32) + offset,
+                    transform.point(primary, size),
+                    "{transform:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn region_windows_are_moved_and_clipped() {
+        let mut buffer = [0u8; 1024];
+        let recorder = record(&mut buffer, false, |display| {
+            let area = Rectangle::new(Point::new(10, 20), Size::new(100, 50));
+            let inner = Rectangle::new(Point::new(90, 40), Size::new(30, 30));
+            let full = Rectangle::new(Point::zero(), Size::new(30, 30));
+            let mut region = display.region(area);
+            region
+                .fill_solid(
+                    &Rectangle::new(Point::new(2, 3), Size::new(4, 2)),
+                    Rgb565::RED,
+                )
+                .unwrap();
+            region
+                .fill_contiguous(
+                    &Rectangle::new(Point::new(1, 1), Size::new(2, 1)),
+                    [Rgb565::RED; 2],
+                )
+                .unwrap();
+            // Only the pixel inside the region is drawn
+            region
+                .fill_contiguous(
+                    &Rectangle::new(Point::new(99, 0), Size::new(2, 1)),
+                    [Rgb565::RED; 2],
+                )
+                .unwrap();
+            // Nested regions are clipped to the outer one
+            region.region(inner).fill_solid(&full, Rgb565::RED).unwrap();
+            // And to the clip of the display
+            display.set_clip(Rectangle::new(Point::new(105, 0), Size::new(100, 240)));
+            let mut region = display.region(area);
+            region.region(inner).fill_solid(&full, Rgb565::RED).unwrap();
+        });
+        assert_eq!(
+            windows(&recorder),
+            [
+                [12, 15, 23, 24],
+                [11, 12, 21, 21],
+                [109, 109, 20, 20],
+                [100, 109, 60, 69],
+                [105, 109, 60, 69]
+            ]
+        );
+    }
+
+    #[test]
+    fn region_clipped_fill_is_one_window() {
+        use embedded_graphics_core::{pixelcolor::raw::RawU16, primitives::Rectangle};
+
+        let mut buffer = [0u8; 1024];
+        let recorder = record(&mut buffer, false, |display| {
+            let area = Rectangle::new(Point::new(10, 20), Size::new(100, 50));
+            let colors = (0..9).map(|i| Rgb565::from(RawU16::new(i)));
+            display
+                .region(area)
+                .fill_contiguous(&Rectangle::new(Point::new(98, 48), Size::new(3, 3)), colors)
+                .unwrap();
+        });
+        assert_eq!(windows(&recorder), [[108, 109, 68, 69]]);
+        let pixels: std::vec::Vec<_> = commands(&recorder)
+            .into_iter()
+            .filter(|(cmd, _)| *cmd == 0x2c)
+            .flat_map(|(_, data)| data)
+            .collect();
+        assert_eq!(pixels, [0, 0, 0, 1, 0, 3, 0, 4]);
+    }
+
+    #[test]
+    fn clip_windows_are_intersected() {
+        let mut buffer = std::vec![0u8; 4096];
+        let recorder = record(&mut buffer, false, |display| {
+            display.set_clip(Rectangle::new(Point::new(10, 10), Size::new(20, 20)));
+            let corner = Rectangle::new(Point::zero(), Size::new(15, 15));
+            display.fill_solid(&corner, Rgb565::RED).unwrap();
+            display
+                .fill_contiguous(
+                    &Rectangle::new(Point::new(25, 25), Size::new(10, 10)),
+                    core::iter::repeat(Rgb565::RED),
+                )
+                .unwrap();
+            display.clear(Rgb565::BLACK).unwrap();
+            // Nothing is sent outside of the clip
+            display
+                .fill_solid(
+                    &Rectangle::new(Point::new(40, 0), Size::new(5, 5)),
+                    Rgb565::RED,
+                )
+                .unwrap();
+            display.clear_clip();
+            display.fill_solid(&corner, Rgb565::RED).unwrap();
+        });
+        assert_eq!(
+            windows(&recorder),
+            [
+                [10, 14, 10, 14],
+                [25, 29, 25, 29],
+                [10, 29, 10, 29],
+                [0, 14, 0, 14]
+            ]
+        );
+    }
+
+    #[test]
+    fn typed_dcs_commands_match_raw_commands() {
+        struct SetScrollStart(u16);
+
+        impl DcsCommand for SetScrollStart {
+            fn instruction(&self) -> u8 {
+                0x37
+            }
+
+            fn fill_params_buf(&self, buffer: &mut [u8]) -> usize {
+                buffer[..2].copy_from_slice(&self.0.to_be_bytes());
+                2
+            }
+        }
+
+        let (mut a, mut b) = ([0u8; 1024], [0u8; 1024]);
+        let typed = record(&mut a, false, |display| {
+            display.send_dcs(SetScrollStart(0x0123)).unwrap();
+        });
+        let raw = record(&mut b, false, |display| {
+            display.send_command(0x37, &[0x01, 0x23]).unwrap();
+        });
+        assert_eq!(format(&typed), "C 37\nD 01 23\n");
+        assert_eq!(typed.as_bytes(), raw.as_bytes());
+    }
+
+    #[test]
+    fn spi_bus_interface_selects_once_per_command() {
+        use core::cell::RefCell;
+        use embedded_hal::{digital, spi};
+        use std::{rc::Rc, vec::Vec};
+
+        /// Bytes written while chip select was asserted, one entry per
+        /// assertion
+        type Selections = Rc<RefCell<Vec<Vec<u8>>>>;
+
+        struct Bus(Selections);
+        struct Cs(Selections);
+
+        impl spi::ErrorType for Bus {
+            type Error = core::convert::Infallible;
+        }
+
+        impl spi::SpiBus for Bus {
+            fn read(&mut self, _words: &mut [u8]) -> Result<(), Self::Error> {
+                Ok(())
+            }
+
+            fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+                let mut selections = self.0.borrow_mut();
+                selections.last_mut().unwrap().extend_from_slice(words);
+                Ok(())
+            }
+
+            fn transfer(&mut self, _read: &mut [u8], _write: &[u8]) -> Result<(), Self::Error> {
+                Ok(())
+            }
+
+            fn transfer_in_place(&mut self, _words: &mut [u8]) -> Result<(), Self::Error> {
+                Ok(())
+            }
+
+            fn flush(&mut self) -> Result<(), Self::Error> {
+                Ok(())
+            }
+        }
+
+        impl digital::ErrorType for Cs {
+            type Error = core::convert::Infallible;
+        }
+
+        impl digital::OutputPin for Cs {
+            fn set_low(&mut self) -> Result<(), Self::Error> {
+                self.0.borrow_mut().push(Vec::new());
+                Ok(())
+            }
+
+            fn set_high(&mut self) -> Result<(), Self::Error> {
+                Ok(())
+            }
+        }
+
+        let selections = Selections::default();
+        let iface = SpiBusInterface::<_, _, _, 4>::new(
+            Bus(selections.clone()),
+            Cs(selections.clone()),
+            NoPin,
+        );
+        let mut display = Ili9342C::<_, Rgb565>::new(
+            iface,
+            &mut NoDelay,
+            Orientation::Landscape,
+            DisplaySize320x240,
+        )
+        .unwrap();
+        selections.borrow_mut().clear();
+        display.send_command(0x37, &[0x01, 0x23]).unwrap();
+        display.draw_raw_iter(0, 0, 2, 0, [1, 2, 3]).unwrap();
+        assert_eq!(
+            *selections.borrow(),
+            [
+                &[0x37, 0x01, 0x23][..],
+                &[0x2a, 0, 0, 0, 2],
+                &[0x2b, 0, 0, 0, 0],
+                &[0x2c],
+                // Pixel data in transfers of at most 4 bytes
+                &[0, 1, 0, 2],
+                &[0, 3],
+            ]
+        );
+    }
+
+    #[test]
+    fn spi_interface_bounds_transactions() {
+        use embedded_hal::spi::{self, Operation};
+        use std::vec::Vec;
+
+        /// Bytes written by each transaction
+        #[derive(Default)]
+        struct Device(Vec<Vec<u8>>);
+
+        impl spi::ErrorType for Device {
+            type Error = core::convert::Infallible;
+        }
+
+        impl spi::SpiDevice for Device {
+            fn transaction(
+                &mut self,
+                operations: &mut [Operation<'_, u8>],
+            ) -> Result<(), Self::Error> {
+                let mut written = Vec::new();
+                for operation in operations {
+                    if let Operation::Write(bytes) = operation {
+                        written.extend_from_slice(bytes);
+                    }
+                }
+                self.0.push(written);
+                Ok(())
+            }
+        }
+
+        let mut display = Ili9342C::<_, Rgb565>::new(
+            SpiInterface::<_, _, 4>::new(Device::default(), NoPin),
+            &mut NoDelay,
+            Orientation::Landscape,
+            DisplaySize320x240,
+        )
+        .unwrap();
+        display.interface.spi_mut().0.clear();
+        display.send_command(0x37, &[0x01, 0x23]).unwrap();
+        display.draw_raw_iter(0, 0, 2, 0, [1, 2, 3]).unwrap();
+        // The command byte and its parameters need two transactions for
+        // the data/command pin to change in between
+        assert_eq!(
+            display.interface.spi_mut().0,
+            [
+                &[0x37][..],
+                &[0x01, 0x23],
+                &[0x2a],
+                &[0, 0, 0, 2],
+                &[0x2b],
+                &[0, 0, 0, 0],
+                &[0x2c],
+                &[0, 1, 0, 2],
+                &[0, 3],
+            ]
+        );
+    }
+
+    #[cfg(feature = "critical-section")]
+    #[test]
+    fn shared_display_needs_init() {
+        use embedded_graphics_core::primitives::Rectangle;
+
+        let area = Rectangle::new(Point::new(1, 2), Size::new(2, 2));
+        let mut buffer = std::vec![0u8; 1 << 20];
+        let shared = SharedIli9342C::new();
+        assert!(shared.lock(|_| ()).is_none());
+        assert!(matches!(
+            shared.fill_solid(&area, Rgb565::RED),
+            Err(SharedError::NotInitialized)
+        ));
+
+        assert!(shared.init(blank(&mut buffer)).is_none());
+        shared.fill_solid(&area, Rgb565::RED).unwrap();
+        shared.clear(Rgb565::BLACK).unwrap();
+        assert_eq!(
+            shared.lock(|display| display.size()),
+            Some(Size::new(320, 240))
+        );
+
+        let display = shared.take().unwrap();
+        assert!(shared.lock(|_| ()).is_none());
+        assert_eq!(
+            windows(&display.interface),
+            [[1, 2, 2, 3], [0, 319, 0, 239]]
+        );
+    }
+
+    #[test]
+    fn vsync_waits_for_a_rising_edge() {
+        use embedded_hal::digital::ErrorKind;
+
+        /// TE pin reading the given levels, failing after them
+        struct Te<'a>(&'a [bool]);
+
+        impl embedded_hal::digital::ErrorType for Te<'_> {
+            type Error = ErrorKind;
+        }
+
+        impl embedded_hal::digital::InputPin for Te<'_> {
+            fn is_high(&mut self) -> Result<bool, Self::Error> {
+                let (&level, rest) = self.0.split_first().ok_or(ErrorKind::Other)?;
+                self.0 = rest;
+                Ok(level)
+            }
+
+            fn is_low(&mut self) -> Result<bool, Self::Error> {
+                self.is_high().map(|high| !high)
+            }
+        }
+
+        // The end of a blanking period is skipped first
+        let mut te = Te(&[true, true, false, false, true]);
+        tearing::poll_vsync(&mut te).unwrap();
+        assert!(te.0.is_empty());
+        assert!(matches!(
+            tearing::poll_vsync(&mut Te(&[false])),
+            Err(DisplayError::BusWriteError)
+        ));
+    }
 }
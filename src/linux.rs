@@ -0,0 +1,77 @@
+//! Linux spidev and GPIO character device interface, see the `linux` feature
+
+extern crate std;
+
+use std::{io, thread, time::Duration};
+
+use embedded_hal::digital::OutputPin;
+use embedded_hal_0_2::blocking::delay::DelayMs;
+use linux_embedded_hal::{
+    gpio_cdev::{Chip, LineRequestFlags},
+    spidev::{SpiModeFlags, SpidevOptions},
+    CdevPin, SpidevDevice,
+};
+
+use crate::SpiInterface;
+
+/// [SpiInterface] on a Linux spidev device and a GPIO line as data/command
+/// pin
+pub type LinuxInterface = SpiInterface<SpidevDevice, CdevPin>;
+
+/// Delay sleeping the current thread
+pub struct LinuxDelay;
+
+impl DelayMs<u16> for LinuxDelay {
+    fn delay_ms(&mut self, ms: u16) {
+        thread::sleep(Duration::from_millis(ms as u64));
+    }
+}
+
+fn output_line(chip: &str, line: u32, value: u8) -> io::Result<CdevPin> {
+    let handle = Chip::new(chip)
+        .and_then(|mut chip| chip.get_line(line))
+        .and_then(|line| line.request(LineRequestFlags::OUTPUT, value, "ili9342c"))
+        .map_err(io::Error::other)?;
+    CdevPin::new(handle).map_err(io::Error::other)
+}
+
+/// Open the interface of a panel
+///
+/// `spi` is the spidev device, for example `/dev/spidev0.0`, with chip
+/// select handled by the kernel. `dc_line` is the offset of the data/command
+/// pin on the GPIO chip `chip`, for example `/dev/gpiochip0`.
+///
+/// ```ignore
+/// let iface = linux_interface("/dev/spidev0.0", 32_000_000, "/dev/gpiochip0", 25)?;
+/// linux_reset("/dev/gpiochip0", 27)?;
+/// let mut display = Ili9342C::new(iface, &mut LinuxDelay, Orientation::Landscape, DisplaySize320x240)?;
+/// ```
+pub fn linux_interface(
+    spi: &str,
+    speed_hz: u32,
+    chip: &str,
+    dc_line: u32,
+) -> io::Result<LinuxInterface> {
+    let mut device = SpidevDevice::open(spi).map_err(io::Error::other)?;
+    device.configure(
+        &SpidevOptions::new()
+            .bits_per_word(8)
+            .max_speed_hz(speed_hz)
+            .mode(SpiModeFlags::SPI_MODE_0)
+            .build(),
+    )?;
+    let dc = output_line(chip, dc_line, 0)?;
+    Ok(SpiInterface::new(device, dc))
+}
+
+/// Reset the panel with the reset pin on line `rst_line` of GPIO chip `chip`
+///
+/// Same timing as [hard_reset](crate::hard_reset).
+pub fn linux_reset(chip: &str, rst_line: u32) -> io::Result<()> {
+    let mut rst = output_line(chip, rst_line, 1)?;
+    rst.set_low().map_err(io::Error::other)?;
+    LinuxDelay.delay_ms(1);
+    rst.set_high().map_err(io::Error::other)?;
+    LinuxDelay.delay_ms(120);
+    Ok(())
+}
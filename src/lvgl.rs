@@ -0,0 +1,51 @@
+//! Flush callback glue for LVGL, see the `lvgl` feature
+
+use display_interface::{DisplayError, WriteOnlyDataCommand};
+use embedded_graphics_core::pixelcolor::Rgb565;
+
+use crate::{Ili9342C, Result};
+
+impl<IFACE> Ili9342C<IFACE, Rgb565>
+where
+    IFACE: WriteOnlyDataCommand,
+{
+    /// Write an LVGL draw buffer to an area, as done by the flush callback
+    ///
+    /// The coordinates are the inclusive corners of the `lv_area_t` passed
+    /// to the callback and `colors` the 16-bit color buffer. Set `swapped`
+    /// when LVGL is built with `LV_COLOR_16_SWAP`, so the bytes of each pixel
+    /// are already in display order. The buffer is sent in bands limited by
+    /// [Self::set_max_transfer_size].
+    ///
+    /// Returns [DisplayError::OutOfBoundsError] if the area is not on the
+    /// display or `colors` holds fewer pixels than the area.
+    ///
+    /// Call `lv_disp_flush_ready` afterwards, as with any flush callback.
+    ///
+    /// ```ignore
+    /// let mut display_driver = lvgl::Display::register(buffer, 320, 240, |refresh| {
+    ///     let area = &refresh.area;
+    ///     display.lvgl_flush(area.x1, area.y1, area.x2, area.y2, refresh.colors, false).ok();
+    /// })?;
+    /// ```
+    pub fn lvgl_flush(
+        &mut self,
+        x1: i16,
+        y1: i16,
+        x2: i16,
+        y2: i16,
+        colors: &[u16],
+        swapped: bool,
+    ) -> Result {
+        if x1 < 0 || y1 < 0 || x2 < x1 || y2 < y1 {
+            return Err(DisplayError::OutOfBoundsError);
+        }
+        let (x1, y1, x2, y2) = (x1 as u16, y1 as u16, x2 as u16, y2 as u16);
+        if x2 as usize >= self.state.width || y2 as usize >= self.state.height {
+            return Err(DisplayError::OutOfBoundsError);
+        }
+        let pixels = (x2 - x1 + 1) as usize * (y2 - y1 + 1) as usize;
+        let colors = colors.get(..pixels).ok_or(DisplayError::OutOfBoundsError)?;
+        self.draw_raw_slice(x1, y1, x2, y2, colors, swapped)
+    }
+}
@@ -0,0 +1,161 @@
+//! Continuously scrolling tickers
+
+use display_interface::{DisplayError, WriteOnlyDataCommand};
+use embedded_graphics_core::{
+    draw_target::DrawTarget,
+    geometry::{Dimensions, Point, Size},
+    primitives::{PointsIter, Rectangle},
+};
+
+use crate::{Ili9342C, PixelFormat, Result, Scroller};
+
+/// Direction a [Marquee] moves its content
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum MarqueeDirection {
+    /// Content moves to the left
+    Horizontal,
+    /// Content moves up
+    Vertical,
+}
+
+/// Ticker moving a long strip of content through an area
+///
+/// The content is sampled by a callback returning the color of a point in
+/// strip coordinates, e.g. from a prerendered framebuffer. The strip repeats
+/// after `length` pixels along the direction of movement.
+///
+/// A marquee created with [Marquee::with_hardware_scroll] moves the content
+/// with the hardware scroll and only draws the lines becoming visible, other
+/// marquees redraw their area on every step.
+///
+/// ```ignore
+/// let mut ticker = Marquee::new(area, MarqueeDirection::Horizontal, strip.width(), 2);
+/// loop {
+///     ticker.step(&mut display, |point| strip.pixel(point))?;
+///     timer.wait(Duration::from_millis(20));
+/// }
+/// ```
+pub struct Marquee {
+    area: Rectangle,
+    direction: MarqueeDirection,
+    length: u32,
+    position: u32,
+    step: u32,
+    scroller: Option<Scroller>,
+}
+
+impl Marquee {
+    /// Ticker redrawing `area` on every step, moving the content by `step`
+    /// pixels
+    pub fn new(area: Rectangle, direction: MarqueeDirection, length: u32, step: u32) -> Self {
+        Self {
+            area,
+            direction,
+            length: length.max(1),
+            position: 0,
+            step,
+            scroller: None,
+        }
+    }
+
+    /// Vertical ticker filling the hardware scroll area between the fixed top
+    /// and bottom lines
+    ///
+    /// The display has to be in a landscape orientation, see
+    /// [Ili9342C::configure_vertical_scroll].
+    pub fn with_hardware_scroll<IFACE, C>(
+        display: &mut Ili9342C<IFACE, C>,
+        fixed_top_lines: u16,
+        fixed_bottom_lines: u16,
+        length: u32,
+        step: u32,
+    ) -> Result<Self>
+    where
+        IFACE: WriteOnlyDataCommand,
+        C: PixelFormat,
+    {
+        let mut scroller =
+            display.configure_vertical_scroll(fixed_top_lines, fixed_bottom_lines)?;
+        display.scroll_vertically(&mut scroller, 0)?;
+        let area = Rectangle::new(
+            Point::new(0, fixed_top_lines as i32),
+            Size::new(display.width() as u32, scroller.scroll_lines() as u32),
+        );
+        if step > scroller.scroll_lines() as u32 {
+            return Err(DisplayError::OutOfBoundsError);
+        }
+        Ok(Self {
+            area,
+            direction: MarqueeDirection::Vertical,
+            length: length.max(1),
+            position: 0,
+            step,
+            scroller: Some(scroller),
+        })
+    }
+
+    /// Offset of the content shown at the start of the area
+    pub fn position(&self) -> u32 {
+        self.position
+    }
+
+    /// Change the number of pixels moved per step
+    pub fn set_step(&mut self, step: u32) {
+        self.step = match self.scroller {
+            Some(scroller) => step.min(scroller.scroll_lines() as u32),
+            None => step,
+        };
+    }
+
+    /// Point of the strip shown at a point of the area
+    fn strip_point(&self, point: Point) -> Point {
+        let wrap = |value: i32| ((value as u32 + self.position) % self.length) as i32;
+        match self.direction {
+            MarqueeDirection::Horizontal => Point::new(wrap(point.x), point.y),
+            MarqueeDirection::Vertical => Point::new(point.x, wrap(point.y)),
+        }
+    }
+
+    /// Draw the whole area at the current position
+    pub fn draw<IFACE, C, F>(&self, display: &mut Ili9342C<IFACE, C>, mut content: F) -> Result
+    where
+        IFACE: WriteOnlyDataCommand,
+        C: PixelFormat,
+        F: FnMut(Point) -> C,
+    {
+        let local = Rectangle::new(Point::zero(), self.area.size);
+        let colors = local.points().map(|point| content(self.strip_point(point)));
+        match &self.scroller {
+            Some(scroller) => display
+                .scroll_region(scroller)
+                .fill_contiguous(&local, colors),
+            None => display.fill_contiguous(&self.area, colors),
+        }
+    }
+
+    /// Move the content by one step and draw the change
+    pub fn step<IFACE, C, F>(&mut self, display: &mut Ili9342C<IFACE, C>, mut content: F) -> Result
+    where
+        IFACE: WriteOnlyDataCommand,
+        C: PixelFormat,
+        F: FnMut(Point) -> C,
+    {
+        self.position = (self.position + self.step) % self.length;
+        let Some(mut scroller) = self.scroller else {
+            return self.draw(display, content);
+        };
+        display.scroll_vertically(&mut scroller, self.step as u16)?;
+        self.scroller = Some(scroller);
+
+        // Only the lines scrolled in at the bottom are new
+        let mut region = display.scroll_region(&scroller);
+        let size = region.bounding_box().size;
+        let band = Rectangle::new(
+            Point::new(0, (size.height - self.step) as i32),
+            Size::new(size.width, self.step),
+        );
+        let colors = band.points().map(|point| content(self.strip_point(point)));
+        region.fill_contiguous(&band, colors)
+    }
+}
@@ -0,0 +1,54 @@
+//! Typed MIPI DCS commands
+
+use display_interface::WriteOnlyDataCommand;
+
+use crate::{Ili9342C, PixelFormat, Result};
+
+/// Room for the parameters of a [DcsCommand]
+const MAX_PARAMS: usize = 16;
+
+/// A MIPI DCS command with its parameters
+///
+/// The same shape as the command traits of generic MIPI DCS crates, so
+/// typed commands written against them can be sent with
+/// [Ili9342C::send_dcs] through a thin impl.
+///
+/// ```ignore
+/// struct SetScrollStart(u16);
+///
+/// impl DcsCommand for SetScrollStart {
+///     fn instruction(&self) -> u8 {
+///         0x37
+///     }
+///
+///     fn fill_params_buf(&self, buffer: &mut [u8]) -> usize {
+///         buffer[..2].copy_from_slice(&self.0.to_be_bytes());
+///         2
+///     }
+/// }
+/// ```
+pub trait DcsCommand {
+    /// Instruction byte of the command
+    fn instruction(&self) -> u8;
+
+    /// Write the parameters to `buffer`, returning their number
+    ///
+    /// The buffer holds 16 bytes.
+    fn fill_params_buf(&self, buffer: &mut [u8]) -> usize;
+}
+
+impl<IFACE, C> Ili9342C<IFACE, C>
+where
+    IFACE: WriteOnlyDataCommand,
+    C: PixelFormat,
+{
+    /// Send a typed DCS command
+    ///
+    /// Like [Self::send_command], the driver does not track what the
+    /// command changes.
+    pub fn send_dcs(&mut self, command: impl DcsCommand) -> Result {
+        let mut params = [0u8; MAX_PARAMS];
+        let len = command.fill_params_buf(&mut params).min(MAX_PARAMS);
+        self.send_command(command.instruction(), &params[..len])
+    }
+}
@@ -0,0 +1,227 @@
+//! The same content on two displays
+
+use display_interface::{DisplayError, WriteOnlyDataCommand};
+use embedded_graphics_core::{
+    draw_target::DrawTarget,
+    geometry::{OriginDimensions, Point, Size},
+    primitives::Rectangle,
+    Pixel,
+};
+
+use crate::{Ili9342C, PixelFormat, Result};
+
+/// Number of colors buffered per run in [MirroredDisplay::fill_contiguous]
+const RUN: usize = 64;
+
+/// Mapping from the coordinates of the primary display to the secondary one
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum MirrorTransform {
+    /// Same coordinates
+    #[default]
+    Identity,
+    /// Mirrored left to right
+    FlipX,
+    /// Mirrored top to bottom
+    FlipY,
+    /// Rotated by 180°
+    Rotate180,
+    /// Rotated by 90° clockwise, the secondary display has the width and
+    /// height swapped
+    Rotate90,
+    /// Rotated by 270° clockwise, the secondary display has the width and
+    /// height swapped
+    Rotate270,
+}
+
+impl MirrorTransform {
+    /// Whether the width and height are swapped
+    fn swaps_axes(self) -> bool {
+        matches!(self, Self::Rotate90 | Self::Rotate270)
+    }
+
+    /// Map a point on a display of `size`
+    pub(crate) fn point(self, point: Point, size: Size) -> Point {
+        let right = size.width as i32 - 1;
+        let bottom = size.height as i32 - 1;
+        match self {
+            Self::Identity => point,
+            Self::FlipX => Point::new(right - point.x, point.y),
+            Self::FlipY => Point::new(point.x, bottom - point.y),
+            Self::Rotate180 => Point::new(right - point.x, bottom - point.y),
+            Self::Rotate90 => Point::new(bottom - point.y, point.x),
+            Self::Rotate270 => Point::new(point.y, right - point.x),
+        }
+    }
+
+    /// Map a rectangle on a display of `size`
+    pub(crate) fn rectangle(self, area: &Rectangle, size: Size) -> Option<Rectangle> {
+        let a = self.point(area.top_left, size);
+        let b = self.point(area.bottom_right()?, size);
+        Some(Rectangle::with_corners(a, b))
+    }
+}
+
+/// Two displays showing the same content
+///
+/// Every draw call goes to the primary display and, mapped through the
+/// [MirrorTransform], to the secondary display. The draw target has the size
+/// of the primary display.
+///
+/// ```ignore
+/// // External panel mounted upside down
+/// let mut both = MirroredDisplay::new(internal, external, MirrorTransform::Rotate180)?;
+/// Text::new("Ready", Point::new(10, 20), style).draw(&mut both)?;
+/// ```
+pub struct MirroredDisplay<IA, IB, C> {
+    primary: Ili9342C<IA, C>,
+    secondary: Ili9342C<IB, C>,
+    transform: MirrorTransform,
+}
+
+impl<IA, IB, C> MirroredDisplay<IA, IB, C>
+where
+    IA: WriteOnlyDataCommand,
+    IB: WriteOnlyDataCommand,
+    C: PixelFormat,
+{
+    /// Combine two displays, the secondary one has to match the size of the
+    /// primary display after the transform
+    pub fn new(
+        primary: Ili9342C<IA, C>,
+        secondary: Ili9342C<IB, C>,
+        transform: MirrorTransform,
+    ) -> Result<Self> {
+        let size = primary.size();
+        let expected = if transform.swaps_axes() {
+            Size::new(size.height, size.width)
+        } else {
+            size
+        };
+        if secondary.size() != expected {
+            return Err(DisplayError::OutOfBoundsError);
+        }
+        Ok(Self {
+            primary,
+            secondary,
+            transform,
+        })
+    }
+
+    /// Transform of the secondary display
+    pub fn transform(&self) -> MirrorTransform {
+        self.transform
+    }
+
+    /// Access the primary display
+    pub fn primary_mut(&mut self) -> &mut Ili9342C<IA, C> {
+        &mut self.primary
+    }
+
+    /// Access the secondary display
+    pub fn secondary_mut(&mut self) -> &mut Ili9342C<IB, C> {
+        &mut self.secondary
+    }
+
+    /// Release both displays
+    pub fn into_inner(self) -> (Ili9342C<IA, C>, Ili9342C<IB, C>) {
+        (self.primary, self.secondary)
+    }
+
+    /// Draw a horizontal run of pixels starting at `start` on both displays
+    fn draw_run(&mut self, start: Point, colors: &[C]) -> Result {
+        let run = Rectangle::new(start, Size::new(colors.len() as u32, 1));
+        self.primary.fill_contiguous(&run, colors.iter().copied())?;
+        let size = self.size();
+        let first = self.transform.point(start, size);
+        let Some(mapped) = self.transform.rectangle(&run, size) else {
+            return Ok(());
+        };
+        if mapped.top_left == first {
+            self.secondary
+                .fill_contiguous(&mapped, colors.iter().copied())
+        } else {
+            // The run is mapped right to left or bottom to top
+            self.secondary
+                .fill_contiguous(&mapped, colors.iter().rev().copied())
+        }
+    }
+}
+
+impl<IA, IB, C> OriginDimensions for MirroredDisplay<IA, IB, C> {
+    fn size(&self) -> Size {
+        self.primary.size()
+    }
+}
+
+impl<IA, IB, C> DrawTarget for MirroredDisplay<IA, IB, C>
+where
+    IA: WriteOnlyDataCommand,
+    IB: WriteOnlyDataCommand,
+    C: PixelFormat,
+{
+    type Color = C;
+
+    type Error = DisplayError;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let size = self.size();
+        for pixel in pixels {
+            self.primary.draw_iter(core::iter::once(pixel))?;
+            let point = self.transform.point(pixel.0, size);
+            self.secondary
+                .draw_iter(core::iter::once(Pixel(point, pixel.1)))?;
+        }
+        Ok(())
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        let mut colors = colors.into_iter();
+        let Some(first) = colors.next() else {
+            return Ok(());
+        };
+        // The colors can only be iterated once, buffer them in short runs
+        let mut buffer = [first; RUN];
+        let mut pending = Some(first);
+        for y in 0..area.size.height as i32 {
+            let mut x = 0;
+            while x < area.size.width as i32 {
+                let len = (area.size.width as i32 - x).min(RUN as i32) as usize;
+                let mut filled = 0;
+                for slot in &mut buffer[..len] {
+                    match pending.take().or_else(|| colors.next()) {
+                        Some(color) => *slot = color,
+                        None => break,
+                    }
+                    filled += 1;
+                }
+                if filled == 0 {
+                    return Ok(());
+                }
+                let start = area.top_left + Point::new(x, y);
+                self.draw_run(start, &buffer[..filled])?;
+                x += len as i32;
+            }
+        }
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        self.primary.fill_solid(area, color)?;
+        match self.transform.rectangle(area, self.size()) {
+            Some(mapped) => self.secondary.fill_solid(&mapped, color),
+            None => Ok(()),
+        }
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.primary.clear(color)?;
+        self.secondary.clear(color)
+    }
+}
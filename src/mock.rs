@@ -0,0 +1,111 @@
+//! Interface recording everything sent, for tests, see the `mock` feature
+
+use alloc::vec::Vec;
+
+use display_interface::{DataFormat, DisplayError, WriteOnlyDataCommand};
+
+use crate::{ReadDataCommand, Result};
+
+/// Interface call recorded by a [MockInterface]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Transfer {
+    /// Command bytes
+    Command(Vec<u8>),
+    /// Data bytes
+    Data(Vec<u8>),
+}
+
+/// Interface recording commands and data in memory
+///
+/// Applications can drive an [Ili9342C](crate::Ili9342C) with it in unit
+/// tests and check what would have been sent to the panel.
+///
+/// ```ignore
+/// let mut display = Ili9342C::new(MockInterface::new(), &mut delay, mode, size)?;
+/// display.clear(Rgb565::RED)?;
+/// let commands = display.into_inner().commands();
+/// ```
+#[derive(Clone, Default, Debug)]
+pub struct MockInterface {
+    transfers: Vec<Transfer>,
+    responses: Vec<(u8, Vec<u8>)>,
+}
+
+impl MockInterface {
+    /// Create an interface with nothing recorded
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Interface calls in the order they were made
+    pub fn transfers(&self) -> &[Transfer] {
+        &self.transfers
+    }
+
+    /// Commands with the data sent after each of them
+    pub fn commands(&self) -> Vec<(u8, Vec<u8>)> {
+        let mut commands: Vec<(u8, Vec<u8>)> = Vec::new();
+        for transfer in &self.transfers {
+            match transfer {
+                Transfer::Command(bytes) => {
+                    commands.extend(bytes.iter().map(|&cmd| (cmd, Vec::new())))
+                }
+                Transfer::Data(bytes) => {
+                    if let Some((_, data)) = commands.last_mut() {
+                        data.extend_from_slice(bytes);
+                    }
+                }
+            }
+        }
+        commands
+    }
+
+    /// Drop everything recorded
+    pub fn clear(&mut self) {
+        self.transfers.clear();
+    }
+
+    /// Bytes returned when a command is read with [ReadDataCommand], other
+    /// commands read zeros
+    pub fn respond(&mut self, command: u8, bytes: &[u8]) {
+        self.responses.retain(|(cmd, _)| *cmd != command);
+        self.responses.push((command, bytes.to_vec()));
+    }
+}
+
+fn to_bytes(data: DataFormat<'_>) -> Result<Vec<u8>> {
+    Ok(match data {
+        DataFormat::U8(slice) => slice.to_vec(),
+        DataFormat::U16(slice) => slice.iter().flat_map(|value| value.to_ne_bytes()).collect(),
+        DataFormat::U16BE(slice) => slice.iter().flat_map(|value| value.to_be_bytes()).collect(),
+        DataFormat::U16LE(slice) => slice.iter().flat_map(|value| value.to_le_bytes()).collect(),
+        DataFormat::U8Iter(iter) => iter.collect(),
+        DataFormat::U16BEIter(iter) => iter.flat_map(u16::to_be_bytes).collect(),
+        DataFormat::U16LEIter(iter) => iter.flat_map(u16::to_le_bytes).collect(),
+        _ => return Err(DisplayError::DataFormatNotImplemented),
+    })
+}
+
+impl WriteOnlyDataCommand for MockInterface {
+    fn send_commands(&mut self, cmd: DataFormat<'_>) -> Result {
+        self.transfers.push(Transfer::Command(to_bytes(cmd)?));
+        Ok(())
+    }
+
+    fn send_data(&mut self, buf: DataFormat<'_>) -> Result {
+        self.transfers.push(Transfer::Data(to_bytes(buf)?));
+        Ok(())
+    }
+}
+
+impl ReadDataCommand for MockInterface {
+    fn read_data(&mut self, command: u8, buffer: &mut [u8]) -> Result {
+        self.transfers.push(Transfer::Command(alloc::vec![command]));
+        buffer.fill(0);
+        if let Some((_, bytes)) = self.responses.iter().find(|(cmd, _)| *cmd == command) {
+            let len = bytes.len().min(buffer.len());
+            buffer[..len].copy_from_slice(&bytes[..len]);
+        }
+        Ok(())
+    }
+}
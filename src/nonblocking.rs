@@ -0,0 +1,78 @@
+//! Non-blocking transfers for superloops, in the style of [nb]
+
+use display_interface::{DisplayError, WriteOnlyDataCommand};
+
+use crate::{Ili9342C, PixelFormat, Result};
+
+/// Transfer started with [Ili9342C::start_flush]
+pub struct PendingFlush<'a, T> {
+    pixels: &'a [T],
+    sent: usize,
+}
+
+impl<T> PendingFlush<'_, T> {
+    /// Number of pixels sent so far
+    pub fn sent(&self) -> usize {
+        self.sent
+    }
+
+    /// Whether all pixels have been sent
+    pub fn is_done(&self) -> bool {
+        self.sent == self.pixels.len()
+    }
+}
+
+impl<IFACE, C> Ili9342C<IFACE, C>
+where
+    IFACE: WriteOnlyDataCommand,
+    C: PixelFormat,
+{
+    /// Set the drawing window and start writing raw pixels to it
+    ///
+    /// No pixels are sent yet, each [Self::poll_flush] sends the next chunk of
+    /// [Self::set_max_transfer_size]. No other commands may be sent to the
+    /// display until the transfer is done.
+    pub fn start_flush<'a>(
+        &mut self,
+        x0: u16,
+        y0: u16,
+        x1: u16,
+        y1: u16,
+        pixels: &'a [C::RawValue],
+    ) -> Result<PendingFlush<'a, C::RawValue>> {
+        self.start_memory_write(x0, y0, x1, y1)?;
+        Ok(PendingFlush { pixels, sent: 0 })
+    }
+
+    /// Send the next chunk of a transfer
+    ///
+    /// Returns [nb::Error::WouldBlock] until all pixels are sent. Without a
+    /// maximum transfer size or watchdog hook the whole transfer is sent by
+    /// the first poll.
+    pub fn poll_flush(
+        &mut self,
+        flush: &mut PendingFlush<'_, C::RawValue>,
+    ) -> nb::Result<(), DisplayError> {
+        let remaining = &flush.pixels[flush.sent..];
+        let chunk = self
+            .transfer_limit()
+            .map_or(remaining.len(), |bytes| {
+                (bytes / C::BYTES_PER_PACK).max(1) * C::PIXELS_PER_PACK
+            })
+            .min(remaining.len());
+        let pixels = remaining[..chunk].iter().copied();
+        crate::pixel_format::send_pixels::<C, _, _>(
+            &mut self.interface,
+            self.state.init.endian,
+            pixels,
+        )?;
+        self.pixels_sent(C::data_len(chunk));
+        flush.sent += chunk;
+        if flush.is_done() {
+            self.state.timing.transfer_end();
+            Ok(())
+        } else {
+            Err(nb::Error::WouldBlock)
+        }
+    }
+}
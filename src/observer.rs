@@ -0,0 +1,56 @@
+//! Tracing of the commands sent to the display
+
+use display_interface::WriteOnlyDataCommand;
+
+use crate::{Ili9342C, PixelFormat};
+
+/// Observer of the command stream, see [Ili9342C::set_command_observer]
+///
+/// ```ignore
+/// struct Logger;
+///
+/// impl CommandObserver for Logger {
+///     fn command(&self, command: u8, len: usize) {
+///         defmt::trace!("cmd {=u8:#x} ({} bytes)", command, len);
+///     }
+/// }
+///
+/// display.set_command_observer(Some(&Logger));
+/// ```
+pub trait CommandObserver {
+    /// Called before a command is sent, with the number of parameter bytes
+    fn command(&self, command: u8, len: usize);
+
+    /// Called after pixel data of a memory write was sent, the pixels of one
+    /// memory write can be reported in several calls
+    fn pixels(&self, bytes: usize) {
+        let _ = bytes;
+    }
+}
+
+/// Function called with the command and the number of parameter bytes
+impl CommandObserver for fn(u8, usize) {
+    fn command(&self, command: u8, len: usize) {
+        self(command, len)
+    }
+}
+
+impl<IFACE, C> Ili9342C<IFACE, C>
+where
+    IFACE: WriteOnlyDataCommand,
+    C: PixelFormat,
+{
+    /// Register an observer called for each command, or remove it with
+    /// `None`
+    pub fn set_command_observer(&mut self, observer: Option<&'static dyn CommandObserver>) {
+        self.state.observer = observer;
+    }
+
+    /// Account for pixel data sent to the interface
+    pub(crate) fn pixels_sent(&mut self, bytes: usize) {
+        self.state.stats.pixel_bytes(bytes);
+        if let Some(observer) = self.state.observer {
+            observer.pixels(bytes);
+        }
+    }
+}
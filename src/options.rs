@@ -0,0 +1,106 @@
+//! Init time choices bundled in one value
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_hal_0_2::blocking::delay::DelayMs;
+
+use crate::{
+    Builder, DisplaySize, FrameDivision, FrameRate, GammaPreset, Ili9342C, Orientation,
+    PixelFormat, Result,
+};
+
+/// BGR bit of memory access control
+pub(crate) const BGR: u8 = 0x08;
+
+/// Order of the color components in the panel
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ColorOrder {
+    /// Red, green, blue
+    Rgb,
+    /// Blue, green, red, used by most ILI9342C modules
+    #[default]
+    Bgr,
+}
+
+impl ColorOrder {
+    /// Memory access control value with the BGR bit of this order
+    pub(crate) fn madctl(self, madctl: u8) -> u8 {
+        match self {
+            Self::Rgb => madctl & !BGR,
+            Self::Bgr => madctl | BGR,
+        }
+    }
+}
+
+/// Choices made while initializing a display, see [Ili9342C::with_options]
+///
+/// The pixel format is the `C` type parameter of the display. The defaults
+/// give the same panel as [Ili9342C::new] in landscape orientation.
+///
+/// ```ignore
+/// let options = InitOptions {
+///     orientation: Orientation::Portrait,
+///     color_order: ColorOrder::Rgb,
+///     ..InitOptions::default()
+/// };
+/// let mut display = Ili9342C::<_, Rgb666>::with_options(iface, &mut delay, DisplaySize320x240, options)?;
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct InitOptions {
+    /// Orientation set during init
+    pub orientation: Orientation,
+    /// Order of the color components, also kept when the orientation changes
+    pub color_order: ColorOrder,
+    /// Invert the colors, which most modules need for a normal picture
+    pub inverted: bool,
+    /// Gamma correction tables written during init
+    pub gamma: GammaPreset,
+    /// Frame rate of normal mode, the reset default when `None`
+    pub frame_rate: Option<(FrameDivision, FrameRate)>,
+    /// Column and row of the panel memory shown at the top left corner,
+    /// added to every drawing window
+    ///
+    /// For modules showing only part of the controller memory. The offset
+    /// applies to the orientation in use.
+    pub offset: (u16, u16),
+}
+
+impl Default for InitOptions {
+    fn default() -> Self {
+        Self {
+            orientation: Orientation::Landscape,
+            color_order: ColorOrder::Bgr,
+            inverted: true,
+            gamma: GammaPreset::PanelDefault,
+            frame_rate: None,
+            offset: (0, 0),
+        }
+    }
+}
+
+impl<IFACE, C> Ili9342C<IFACE, C>
+where
+    IFACE: WriteOnlyDataCommand,
+    C: PixelFormat,
+{
+    /// Initialize the display with [InitOptions], see [Builder::options] to
+    /// combine them with other settings
+    pub fn with_options<DELAY, SIZE>(
+        interface: IFACE,
+        delay: &mut DELAY,
+        display_size: SIZE,
+        options: InitOptions,
+    ) -> Result<Self>
+    where
+        DELAY: DelayMs<u16>,
+        SIZE: DisplaySize,
+    {
+        Builder::new(interface)
+            .options(&options)
+            .init(delay, options.orientation, display_size)
+    }
+}
@@ -0,0 +1,101 @@
+//! Test patterns for bring-up and production tests
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_graphics_core::pixelcolor::Rgb888;
+
+use crate::{Ili9342C, PixelFormat, Result};
+
+/// Bars of [Pattern::ColorBars], from left to right
+const COLOR_BARS: [Rgb888; 8] = [
+    Rgb888::new(0xff, 0xff, 0xff),
+    Rgb888::new(0xff, 0xff, 0x00),
+    Rgb888::new(0x00, 0xff, 0xff),
+    Rgb888::new(0x00, 0xff, 0x00),
+    Rgb888::new(0xff, 0x00, 0xff),
+    Rgb888::new(0xff, 0x00, 0x00),
+    Rgb888::new(0x00, 0x00, 0xff),
+    Rgb888::new(0x00, 0x00, 0x00),
+];
+
+/// Full screen test pattern, see [Ili9342C::test_pattern]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Pattern {
+    /// Eight vertical bars: white, yellow, cyan, green, magenta, red, blue
+    /// and black
+    ColorBars,
+    /// Horizontal ramp from black on the left to white on the right
+    GrayRamp,
+    /// Black and white squares of the given size in pixels
+    Checkerboard(u16),
+    /// All pixels white
+    White,
+    /// All pixels black
+    Black,
+    /// White 1 pixel border, a crosshair through the center and corner
+    /// markers (red top left, green top right, blue bottom left, yellow
+    /// bottom right) on black, showing offsets, mirroring and swapped sizes
+    Alignment,
+}
+
+/// Size of the corner markers of [Pattern::Alignment]
+const MARKER_SIZE: usize = 8;
+
+impl Pattern {
+    /// Color of a pixel of the pattern on a screen of the given width
+    pub(crate) fn color(self, x: usize, y: usize, width: usize, height: usize) -> Rgb888 {
+        match self {
+            Pattern::ColorBars => COLOR_BARS[x * COLOR_BARS.len() / width],
+            Pattern::GrayRamp => {
+                let level = (x * 255 / (width - 1).max(1)) as u8;
+                Rgb888::new(level, level, level)
+            }
+            Pattern::Checkerboard(size) => {
+                let size = size.max(1) as usize;
+                if (x / size + y / size).is_multiple_of(2) {
+                    Rgb888::new(0xff, 0xff, 0xff)
+                } else {
+                    Rgb888::new(0, 0, 0)
+                }
+            }
+            Pattern::White => Rgb888::new(0xff, 0xff, 0xff),
+            Pattern::Black => Rgb888::new(0, 0, 0),
+            Pattern::Alignment => {
+                let (right, bottom) = (width - 1, height - 1);
+                let border = x == 0 || y == 0 || x == right || y == bottom;
+                let crosshair = x == width / 2 || y == height / 2;
+                if border || crosshair {
+                    return Rgb888::new(0xff, 0xff, 0xff);
+                }
+                let left = x <= MARKER_SIZE;
+                let top = y <= MARKER_SIZE;
+                let near_right = x + MARKER_SIZE >= right;
+                let near_bottom = y + MARKER_SIZE >= bottom;
+                match (left, top, near_right, near_bottom) {
+                    (true, true, _, _) => Rgb888::new(0xff, 0, 0),
+                    (_, true, true, _) => Rgb888::new(0, 0xff, 0),
+                    (true, _, _, true) => Rgb888::new(0, 0, 0xff),
+                    (_, _, true, true) => Rgb888::new(0xff, 0xff, 0),
+                    _ => Rgb888::new(0, 0, 0),
+                }
+            }
+        }
+    }
+}
+
+impl<IFACE, C> Ili9342C<IFACE, C>
+where
+    IFACE: WriteOnlyDataCommand,
+    C: PixelFormat,
+{
+    /// Fill the screen with a test pattern
+    ///
+    /// The pixels are generated while they are sent, no buffer is needed.
+    pub fn test_pattern(&mut self, pattern: Pattern) -> Result {
+        let (width, height) = (self.state.width, self.state.height);
+        let pixels = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| C::from_rgb888(pattern.color(x, y, width, height)).to_raw());
+        self.draw_raw_iter(0, 0, width as u16 - 1, height as u16 - 1, pixels)
+    }
+}
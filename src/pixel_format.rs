@@ -0,0 +1,300 @@
+//! Pixel formats supported by the display
+
+use display_interface::DataFormat::{U16BEIter, U16LEIter, U8Iter};
+use display_interface::WriteOnlyDataCommand;
+use embedded_graphics_core::pixelcolor::{
+    raw::{RawData, RawU16, RawU18},
+    PixelColor, Rgb565, Rgb666, Rgb888, RgbColor,
+};
+
+use crate::{ColorSet, Result};
+
+/// Color type of the pixel data written to the display memory
+///
+/// The format decides the PixelFormatSet value written during init, the color
+/// of the [DrawTarget](embedded_graphics_core::draw_target::DrawTarget) and the
+/// raw values accepted by the raw drawing methods.
+pub trait PixelFormat: PixelColor {
+    /// Value of the PixelFormatSet (0x3A) register for this format
+    const PIXEL_FORMAT_SET: u8;
+
+    /// Number of bytes sent per pixel, rounded up for packed formats
+    const BYTES_PER_PIXEL: usize;
+
+    /// Number of pixels packed together, transfers are only split between
+    /// packs
+    const PIXELS_PER_PACK: usize = 1;
+
+    /// Number of bytes of a pack of [Self::PIXELS_PER_PACK] pixels
+    const BYTES_PER_PACK: usize = Self::BYTES_PER_PIXEL;
+
+    /// Color Set (0x2D) table written during init, for formats expanded by
+    /// the lookup table of the controller
+    const COLOR_SET: Option<&'static ColorSet> = None;
+
+    /// Raw pixel value accepted by the raw drawing methods
+    type RawValue: Copy;
+
+    /// Convert a color to its raw value
+    fn to_raw(self) -> Self::RawValue;
+
+    /// Convert a 24-bit color to this format
+    fn from_rgb888(color: Rgb888) -> Self;
+
+    /// Convert this color to a 24-bit color
+    fn to_rgb888(self) -> Rgb888;
+
+    /// Send raw pixel values to the display memory
+    fn send_pixels<IFACE, I>(interface: &mut IFACE, pixels: I) -> Result
+    where
+        IFACE: WriteOnlyDataCommand,
+        I: Iterator<Item = Self::RawValue>;
+
+    /// Send raw pixel values least significant byte first, see
+    /// [PixelEndian]
+    ///
+    /// Formats sent as single bytes send them like [Self::send_pixels].
+    fn send_pixels_le<IFACE, I>(interface: &mut IFACE, pixels: I) -> Result
+    where
+        IFACE: WriteOnlyDataCommand,
+        I: Iterator<Item = Self::RawValue>,
+    {
+        Self::send_pixels(interface, pixels)
+    }
+
+    /// Number of bytes sent for a number of pixels
+    fn data_len(pixels: usize) -> usize {
+        pixels / Self::PIXELS_PER_PACK * Self::BYTES_PER_PACK
+            + pixels % Self::PIXELS_PER_PACK * Self::BYTES_PER_PIXEL
+    }
+}
+
+/// Byte order of the 16 bit pixel words on the bus
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PixelEndian {
+    /// Most significant byte first, what the controller expects after reset
+    #[default]
+    Big,
+    /// Least significant byte first, for interfaces or bridges swapping the
+    /// bytes of each word on the way to the controller
+    Little,
+    /// Least significant byte first, with the ENDIAN bit of Interface
+    /// Control (0xF6) set so the controller swaps them back
+    ///
+    /// The datasheet only supports this on the 8 bit parallel interfaces.
+    LittleController,
+}
+
+/// Send raw pixel values in a byte order
+pub(crate) fn send_pixels<C, IFACE, I>(
+    interface: &mut IFACE,
+    endian: PixelEndian,
+    pixels: I,
+) -> Result
+where
+    C: PixelFormat,
+    IFACE: WriteOnlyDataCommand,
+    I: Iterator<Item = C::RawValue>,
+{
+    match endian {
+        PixelEndian::Big => C::send_pixels(interface, pixels),
+        PixelEndian::Little | PixelEndian::LittleController => C::send_pixels_le(interface, pixels),
+    }
+}
+
+/// 16 bits per pixel, sent as 2 bytes
+impl PixelFormat for Rgb565 {
+    const PIXEL_FORMAT_SET: u8 = 0x55;
+
+    const BYTES_PER_PIXEL: usize = 2;
+
+    type RawValue = u16;
+
+    fn to_raw(self) -> u16 {
+        RawU16::from(self).into_inner()
+    }
+
+    fn from_rgb888(color: Rgb888) -> Self {
+        color.into()
+    }
+
+    fn to_rgb888(self) -> Rgb888 {
+        self.into()
+    }
+
+    fn send_pixels<IFACE, I>(interface: &mut IFACE, mut pixels: I) -> Result
+    where
+        IFACE: WriteOnlyDataCommand,
+        I: Iterator<Item = u16>,
+    {
+        interface.send_data(U16BEIter(&mut pixels))
+    }
+
+    fn send_pixels_le<IFACE, I>(interface: &mut IFACE, mut pixels: I) -> Result
+    where
+        IFACE: WriteOnlyDataCommand,
+        I: Iterator<Item = u16>,
+    {
+        interface.send_data(U16LEIter(&mut pixels))
+    }
+}
+
+/// 18 bits per pixel, sent as 3 bytes
+impl PixelFormat for Rgb666 {
+    const PIXEL_FORMAT_SET: u8 = 0x66;
+
+    const BYTES_PER_PIXEL: usize = 3;
+
+    type RawValue = u32;
+
+    fn to_raw(self) -> u32 {
+        RawU18::from(self).into_inner()
+    }
+
+    fn from_rgb888(color: Rgb888) -> Self {
+        // Round to the nearest level, like the embedded-graphics conversions
+        let convert = |c: u8| ((c as u16 * 63 + 127) / 255) as u8;
+        Rgb666::new(convert(color.r()), convert(color.g()), convert(color.b()))
+    }
+
+    fn to_rgb888(self) -> Rgb888 {
+        let convert = |c: u8| ((c as u16 * 255 + 31) / 63) as u8;
+        Rgb888::new(convert(self.r()), convert(self.g()), convert(self.b()))
+    }
+
+    fn send_pixels<IFACE, I>(interface: &mut IFACE, pixels: I) -> Result
+    where
+        IFACE: WriteOnlyDataCommand,
+        I: Iterator<Item = u32>,
+    {
+        // Each channel is sent in the upper 6 bits of its own byte
+        interface.send_data(U8Iter(&mut pixels.flat_map(|raw| {
+            [
+                ((raw >> 10) & 0xfc) as u8,
+                ((raw >> 4) & 0xfc) as u8,
+                ((raw << 2) & 0xfc) as u8,
+            ]
+        })))
+    }
+}
+
+/// 12-bit color with 4 bits per channel
+///
+/// Drawn with the 12 bit pixel format, which sends two pixels in three bytes
+/// and uses a quarter less bus traffic than [Rgb565]. The controller expands
+/// the channels with the [ColorSet] table, which is written during init.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, PartialOrd, Ord)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Rgb444(u16);
+
+impl Rgb444 {
+    /// Create a color from 4 bit channels, higher bits are ignored
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Self(((r as u16 & 0xf) << 8) | ((g as u16 & 0xf) << 4) | (b as u16 & 0xf))
+    }
+}
+
+impl PixelColor for Rgb444 {
+    type Raw = RawU16;
+}
+
+impl RgbColor for Rgb444 {
+    fn r(&self) -> u8 {
+        (self.0 >> 8) as u8 & 0xf
+    }
+
+    fn g(&self) -> u8 {
+        (self.0 >> 4) as u8 & 0xf
+    }
+
+    fn b(&self) -> u8 {
+        self.0 as u8 & 0xf
+    }
+
+    const MAX_R: u8 = 0xf;
+    const MAX_G: u8 = 0xf;
+    const MAX_B: u8 = 0xf;
+
+    const BLACK: Self = Self::new(0, 0, 0);
+    const RED: Self = Self::new(0xf, 0, 0);
+    const GREEN: Self = Self::new(0, 0xf, 0);
+    const BLUE: Self = Self::new(0, 0, 0xf);
+    const YELLOW: Self = Self::new(0xf, 0xf, 0);
+    const MAGENTA: Self = Self::new(0xf, 0, 0xf);
+    const CYAN: Self = Self::new(0, 0xf, 0xf);
+    const WHITE: Self = Self::new(0xf, 0xf, 0xf);
+}
+
+/// 12 bits per pixel, two pixels sent as 3 bytes
+///
+/// An odd last pixel of a transfer is sent as 2 bytes.
+impl PixelFormat for Rgb444 {
+    const PIXEL_FORMAT_SET: u8 = 0x33;
+
+    const BYTES_PER_PIXEL: usize = 2;
+
+    const PIXELS_PER_PACK: usize = 2;
+
+    const BYTES_PER_PACK: usize = 3;
+
+    const COLOR_SET: Option<&'static ColorSet> = Some(&ColorSet::LINEAR_12);
+
+    type RawValue = u16;
+
+    fn to_raw(self) -> u16 {
+        self.0
+    }
+
+    fn from_rgb888(color: Rgb888) -> Self {
+        let convert = |c: u8| ((c as u16 * 15 + 127) / 255) as u8;
+        Self::new(convert(color.r()), convert(color.g()), convert(color.b()))
+    }
+
+    fn to_rgb888(self) -> Rgb888 {
+        Rgb888::new(self.r() * 0x11, self.g() * 0x11, self.b() * 0x11)
+    }
+
+    fn send_pixels<IFACE, I>(interface: &mut IFACE, pixels: I) -> Result
+    where
+        IFACE: WriteOnlyDataCommand,
+        I: Iterator<Item = u16>,
+    {
+        interface.send_data(U8Iter(&mut Pack12 {
+            pixels,
+            bytes: [0; 3],
+            len: 0,
+            next: 0,
+        }))
+    }
+}
+
+/// Packs 12 bit pixels into bytes, `RG BR GB` for each pair
+struct Pack12<I> {
+    pixels: I,
+    bytes: [u8; 3],
+    len: usize,
+    next: usize,
+}
+
+impl<I: Iterator<Item = u16>> Iterator for Pack12<I> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        if self.next == self.len {
+            let first = self.pixels.next()?;
+            self.bytes[0] = (first >> 4) as u8;
+            self.bytes[1] = (first << 4) as u8;
+            self.len = 2;
+            if let Some(second) = self.pixels.next() {
+                self.bytes[1] |= (second >> 8) as u8 & 0xf;
+                self.bytes[2] = second as u8;
+                self.len = 3;
+            }
+            self.next = 0;
+        }
+        self.next += 1;
+        Some(self.bytes[self.next - 1])
+    }
+}
@@ -0,0 +1,280 @@
+//! Power and voltage settings of the panel
+
+use display_interface::{DisplayError, WriteOnlyDataCommand};
+use embedded_hal_0_2::blocking::delay::DelayMs;
+use embedded_hal_0_2::digital::v2::OutputPin;
+
+use crate::{
+    CabcMode, Command, FrameDivision, FrameRate, Ili9342C, ModeState, PixelFormat, Result,
+};
+
+/// Entry Mode Set value of normal operation
+const ENTRY_MODE_NORMAL: u8 = 0x07;
+/// Deep standby bit of Entry Mode Set
+const ENTRY_MODE_DEEP_STANDBY: u8 = 0x08;
+
+/// Reset the controller with its hardware reset pin
+///
+/// Waits the 120 ms the controller needs before accepting the init sequence.
+pub fn hard_reset<RST, DELAY>(rst: &mut RST, delay: &mut DELAY) -> Result
+where
+    RST: OutputPin,
+    DELAY: DelayMs<u16>,
+{
+    rst.set_low().map_err(|_| DisplayError::RSError)?;
+    delay.delay_ms(1);
+    rst.set_high().map_err(|_| DisplayError::RSError)?;
+    delay.delay_ms(120);
+    Ok(())
+}
+
+/// Power Control 1/2 (0xC0/0xC1) settings
+///
+/// The defaults are the values of the ILI9342C vendor init, check the panel
+/// datasheet before changing them.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PowerConfig {
+    /// VRH1 code, sets the GVDD level
+    pub vrh1: u8,
+    /// VRH2 code, sets the GVCL level
+    pub vrh2: u8,
+    /// BT code, sets the factor of the step-up circuits
+    pub bt: u8,
+}
+
+impl Default for PowerConfig {
+    fn default() -> Self {
+        Self {
+            vrh1: 0x12,
+            vrh2: 0x12,
+            bt: 0x03,
+        }
+    }
+}
+
+/// DDVDH step-up ratio of Pump Ratio Control (0xF7)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PumpRatio {
+    /// DDVDH = 2 x VCI
+    X2 = 0x20,
+    /// DDVDH = 3 x VCI
+    X3 = 0x30,
+}
+
+/// Power On Sequence Control (0xED) parameters
+///
+/// Soft start and power on order of the supply voltages, in the register
+/// layout of the datasheet. A common module value is `[0x64, 0x03, 0x12, 0x81]`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PowerOnSequence(pub [u8; 4]);
+
+/// Bundled frame rate, idle mode, CABC and brightness settings
+///
+/// The brightness and CABC settings only have an effect on modules with the
+/// backlight driven by the LEDPWM output of the controller.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PowerProfile {
+    /// 30 Hz in idle mode showing only 8 colors, CABC for moving images and
+    /// low brightness. Suits static status screens on battery.
+    Low,
+    /// 70 Hz, CABC for user interfaces and medium brightness
+    Balanced,
+    /// 119 Hz, no CABC and full brightness
+    Performance,
+}
+
+impl<IFACE, C> Ili9342C<IFACE, C>
+where
+    IFACE: WriteOnlyDataCommand,
+    C: PixelFormat,
+{
+    /// Put the panel into deep standby, its lowest power state
+    ///
+    /// The display is turned off and enters sleep before deep standby is
+    /// enabled with Entry Mode Set. The controller ignores all commands until
+    /// it is woken with [Self::wake_from_deep_standby].
+    pub fn enter_deep_standby<DELAY>(&mut self, delay: &mut DELAY) -> Result
+    where
+        DELAY: DelayMs<u16>,
+    {
+        self.display_mode(ModeState::Off)?;
+        self.sleep_mode(ModeState::On)?;
+        // Wait 5ms after Sleep In before sending commands
+        delay.delay_ms(5);
+        self.command(
+            Command::EntryModeSet,
+            &[ENTRY_MODE_NORMAL | ENTRY_MODE_DEEP_STANDBY],
+        )
+    }
+
+    /// Leave deep standby with a hardware reset and initialize the panel
+    /// again with [Self::reinit]
+    ///
+    /// The orientation, mirroring and the other settings sent before are
+    /// restored, the panel is left awake and on. The display memory content
+    /// is lost and has to be redrawn.
+    pub fn wake_from_deep_standby<RST, DELAY>(&mut self, rst: &mut RST, delay: &mut DELAY) -> Result
+    where
+        RST: OutputPin,
+        DELAY: DelayMs<u16>,
+    {
+        hard_reset(rst, delay)?;
+        self.state.panel.sleeping = false;
+        self.state.panel.display_on = true;
+        self.reinit(delay)
+    }
+
+    /// Turn the display off and put it to sleep before its supply is cut,
+    /// returning the interface
+    ///
+    /// The interface is returned with the error if a command fails, so the
+    /// bus can be used again.
+    pub fn shutdown<DELAY>(mut self, delay: &mut DELAY) -> Result<IFACE, (IFACE, DisplayError)>
+    where
+        DELAY: DelayMs<u16>,
+    {
+        let result = self
+            .display_mode(ModeState::Off)
+            .and_then(|()| self.sleep_mode(ModeState::On));
+        if let Err(error) = result {
+            return Err((self.interface, error));
+        }
+        // The panel needs 120ms in sleep to discharge before power off
+        delay.delay_ms(120);
+        Ok(self.interface)
+    }
+
+    /// Like [Self::shutdown], turning the backlight off first
+    ///
+    /// `backlight` is the pin enabling the backlight, driven low to disable
+    /// it. The panel is shut down even if the pin fails, its error is then
+    /// returned as [DisplayError::BusWriteError].
+    pub fn shutdown_with_backlight<DELAY, BL>(
+        self,
+        delay: &mut DELAY,
+        backlight: &mut BL,
+    ) -> Result<IFACE, (IFACE, DisplayError)>
+    where
+        DELAY: DelayMs<u16>,
+        BL: OutputPin,
+    {
+        let backlight = backlight.set_low();
+        let interface = self.shutdown(delay)?;
+        match backlight {
+            Ok(()) => Ok(interface),
+            Err(_) => Err((interface, DisplayError::BusWriteError)),
+        }
+    }
+
+    /// Apply a power profile
+    pub fn set_power_profile(&mut self, profile: PowerProfile) -> Result {
+        let (division, rate, idle, cabc, brightness) = match profile {
+            PowerProfile::Low => (
+                FrameDivision::Div2,
+                FrameRate::Hz61,
+                ModeState::On,
+                CabcMode::MovingImage,
+                0x40,
+            ),
+            PowerProfile::Balanced => (
+                FrameDivision::Div1,
+                FrameRate::Hz70,
+                ModeState::Off,
+                CabcMode::UserInterface,
+                0xb0,
+            ),
+            PowerProfile::Performance => (
+                FrameDivision::Div1,
+                FrameRate::Hz119,
+                ModeState::Off,
+                CabcMode::Off,
+                0xff,
+            ),
+        };
+        if let ModeState::On = idle {
+            self.set_idle_frame_rate(division, rate)?;
+        } else {
+            self.set_frame_rate(division, rate)?;
+        }
+        self.idle_mode(idle)?;
+        self.set_brightness_control(true)?;
+        self.set_cabc(cabc)?;
+        self.set_brightness(brightness)
+    }
+
+    /// Only refresh the panel lines `start..=end`, in idle mode and at a low
+    /// frame rate, for always on screens like a clock strip
+    ///
+    /// The lines are in the gate scan order of the panel, the rows of
+    /// [Orientation::Landscape](crate::Orientation::Landscape). Lines outside
+    /// the area show the non-display level of [Self::set_display_function].
+    /// The frame rate is set for both the idle and the partial mode, restore
+    /// full operation with [Self::exit_always_on].
+    pub fn enter_always_on(
+        &mut self,
+        start: u16,
+        end: u16,
+        division: FrameDivision,
+        rate: FrameRate,
+    ) -> Result {
+        if start > end {
+            return Err(DisplayError::OutOfBoundsError);
+        }
+        let [start_hi, start_lo] = start.to_be_bytes();
+        let [end_hi, end_lo] = end.to_be_bytes();
+        self.command(Command::PartialArea, &[start_hi, start_lo, end_hi, end_lo])?;
+        self.set_idle_frame_rate(division, rate)?;
+        self.set_partial_frame_rate(division, rate)?;
+        self.command(Command::PartialModeOn, &[])?;
+        self.idle_mode(ModeState::On)
+    }
+
+    /// Leave the partial and idle mode of [Self::enter_always_on]
+    pub fn exit_always_on(&mut self) -> Result {
+        self.idle_mode(ModeState::Off)?;
+        self.command(Command::NormalModeOn, &[])
+    }
+
+    /// Write the Power Control 1/2 registers
+    pub fn set_power_config(&mut self, config: &PowerConfig) -> Result {
+        self.command(Command::PowerControl1, &[config.vrh1, config.vrh2])?;
+        self.command(Command::PowerControl2, &[config.bt])
+    }
+
+    /// Write Power On Sequence Control
+    pub fn set_power_on_sequence(&mut self, sequence: PowerOnSequence) -> Result {
+        self.command(Command::PowerOnSequenceControl, &sequence.0)
+    }
+
+    /// Set the ratio of the DDVDH charge pump
+    pub fn set_pump_ratio(&mut self, ratio: PumpRatio) -> Result {
+        self.command(Command::PumpRatioControl, &[ratio as u8])
+    }
+
+    /// Set the VCOMH and VCOML voltages with VCOM Control 1 (0xC5)
+    ///
+    /// Both values are 7 bit register codes, see the VMH and VML tables of
+    /// the datasheet.
+    pub fn set_vcom_control1(&mut self, vmh: u8, vml: u8) -> Result {
+        self.command(Command::VcomControl1, &[vmh & 0x7f, vml & 0x7f])
+    }
+
+    /// Set the VCOM offset with VCOM Control 2 (0xC7)
+    ///
+    /// `offset` is the 7 bit VMF code, `None` disables the offset and uses
+    /// the NV memory value instead.
+    pub fn set_vcom_control2(&mut self, offset: Option<u8>) -> Result {
+        match offset {
+            Some(offset) => self.command(Command::VcomControl2, &[0x80 | (offset & 0x7f)]),
+            None => self.command(Command::VcomControl2, &[0x00]),
+        }
+    }
+}
@@ -0,0 +1,106 @@
+//! Progress bar drawn without embedded-graphics
+
+use display_interface::WriteOnlyDataCommand;
+
+use crate::{Ili9342C, PixelFormat, Result};
+
+/// Horizontal progress bar drawn with raw window fills
+///
+/// Meant for bootloaders and firmware update screens. Updating the progress
+/// only fills the part of the bar that changed.
+///
+/// ```ignore
+/// let mut bar = ProgressBar::new(40, 110, 240, 20, 0x07e0, 0x0000);
+/// bar.draw(&mut display)?;
+/// for (n, block) in image.chunks(4096).enumerate() {
+///     flash.write(block)?;
+///     bar.set_progress(&mut display, (n * 100 / blocks) as u8)?;
+/// }
+/// ```
+pub struct ProgressBar<C: PixelFormat> {
+    x: u16,
+    y: u16,
+    width: u16,
+    height: u16,
+    color: C::RawValue,
+    background: C::RawValue,
+    filled: u16,
+}
+
+impl<C: PixelFormat> ProgressBar<C> {
+    /// Bar with its top left corner at `x`, `y`, in raw colors of the display
+    pub fn new(
+        x: u16,
+        y: u16,
+        width: u16,
+        height: u16,
+        color: C::RawValue,
+        background: C::RawValue,
+    ) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+            color,
+            background,
+            filled: 0,
+        }
+    }
+
+    /// Percentage shown, rounded down to whole columns
+    pub fn progress(&self) -> u8 {
+        match self.width {
+            0 => 0,
+            width => (self.filled as u32 * 100 / width as u32) as u8,
+        }
+    }
+
+    /// Fill the columns `start..end` with a color
+    fn fill<IFACE>(
+        &self,
+        display: &mut Ili9342C<IFACE, C>,
+        start: u16,
+        end: u16,
+        color: C::RawValue,
+    ) -> Result
+    where
+        IFACE: WriteOnlyDataCommand,
+    {
+        if start >= end || self.height == 0 {
+            return Ok(());
+        }
+        let count = (end - start) as usize * self.height as usize;
+        display.draw_raw_iter(
+            self.x + start,
+            self.y,
+            self.x + end - 1,
+            self.y + self.height - 1,
+            core::iter::repeat_n(color, count),
+        )
+    }
+
+    /// Draw the whole bar at its current progress
+    pub fn draw<IFACE>(&self, display: &mut Ili9342C<IFACE, C>) -> Result
+    where
+        IFACE: WriteOnlyDataCommand,
+    {
+        self.fill(display, 0, self.filled, self.color)?;
+        self.fill(display, self.filled, self.width, self.background)
+    }
+
+    /// Show a percentage from 0 to 100, larger values are clamped
+    pub fn set_progress<IFACE>(&mut self, display: &mut Ili9342C<IFACE, C>, percent: u8) -> Result
+    where
+        IFACE: WriteOnlyDataCommand,
+    {
+        let filled = (self.width as u32 * percent.min(100) as u32 / 100) as u16;
+        if filled > self.filled {
+            self.fill(display, self.filled, filled, self.color)?;
+        } else {
+            self.fill(display, filled, self.filled, self.background)?;
+        }
+        self.filled = filled;
+        Ok(())
+    }
+}
@@ -0,0 +1,267 @@
+//! Ratatui backend, see the `ratatui` feature
+
+use core::fmt;
+
+use display_interface::{DisplayError, WriteOnlyDataCommand};
+use embedded_graphics::{
+    mono_font::{ascii::FONT_6X10, MonoTextStyleBuilder},
+    text::{Baseline, Text},
+    Drawable,
+};
+use embedded_graphics_core::{
+    draw_target::DrawTarget,
+    geometry::{Point, Size},
+    pixelcolor::Rgb888,
+    primitives::Rectangle,
+};
+use ratatui_core::{
+    backend::{Backend, ClearType, WindowSize},
+    buffer::Cell,
+    layout::{Position, Size as CellSize},
+    style::{Color, Modifier},
+};
+
+use crate::{color::ANSI, Ili9342C, PixelFormat};
+
+/// Width of a cell in pixels
+const CELL_WIDTH: u32 = 6;
+/// Height of a cell in pixels
+const CELL_HEIGHT: u32 = 10;
+
+/// [DisplayError] as a [core::error::Error], as required by [Backend]
+#[derive(Debug)]
+pub struct BackendError(pub DisplayError);
+
+impl fmt::Display for BackendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "display error: {:?}", self.0)
+    }
+}
+
+impl core::error::Error for BackendError {}
+
+impl From<DisplayError> for BackendError {
+    fn from(error: DisplayError) -> Self {
+        Self(error)
+    }
+}
+
+/// Ratatui [Backend] drawing the terminal grid with a 6x10 pixel font
+///
+/// Only the cells the terminal reports as changed are drawn, each with its
+/// own small window write. Reversed and underlined cells are supported, other
+/// modifiers are ignored. The cursor is tracked but not drawn.
+///
+/// ```ignore
+/// let mut terminal = Terminal::new(RatatuiBackend::new(display))?;
+/// terminal.draw(|frame| frame.render_widget(Paragraph::new("Hello"), frame.area()))?;
+/// ```
+pub struct RatatuiBackend<IFACE, C> {
+    display: Ili9342C<IFACE, C>,
+    cursor: Position,
+}
+
+impl<IFACE, C> RatatuiBackend<IFACE, C>
+where
+    IFACE: WriteOnlyDataCommand,
+    C: PixelFormat,
+{
+    /// Use a display as terminal
+    pub fn new(display: Ili9342C<IFACE, C>) -> Self {
+        Self {
+            display,
+            cursor: Position::ORIGIN,
+        }
+    }
+
+    /// Access the display
+    pub fn display_mut(&mut self) -> &mut Ili9342C<IFACE, C> {
+        &mut self.display
+    }
+
+    /// Release the display
+    pub fn into_inner(self) -> Ili9342C<IFACE, C> {
+        self.display
+    }
+
+    /// Number of columns and rows
+    fn grid(&self) -> CellSize {
+        CellSize::new(
+            (self.display.width() as u32 / CELL_WIDTH) as u16,
+            (self.display.height() as u32 / CELL_HEIGHT) as u16,
+        )
+    }
+
+    /// Fill the cells `start..end` of a row with the background color
+    fn clear_cells(&mut self, row: u16, start: u16, end: u16) -> Result<(), DisplayError> {
+        let area = Rectangle::new(
+            Point::new(
+                (start as u32 * CELL_WIDTH) as i32,
+                (row as u32 * CELL_HEIGHT) as i32,
+            ),
+            Size::new(end.saturating_sub(start) as u32 * CELL_WIDTH, CELL_HEIGHT),
+        );
+        self.display
+            .fill_solid(&area, to_color(Color::Reset, false))
+    }
+
+    /// Fill whole rows with the background color
+    fn clear_rows(&mut self, start: u16, end: u16) -> Result<(), DisplayError> {
+        let area = Rectangle::new(
+            Point::new(0, (start as u32 * CELL_HEIGHT) as i32),
+            Size::new(
+                self.display.width() as u32,
+                end.saturating_sub(start) as u32 * CELL_HEIGHT,
+            ),
+        );
+        self.display
+            .fill_solid(&area, to_color(Color::Reset, false))
+    }
+}
+
+/// Convert a terminal color, [Color::Reset] is white text on black
+fn to_color<C: PixelFormat>(color: Color, foreground: bool) -> C {
+    let index = match color {
+        Color::Reset => {
+            if foreground {
+                15
+            } else {
+                0
+            }
+        }
+        Color::Black => 0,
+        Color::Red => 1,
+        Color::Green => 2,
+        Color::Yellow => 3,
+        Color::Blue => 4,
+        Color::Magenta => 5,
+        Color::Cyan => 6,
+        Color::Gray => 7,
+        Color::DarkGray => 8,
+        Color::LightRed => 9,
+        Color::LightGreen => 10,
+        Color::LightYellow => 11,
+        Color::LightBlue => 12,
+        Color::LightMagenta => 13,
+        Color::LightCyan => 14,
+        Color::White => 15,
+        Color::Rgb(r, g, b) => return C::from_rgb888(Rgb888::new(r, g, b)),
+        Color::Indexed(index) => index,
+    };
+    let (r, g, b) = match index {
+        0..=15 => ANSI[index as usize],
+        16..=231 => {
+            // 6x6x6 color cube
+            let level = |value: u8| if value == 0 { 0 } else { 55 + value * 40 };
+            let index = index - 16;
+            (level(index / 36), level(index / 6 % 6), level(index % 6))
+        }
+        _ => {
+            let gray = 8 + (index - 232) * 10;
+            (gray, gray, gray)
+        }
+    };
+    C::from_rgb888(Rgb888::new(r, g, b))
+}
+
+impl<IFACE, C> Backend for RatatuiBackend<IFACE, C>
+where
+    IFACE: WriteOnlyDataCommand,
+    C: PixelFormat,
+{
+    type Error = BackendError;
+
+    fn draw<'a, I>(&mut self, content: I) -> Result<(), Self::Error>
+    where
+        I: Iterator<Item = (u16, u16, &'a Cell)>,
+    {
+        let grid = self.grid();
+        for (x, y, cell) in content {
+            if x >= grid.width || y >= grid.height {
+                continue;
+            }
+            let mut fg = to_color::<C>(cell.fg, true);
+            let mut bg = to_color::<C>(cell.bg, false);
+            if cell.modifier.contains(Modifier::REVERSED) {
+                core::mem::swap(&mut fg, &mut bg);
+            }
+            let mut style = MonoTextStyleBuilder::new()
+                .font(&FONT_6X10)
+                .text_color(fg)
+                .background_color(bg);
+            if cell.modifier.contains(Modifier::UNDERLINED) {
+                style = style.underline();
+            }
+            let mut symbol = [0; 4];
+            let symbol = match cell.symbol().chars().next() {
+                Some(c) if c.is_ascii() => c.encode_utf8(&mut symbol),
+                // The font only has ASCII glyphs
+                Some(_) => "?",
+                None => " ",
+            };
+            let origin = Point::new(
+                (x as u32 * CELL_WIDTH) as i32,
+                (y as u32 * CELL_HEIGHT) as i32,
+            );
+            Text::with_baseline(symbol, origin, style.build(), Baseline::Top)
+                .draw(&mut self.display)?;
+        }
+        Ok(())
+    }
+
+    fn hide_cursor(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn show_cursor(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn get_cursor_position(&mut self) -> Result<Position, Self::Error> {
+        Ok(self.cursor)
+    }
+
+    fn set_cursor_position<P: Into<Position>>(&mut self, position: P) -> Result<(), Self::Error> {
+        self.cursor = position.into();
+        Ok(())
+    }
+
+    fn clear(&mut self) -> Result<(), Self::Error> {
+        self.display.clear(to_color(Color::Reset, false))?;
+        Ok(())
+    }
+
+    fn clear_region(&mut self, clear_type: ClearType) -> Result<(), Self::Error> {
+        let grid = self.grid();
+        let Position { x, y } = self.cursor;
+        match clear_type {
+            ClearType::All => return self.clear(),
+            ClearType::AfterCursor => {
+                self.clear_cells(y, x, grid.width)?;
+                self.clear_rows(y + 1, grid.height)?;
+            }
+            ClearType::BeforeCursor => {
+                self.clear_rows(0, y)?;
+                self.clear_cells(y, 0, x + 1)?;
+            }
+            ClearType::CurrentLine => self.clear_rows(y, y + 1)?,
+            ClearType::UntilNewLine => self.clear_cells(y, x, grid.width)?,
+        }
+        Ok(())
+    }
+
+    fn size(&self) -> Result<CellSize, Self::Error> {
+        Ok(self.grid())
+    }
+
+    fn window_size(&mut self) -> Result<WindowSize, Self::Error> {
+        Ok(WindowSize {
+            columns_rows: self.grid(),
+            pixels: CellSize::new(self.display.width() as u16, self.display.height() as u16),
+        })
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
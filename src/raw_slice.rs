@@ -0,0 +1,41 @@
+//! Drawing RGB565 pixel slices, including byte swapped ones
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_graphics_core::pixelcolor::Rgb565;
+
+use crate::{Ili9342C, Result};
+
+impl<IFACE> Ili9342C<IFACE, Rgb565>
+where
+    IFACE: WriteOnlyDataCommand,
+{
+    /// Draw a slice of raw RGB565 pixels to a window, like
+    /// [Self::draw_raw_iter]
+    ///
+    /// Set `swapped` when the bytes of each pixel are already stored in the
+    /// order sent to the display, most significant byte first in memory, as
+    /// DMA friendly framebuffers and LVGL with `LV_COLOR_16_SWAP` keep them.
+    /// The memory is then sent as is without touching the pixels.
+    pub fn draw_raw_slice(
+        &mut self,
+        x0: u16,
+        y0: u16,
+        x1: u16,
+        y1: u16,
+        pixels: &[u16],
+        swapped: bool,
+    ) -> Result {
+        if !swapped {
+            return self.draw_raw_iter(x0, y0, x1, y1, pixels.iter().copied());
+        }
+        // SAFETY: u16 has no padding and a stricter alignment than u8
+        let bytes = unsafe {
+            core::slice::from_raw_parts(
+                pixels.as_ptr() as *const u8,
+                core::mem::size_of_val(pixels),
+            )
+        };
+        self.start_memory_write(x0, y0, x1, y1)?;
+        self.write_pixel_bytes(bytes)
+    }
+}
@@ -0,0 +1,87 @@
+//! Reading registers back from the panel
+
+use display_interface::{DataFormat, DisplayError, WriteOnlyDataCommand};
+use embedded_hal::{
+    digital::OutputPin,
+    spi::{Operation, SpiDevice},
+};
+
+use crate::{Command, Ili9342C, PixelFormat, Result, SpiInterface};
+
+/// Interface that can also read parameters back from the panel
+///
+/// Reading needs the data output of the panel to be wired, e.g. SDO to MISO
+/// for SPI.
+pub trait ReadDataCommand: WriteOnlyDataCommand {
+    /// Send a command and read the bytes returned by the panel
+    ///
+    /// The bytes are returned as read, many registers start with a dummy
+    /// byte.
+    fn read_data(&mut self, command: u8, buffer: &mut [u8]) -> Result;
+}
+
+/// Controller detected by [probe]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Controller {
+    /// ILI9342C, natively 320x240
+    Ili9342C,
+    /// ILI9341, natively 240x320
+    Ili9341,
+    /// Another controller or no panel, with the ID bytes read
+    Unknown([u8; 3]),
+}
+
+/// Read the ID bytes of Read ID4 (0xD3), skipping the dummy byte
+fn read_id4<IFACE: ReadDataCommand>(interface: &mut IFACE) -> Result<[u8; 3]> {
+    let mut buffer = [0; 4];
+    interface.read_data(Command::ReadId4 as u8, &mut buffer)?;
+    Ok([buffer[1], buffer[2], buffer[3]])
+}
+
+/// Detect the controller behind an interface from its ID
+///
+/// Run this before creating the driver, e.g. to choose the init settings of
+/// a product variant. The ILI9342C only answers after its extended commands
+/// are enabled, so [probe] sends the enable sequence if the first read is not
+/// recognized.
+pub fn probe<IFACE: ReadDataCommand>(interface: &mut IFACE) -> Result<Controller> {
+    let detect = |id: [u8; 3]| match id {
+        [_, 0x93, 0x42] => Some(Controller::Ili9342C),
+        [_, 0x93, 0x41] => Some(Controller::Ili9341),
+        _ => None,
+    };
+    if let Some(controller) = detect(read_id4(interface)?) {
+        return Ok(controller);
+    }
+    interface.send_commands(DataFormat::U8(&[Command::ExtC as u8]))?;
+    interface.send_data(DataFormat::U8(&[0xff, 0x93, 0x42]))?;
+    let id = read_id4(interface)?;
+    Ok(detect(id).unwrap_or(Controller::Unknown(id)))
+}
+
+impl<SPI, DC, const BUF: usize> ReadDataCommand for SpiInterface<SPI, DC, BUF>
+where
+    SPI: SpiDevice,
+    DC: OutputPin,
+{
+    fn read_data(&mut self, command: u8, buffer: &mut [u8]) -> Result {
+        // Chip select has to stay low between the command and the read
+        self.dc_mut().set_low().map_err(|_| DisplayError::DCError)?;
+        self.spi_mut()
+            .transaction(&mut [Operation::Write(&[command]), Operation::Read(buffer)])
+            .map_err(|_| DisplayError::BusWriteError)
+    }
+}
+
+impl<IFACE, C> Ili9342C<IFACE, C>
+where
+    IFACE: ReadDataCommand,
+    C: PixelFormat,
+{
+    /// Read the three ID bytes of Read ID4 (0xD3), the driver IC version and
+    /// model, e.g. `[0x00, 0x93, 0x42]`
+    pub fn read_id(&mut self) -> Result<[u8; 3]> {
+        read_id4(&mut self.interface)
+    }
+}
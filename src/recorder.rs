@@ -0,0 +1,224 @@
+//! Recording the command stream for later transmission
+
+use display_interface::{DataFormat, DisplayError, WriteOnlyDataCommand};
+
+use crate::Result;
+
+const COMMAND: u8 = 0;
+const DATA: u8 = 1;
+const HEADER_LEN: usize = 3;
+/// Start of a serialized stream, with the format version in the last byte
+const MAGIC: [u8; 4] = *b"ILR\x01";
+
+/// Part of a recorded command stream
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Recorded<'a> {
+    /// Command bytes, sent with the data/command pin low
+    Command(&'a [u8]),
+    /// Data bytes, sent with the data/command pin high
+    Data(&'a [u8]),
+}
+
+/// Interface recording the command stream into a buffer
+///
+/// Drive an [Ili9342C](crate::Ili9342C) with the recorder to build a frame,
+/// then send it to the real interface with [CommandRecorder::replay], for
+/// example from another priority or core. Running out of buffer space fails
+/// with [DisplayError::OutOfBoundsError].
+///
+/// ```ignore
+/// let mut display = Ili9342C::from_parts(CommandRecorder::new(&mut buffer), state);
+/// display.clear(Rgb565::BLACK)?;
+/// let (recorder, state) = display.release();
+/// recorder.replay(&mut iface)?;
+/// ```
+///
+/// The stream is stored as entries of a kind byte, a big endian 16 bit
+/// length and the bytes.
+pub struct CommandRecorder<'b> {
+    buffer: &'b mut [u8],
+    len: usize,
+}
+
+impl<'b> CommandRecorder<'b> {
+    /// Record into a buffer
+    pub fn new(buffer: &'b mut [u8]) -> Self {
+        Self { buffer, len: 0 }
+    }
+
+    /// Recorded bytes
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buffer[..self.len]
+    }
+
+    /// Number of recorded bytes
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether nothing was recorded
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Drop the recorded stream
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Iterate over the recorded commands and data
+    pub fn entries(&self) -> impl Iterator<Item = Recorded<'_>> {
+        entries(self.as_bytes())
+    }
+
+    /// Send the recorded stream to an interface
+    pub fn replay<IFACE: WriteOnlyDataCommand>(&self, interface: &mut IFACE) -> Result {
+        replay(self.as_bytes(), interface)
+    }
+
+    /// Write the stream in the serialized format read by
+    /// [RecordedStream::from_bytes], in chunks
+    ///
+    /// ```ignore
+    /// recorder.serialize(&mut |chunk| rtt_channel.write(chunk));
+    /// ```
+    pub fn serialize<F: FnMut(&[u8]) + ?Sized>(&self, write: &mut F) {
+        write(&MAGIC);
+        write(self.as_bytes());
+    }
+
+    /// Release the buffer
+    pub fn into_inner(self) -> &'b mut [u8] {
+        self.buffer
+    }
+
+    fn record(&mut self, kind: u8, bytes: impl Iterator<Item = u8>) -> Result {
+        let mut header = None;
+        for byte in bytes {
+            let start = match header {
+                Some(start) if self.len - start - HEADER_LEN < u16::MAX as usize => start,
+                _ => {
+                    if self.len + HEADER_LEN > self.buffer.len() {
+                        return Err(DisplayError::OutOfBoundsError);
+                    }
+                    self.buffer[self.len] = kind;
+                    header = Some(self.len);
+                    self.len += HEADER_LEN;
+                    self.len - HEADER_LEN
+                }
+            };
+            *self
+                .buffer
+                .get_mut(self.len)
+                .ok_or(DisplayError::OutOfBoundsError)? = byte;
+            self.len += 1;
+            let entry_len = (self.len - start - HEADER_LEN) as u16;
+            self.buffer[start + 1..start + HEADER_LEN].copy_from_slice(&entry_len.to_be_bytes());
+        }
+        Ok(())
+    }
+
+    fn record_format(&mut self, kind: u8, data: DataFormat<'_>) -> Result {
+        match data {
+            DataFormat::U8(slice) => self.record(kind, slice.iter().copied()),
+            DataFormat::U16(slice) => {
+                self.record(kind, slice.iter().flat_map(|value| value.to_ne_bytes()))
+            }
+            DataFormat::U16BE(slice) => {
+                self.record(kind, slice.iter().flat_map(|value| value.to_be_bytes()))
+            }
+            DataFormat::U16LE(slice) => {
+                self.record(kind, slice.iter().flat_map(|value| value.to_le_bytes()))
+            }
+            DataFormat::U8Iter(iter) => self.record(kind, iter),
+            DataFormat::U16BEIter(iter) => self.record(kind, iter.flat_map(u16::to_be_bytes)),
+            DataFormat::U16LEIter(iter) => self.record(kind, iter.flat_map(u16::to_le_bytes)),
+            _ => Err(DisplayError::DataFormatNotImplemented),
+        }
+    }
+}
+
+impl WriteOnlyDataCommand for CommandRecorder<'_> {
+    fn send_commands(&mut self, cmd: DataFormat<'_>) -> Result {
+        self.record_format(COMMAND, cmd)
+    }
+
+    fn send_data(&mut self, buf: DataFormat<'_>) -> Result {
+        self.record_format(DATA, buf)
+    }
+}
+
+/// Serialized command stream, e.g. dumped from a device with
+/// [CommandRecorder::serialize]
+///
+/// A host can replay the stream into a [Simulator](crate::Simulator) to see
+/// exactly what the device drew.
+///
+/// ```ignore
+/// let stream = RecordedStream::from_bytes(&std::fs::read("dump.ilr")?)?;
+/// let mut simulator = Simulator::new();
+/// stream.replay(&mut simulator)?;
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct RecordedStream<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> RecordedStream<'a> {
+    /// Read a serialized stream, failing with
+    /// [DisplayError::InvalidFormatError] if it is not in the serialized
+    /// format or truncated
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self> {
+        let bytes = bytes
+            .strip_prefix(&MAGIC)
+            .ok_or(DisplayError::InvalidFormatError)?;
+        let length: usize = entries(bytes)
+            .map(|entry| match entry {
+                Recorded::Command(bytes) | Recorded::Data(bytes) => HEADER_LEN + bytes.len(),
+            })
+            .sum();
+        if length != bytes.len() {
+            return Err(DisplayError::InvalidFormatError);
+        }
+        Ok(Self { bytes })
+    }
+
+    /// Iterate over the recorded commands and data
+    pub fn entries(&self) -> impl Iterator<Item = Recorded<'a>> {
+        entries(self.bytes)
+    }
+
+    /// Send the stream to an interface
+    pub fn replay<IFACE: WriteOnlyDataCommand>(&self, interface: &mut IFACE) -> Result {
+        replay(self.bytes, interface)
+    }
+}
+
+/// Iterate over the entries of a recorded stream, stopping at the first
+/// malformed entry
+fn entries(mut bytes: &[u8]) -> impl Iterator<Item = Recorded<'_>> {
+    core::iter::from_fn(move || {
+        let [kind, high, low, rest @ ..] = bytes else {
+            return None;
+        };
+        let len = u16::from_be_bytes([*high, *low]) as usize;
+        let payload = rest.get(..len)?;
+        bytes = &rest[len..];
+        match *kind {
+            COMMAND => Some(Recorded::Command(payload)),
+            DATA => Some(Recorded::Data(payload)),
+            _ => None,
+        }
+    })
+}
+
+/// Send a recorded stream to an interface
+fn replay<IFACE: WriteOnlyDataCommand>(bytes: &[u8], interface: &mut IFACE) -> Result {
+    for entry in entries(bytes) {
+        match entry {
+            Recorded::Command(bytes) => interface.send_commands(DataFormat::U8(bytes))?,
+            Recorded::Data(bytes) => interface.send_data(DataFormat::U8(bytes))?,
+        }
+    }
+    Ok(())
+}
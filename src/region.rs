@@ -0,0 +1,129 @@
+//! Draw targets for parts of the display
+
+use display_interface::{DisplayError, WriteOnlyDataCommand};
+use embedded_graphics_core::{
+    draw_target::DrawTarget,
+    geometry::{Dimensions, OriginDimensions, Size},
+    primitives::{PointsIter, Rectangle},
+    Pixel,
+};
+
+use crate::{Ili9342C, PixelFormat};
+
+/// [DrawTarget] for a rectangle of the display, see [Ili9342C::region]
+///
+/// Coordinates are relative to the top left corner of the rectangle and
+/// drawing is clipped to it, so a component drawing into its region cannot
+/// overwrite the rest of the screen.
+pub struct Region<'a, IFACE, C> {
+    display: &'a mut Ili9342C<IFACE, C>,
+    area: Rectangle,
+}
+
+impl<IFACE, C> Region<'_, IFACE, C>
+where
+    IFACE: WriteOnlyDataCommand,
+    C: PixelFormat,
+{
+    /// Area of the region on the display
+    pub fn area(&self) -> Rectangle {
+        self.area
+    }
+
+    /// Part of the region
+    pub fn region(&mut self, area: Rectangle) -> Region<'_, IFACE, C> {
+        let area =
+            Rectangle::new(area.top_left + self.area.top_left, area.size).intersection(&self.area);
+        Region {
+            display: &mut *self.display,
+            area,
+        }
+    }
+
+    /// Clip an area in region coordinates and move it to display coordinates
+    fn clip(&self, area: &Rectangle) -> Rectangle {
+        Rectangle::new(area.top_left + self.area.top_left, area.size).intersection(&self.area)
+    }
+}
+
+impl<IFACE, C> Ili9342C<IFACE, C>
+where
+    IFACE: WriteOnlyDataCommand,
+    C: PixelFormat,
+{
+    /// Draw target for a rectangle of the display, clipped to the display
+    ///
+    /// ```ignore
+    /// let mut status_bar = display.region(Rectangle::new(Point::zero(), Size::new(320, 20)));
+    /// Text::new("12:00", Point::new(4, 14), style).draw(&mut status_bar)?;
+    /// ```
+    pub fn region(&mut self, area: Rectangle) -> Region<'_, IFACE, C> {
+        let area = area.intersection(&self.bounding_box());
+        Region {
+            display: self,
+            area,
+        }
+    }
+}
+
+impl<IFACE, C> OriginDimensions for Region<'_, IFACE, C> {
+    fn size(&self) -> Size {
+        self.area.size
+    }
+}
+
+impl<IFACE, C> DrawTarget for Region<'_, IFACE, C>
+where
+    IFACE: WriteOnlyDataCommand,
+    C: PixelFormat,
+{
+    type Color = C;
+
+    type Error = DisplayError;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let area = self.area;
+        self.display.draw_iter(
+            pixels
+                .into_iter()
+                .map(|Pixel(point, color)| Pixel(point + area.top_left, color))
+                .filter(|Pixel(point, _)| area.contains(*point)),
+        )
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        let clipped = self.clip(area);
+        let moved = Rectangle::new(area.top_left + self.area.top_left, area.size);
+        if clipped == moved {
+            self.display.fill_contiguous(&moved, colors)
+        } else if clipped.is_zero_sized() {
+            Ok(())
+        } else {
+            // One window for the visible part, skipping the colors outside
+            self.display.fill_contiguous(
+                &clipped,
+                moved
+                    .points()
+                    .zip(colors)
+                    .filter(|(point, _)| clipped.contains(*point))
+                    .map(|(_, color)| color),
+            )
+        }
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let clipped = self.clip(area);
+        self.display.fill_solid(&clipped, color)
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        let area = self.area;
+        self.display.fill_solid(&area, color)
+    }
+}
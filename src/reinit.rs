@@ -0,0 +1,83 @@
+//! Running the init sequence again on a configured display
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_hal_0_2::blocking::delay::DelayMs;
+
+use crate::{Command, Ili9342C, Mode, PixelFormat, Result};
+
+/// Mode repeating the memory access control value sent last
+struct CurrentMode {
+    madctl: u8,
+    landscape: bool,
+}
+
+impl Mode for CurrentMode {
+    fn mode(&self) -> u8 {
+        self.madctl
+    }
+
+    fn is_landscape(&self) -> bool {
+        self.landscape
+    }
+}
+
+impl<IFACE, C> Ili9342C<IFACE, C>
+where
+    IFACE: WriteOnlyDataCommand,
+    C: PixelFormat,
+{
+    /// Initialize the panel again after it lost power or was reset, restoring
+    /// the state set through the driver
+    ///
+    /// Runs the init sequence with the settings of the [Builder](crate::Builder)
+    /// and sends the orientation, inversion, idle mode, brightness, CABC,
+    /// tearing effect and scroll settings sent before. The panel is put back
+    /// to sleep or turned off if it was. The memory is lost, redraw the
+    /// content afterwards.
+    pub fn reinit<DELAY: DelayMs<u16>>(&mut self, delay: &mut DELAY) -> Result {
+        let panel = self.state.panel;
+        self.rerun_init(delay)?;
+        if !panel.inverted {
+            self.command(Command::InvertOff, &[])?;
+        }
+        if panel.idle {
+            self.command(Command::IdleModeOn, &[])?;
+        }
+        if let Some(value) = panel.ctrl_display {
+            self.command(Command::WriteCtrlDisplay, &[value])?;
+        }
+        if let Some(value) = panel.brightness {
+            self.command(Command::SetBrightness, &[value])?;
+        }
+        if let Some(value) = panel.cabc {
+            self.command(Command::ContentAdaptiveBrightness, &[value])?;
+        }
+        if let Some(value) = panel.tearing {
+            self.command(Command::TearingEffectOn, &[value])?;
+        }
+        if let Some(args) = panel.scroll_define {
+            self.command(Command::VerticalScrollDefine, &args)?;
+        }
+        if let Some(args) = panel.scroll_start {
+            self.command(Command::VerticalScrollAddr, &args)?;
+        }
+        if !panel.display_on {
+            self.command(Command::DisplayOff, &[])?;
+        }
+        if panel.sleeping {
+            self.command(Command::SleepModeOn, &[])?;
+        }
+        Ok(())
+    }
+
+    /// Run the init sequence with the settings of the builder, keeping the
+    /// orientation
+    pub(crate) fn rerun_init<DELAY: DelayMs<u16>>(&mut self, delay: &mut DELAY) -> Result {
+        let mode = CurrentMode {
+            madctl: self.state.panel.madctl(),
+            landscape: self.state.landscape,
+        };
+        let settings = self.state.init;
+        settings.apply(self, delay, &mode)
+    }
+}
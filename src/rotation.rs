@@ -0,0 +1,99 @@
+//! Rotations of the picture, independent of the memory access control bits
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_graphics_core::{geometry::OriginDimensions, prelude::Size};
+
+use crate::access::{MV, MX, MY};
+use crate::{Ili9342C, Mode, PixelFormat, Result};
+
+/// Clockwise rotation of the picture from the native landscape panel
+///
+/// Unlike [Orientation](crate::Orientation), which names raw memory access
+/// control values, every rotation keeps the picture unmirrored. The driver
+/// adds the [ColorOrder](crate::ColorOrder) and the mirroring of
+/// [Ili9342C::set_mirror] when sending it.
+///
+/// ```ignore
+/// display.set_rotation(Rotation::Deg90)?;
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Rotation {
+    /// Landscape, the native orientation
+    #[default]
+    Deg0,
+    /// Portrait
+    Deg90,
+    /// Landscape, upside down
+    Deg180,
+    /// Portrait, upside down
+    Deg270,
+}
+
+impl Mode for Rotation {
+    fn mode(&self) -> u8 {
+        match self {
+            Self::Deg0 => 0,
+            Self::Deg90 => MV | MX,
+            Self::Deg180 => MY | MX,
+            Self::Deg270 => MV | MY,
+        }
+    }
+
+    fn is_landscape(&self) -> bool {
+        matches!(self, Self::Deg0 | Self::Deg180)
+    }
+}
+
+/// Function called with the new rotation and size when the orientation
+/// changes, see [Ili9342C::set_orientation_hook]
+pub type OrientationHook = fn(Option<Rotation>, Size);
+
+impl<IFACE, C> Ili9342C<IFACE, C>
+where
+    IFACE: WriteOnlyDataCommand,
+    C: PixelFormat,
+{
+    /// Rotate the picture, see [Self::set_orientation]
+    pub fn set_rotation(&mut self, rotation: Rotation) -> Result {
+        self.set_orientation(rotation)
+    }
+
+    /// Current rotation, ignoring [Self::set_mirror]
+    ///
+    /// `None` when the memory access control value mirrors the picture, like
+    /// [Orientation::Portrait](crate::Orientation::Portrait) does.
+    pub fn orientation(&self) -> Option<Rotation> {
+        let bits = (self.state.panel.madctl() ^ self.state.mirror) & (MY | MX | MV);
+        [
+            Rotation::Deg0,
+            Rotation::Deg90,
+            Rotation::Deg180,
+            Rotation::Deg270,
+        ]
+        .into_iter()
+        .find(|rotation| rotation.mode() == bits)
+    }
+
+    /// Call a function when [Self::set_orientation], [Self::set_rotation] or
+    /// [Self::flip_180] change the rotation or size
+    ///
+    /// Lets touch mapping or layout code follow the orientation.
+    pub fn set_orientation_hook(&mut self, hook: Option<OrientationHook>) {
+        self.state.orientation_hook = hook;
+    }
+
+    /// Rotation and size compared by [Self::orientation_changed]
+    pub(crate) fn orientation_state(&self) -> (Option<Rotation>, Size) {
+        (self.orientation(), self.size())
+    }
+
+    /// Call the orientation hook if the rotation or size differ from `before`
+    pub(crate) fn orientation_changed(&self, before: (Option<Rotation>, Size)) {
+        let after = self.orientation_state();
+        if let Some(hook) = self.state.orientation_hook.filter(|_| after != before) {
+            hook(after.0, after.1);
+        }
+    }
+}
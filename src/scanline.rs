@@ -0,0 +1,99 @@
+//! Streaming frames produced row by row
+
+use display_interface::{DisplayError, WriteOnlyDataCommand};
+use embedded_graphics_core::{pixelcolor::Rgb888, primitives::Rectangle};
+
+use crate::{Ili9342C, PixelFormat, Result};
+
+/// Pixels of the line buffer of [Ili9342C::render_scanlines], the longest
+/// row of the panel
+const MAX_LINE: usize = 320;
+
+impl<IFACE, C> Ili9342C<IFACE, C>
+where
+    IFACE: WriteOnlyDataCommand,
+    C: PixelFormat,
+{
+    /// Write rows of raw pixels spanning the whole width, starting at row
+    /// `y0`, as the iterator yields them
+    ///
+    /// The rows are sent in one memory write, without buffering a frame.
+    /// Each row has to hold the width of the display, extra pixels are
+    /// ignored. Rows below the bottom of the display are not consumed.
+    ///
+    /// ```ignore
+    /// display.flush_rows(0, decoder.rows().map(|row| &row[..]))?;
+    /// ```
+    pub fn flush_rows<'a, I>(&mut self, y0: u16, rows: I) -> Result
+    where
+        I: IntoIterator<Item = &'a [C::RawValue]>,
+        C::RawValue: 'a,
+    {
+        if y0 as usize >= self.state.height {
+            return Err(DisplayError::OutOfBoundsError);
+        }
+        let width = self.state.width;
+        let pixels = rows
+            .into_iter()
+            .take(self.state.height - y0 as usize)
+            .flat_map(|row| row.iter().take(width).copied());
+        self.draw_raw_iter(
+            0,
+            y0,
+            width as u16 - 1,
+            self.state.height as u16 - 1,
+            pixels,
+        )
+    }
+
+    /// Render an area row by row into a line buffer, sending each row once
+    /// `render` filled it
+    ///
+    /// `render` is called with the display row and the buffer of the width
+    /// of the area, which keeps the pixels of the previous row. The rows are
+    /// sent in one memory write.
+    ///
+    /// ```ignore
+    /// let area = Rectangle::new(Point::zero(), Size::new(320, 240));
+    /// display.render_scanlines(area, |y, row| {
+    ///     for (x, pixel) in row.iter_mut().enumerate() {
+    ///         *pixel = plasma(x, y).to_raw();
+    ///     }
+    /// })?;
+    /// ```
+    pub fn render_scanlines<F>(&mut self, area: Rectangle, mut render: F) -> Result
+    where
+        F: FnMut(u16, &mut [C::RawValue]),
+    {
+        let Some(bottom_right) = area.bottom_right() else {
+            return Ok(());
+        };
+        let width = area.size.width as usize;
+        if area.top_left.x < 0
+            || area.top_left.y < 0
+            || bottom_right.x as usize >= self.state.width
+            || bottom_right.y as usize >= self.state.height
+            || width > MAX_LINE
+        {
+            return Err(DisplayError::OutOfBoundsError);
+        }
+        let (x0, y0) = (area.top_left.x as u16, area.top_left.y as u16);
+        let (x1, y1) = (bottom_right.x as u16, bottom_right.y as u16);
+        let mut line = [C::from_rgb888(Rgb888::new(0, 0, 0)).to_raw(); MAX_LINE];
+        let line = &mut line[..width];
+        let (mut y, mut x) = (y0, width);
+        let pixels = core::iter::from_fn(|| {
+            if x == width {
+                if y > y1 {
+                    return None;
+                }
+                render(y, line);
+                y += 1;
+                x = 0;
+            }
+            x += 1;
+            Some(line[x - 1])
+        });
+        self.draw_raw_iter(x0, y0, x1, y1, pixels)
+    }
+}
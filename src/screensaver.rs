@@ -0,0 +1,131 @@
+//! Dimming and blanking the display when the user is idle
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_hal_0_2::blocking::delay::DelayMs;
+
+use crate::{Ili9342C, ModeState, PixelFormat, Result};
+
+/// State of a [Screensaver]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ScreensaverState {
+    /// Full brightness
+    Active,
+    /// Dimmed brightness
+    Dimmed,
+    /// Display off and in sleep mode
+    Off,
+}
+
+/// Dims the display and then turns it off after idle timeouts
+///
+/// Call [Screensaver::tick] periodically and [Screensaver::notify_activity]
+/// on user input. Uses the brightness control of the controller, which has
+/// to be enabled with [Ili9342C::set_brightness_control].
+///
+/// ```ignore
+/// let mut saver = Screensaver::new(255, 30, 30_000, 120_000);
+/// loop {
+///     if touched {
+///         saver.notify_activity(&mut display, &mut delay)?;
+///     }
+///     saver.tick(&mut display, 10)?;
+/// }
+/// ```
+pub struct Screensaver {
+    brightness: u8,
+    dim_brightness: u8,
+    dim_after_ms: u32,
+    off_after_ms: u32,
+    idle_ms: u32,
+    state: ScreensaverState,
+}
+
+impl Screensaver {
+    /// Dim to `dim_brightness` after `dim_after_ms` without activity and turn
+    /// the display off after `off_after_ms`
+    pub fn new(brightness: u8, dim_brightness: u8, dim_after_ms: u32, off_after_ms: u32) -> Self {
+        Self {
+            brightness,
+            dim_brightness,
+            dim_after_ms,
+            off_after_ms,
+            idle_ms: 0,
+            state: ScreensaverState::Active,
+        }
+    }
+
+    /// Current state
+    pub fn state(&self) -> ScreensaverState {
+        self.state
+    }
+
+    /// Milliseconds since the last activity
+    pub fn idle_ms(&self) -> u32 {
+        self.idle_ms
+    }
+
+    /// Change the full brightness, applied right away when active
+    pub fn set_brightness<IFACE, C>(
+        &mut self,
+        display: &mut Ili9342C<IFACE, C>,
+        brightness: u8,
+    ) -> Result
+    where
+        IFACE: WriteOnlyDataCommand,
+        C: PixelFormat,
+    {
+        self.brightness = brightness;
+        if self.state == ScreensaverState::Active {
+            display.set_brightness(brightness)?;
+        }
+        Ok(())
+    }
+
+    /// Restart the idle timeouts, restoring full brightness
+    ///
+    /// Waking the display from the off state waits for the sleep out time.
+    pub fn notify_activity<IFACE, C, DELAY>(
+        &mut self,
+        display: &mut Ili9342C<IFACE, C>,
+        delay: &mut DELAY,
+    ) -> Result
+    where
+        IFACE: WriteOnlyDataCommand,
+        C: PixelFormat,
+        DELAY: DelayMs<u16>,
+    {
+        self.idle_ms = 0;
+        match self.state {
+            ScreensaverState::Active => return Ok(()),
+            ScreensaverState::Dimmed => {}
+            ScreensaverState::Off => {
+                display.wake(delay)?;
+                display.display_mode(ModeState::On)?;
+            }
+        }
+        display.set_brightness(self.brightness)?;
+        self.state = ScreensaverState::Active;
+        Ok(())
+    }
+
+    /// Advance the idle time, dimming or turning off the display when a
+    /// timeout is reached
+    pub fn tick<IFACE, C>(&mut self, display: &mut Ili9342C<IFACE, C>, elapsed_ms: u32) -> Result
+    where
+        IFACE: WriteOnlyDataCommand,
+        C: PixelFormat,
+    {
+        self.idle_ms = self.idle_ms.saturating_add(elapsed_ms);
+        if self.state != ScreensaverState::Off && self.idle_ms >= self.off_after_ms {
+            display.set_brightness(0)?;
+            display.display_mode(ModeState::Off)?;
+            display.sleep_mode(ModeState::On)?;
+            self.state = ScreensaverState::Off;
+        } else if self.state == ScreensaverState::Active && self.idle_ms >= self.dim_after_ms {
+            display.set_brightness(self.dim_brightness)?;
+            self.state = ScreensaverState::Dimmed;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,175 @@
+//! Hardware vertical scrolling
+
+use display_interface::{DisplayError, WriteOnlyDataCommand};
+use embedded_hal::digital::InputPin;
+use embedded_hal_0_2::blocking::delay::DelayMs;
+
+use crate::{tearing::poll_vsync, Command, Ili9342C, PixelFormat, Result};
+
+/// Time between the steps of [Ili9342C::animate_scroll], about one frame
+const STEP_MS: u16 = 16;
+
+/// State of the hardware scroll area, created by
+/// [Ili9342C::configure_vertical_scroll]
+///
+/// The scroll area is made of the memory lines between the fixed top and
+/// bottom areas. [Scroller::top_offset] is the memory line shown at the top of
+/// the scroll area, the lines wrap around at the end of the area.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Scroller {
+    top_offset: u16,
+    fixed_top_lines: u16,
+    fixed_bottom_lines: u16,
+    height: u16,
+}
+
+impl Scroller {
+    fn new(fixed_top_lines: u16, fixed_bottom_lines: u16, height: u16) -> Self {
+        Self {
+            top_offset: fixed_top_lines,
+            fixed_top_lines,
+            fixed_bottom_lines,
+            height,
+        }
+    }
+
+    /// Memory line shown at the top of the scroll area
+    pub fn top_offset(&self) -> u16 {
+        self.top_offset
+    }
+
+    /// Number of lines in the fixed top area
+    pub fn fixed_top_lines(&self) -> u16 {
+        self.fixed_top_lines
+    }
+
+    /// Number of lines in the fixed bottom area
+    pub fn fixed_bottom_lines(&self) -> u16 {
+        self.fixed_bottom_lines
+    }
+
+    /// Number of lines in the scroll area
+    pub fn scroll_lines(&self) -> u16 {
+        self.height - self.fixed_top_lines - self.fixed_bottom_lines
+    }
+}
+
+impl<IFACE, C> Ili9342C<IFACE, C>
+where
+    IFACE: WriteOnlyDataCommand,
+    C: PixelFormat,
+{
+    /// Configures the screen for hardware-accelerated vertical scrolling.
+    ///
+    /// The scroll follows the memory lines of the panel, which run vertically
+    /// in the landscape orientations.
+    pub fn configure_vertical_scroll(
+        &mut self,
+        fixed_top_lines: u16,
+        fixed_bottom_lines: u16,
+    ) -> Result<Scroller> {
+        let height = if self.state.landscape {
+            self.state.height
+        } else {
+            self.state.width
+        } as u16;
+        if fixed_top_lines + fixed_bottom_lines >= height {
+            return Err(DisplayError::OutOfBoundsError);
+        }
+        let scroll_lines = height - fixed_top_lines - fixed_bottom_lines;
+
+        self.command(
+            Command::VerticalScrollDefine,
+            &[
+                (fixed_top_lines >> 8) as u8,
+                (fixed_top_lines & 0xff) as u8,
+                (scroll_lines >> 8) as u8,
+                (scroll_lines & 0xff) as u8,
+                (fixed_bottom_lines >> 8) as u8,
+                (fixed_bottom_lines & 0xff) as u8,
+            ],
+        )?;
+
+        Ok(Scroller::new(fixed_top_lines, fixed_bottom_lines, height))
+    }
+
+    /// Scroll the scroll area by `num_lines`, wrapping around at its end
+    pub fn scroll_vertically(&mut self, scroller: &mut Scroller, num_lines: u16) -> Result {
+        let offset = (scroller.top_offset - scroller.fixed_top_lines) as u32 + num_lines as u32;
+        let scroll_lines = scroller.scroll_lines() as u32;
+        self.set_scroll_position(scroller, (offset % scroll_lines) as u16)
+    }
+
+    /// Show line `line` of the scroll area at its top, wrapping around at its
+    /// end
+    ///
+    /// Writes the scroll start address directly, for ring buffers keeping
+    /// track of their own head line.
+    pub fn set_scroll_position(&mut self, scroller: &mut Scroller, line: u16) -> Result {
+        scroller.top_offset = scroller.fixed_top_lines + line % scroller.scroll_lines();
+
+        self.command(
+            Command::VerticalScrollAddr,
+            &[
+                (scroller.top_offset >> 8) as u8,
+                (scroller.top_offset & 0xff) as u8,
+            ],
+        )
+    }
+
+    /// Scroll by `lines` over `duration_ms`, one step about every frame
+    ///
+    /// Negative `lines` scroll backwards. The steps are spread evenly, so the
+    /// content moves smoothly instead of jumping by the whole amount.
+    pub fn animate_scroll<DELAY: DelayMs<u16>>(
+        &mut self,
+        scroller: &mut Scroller,
+        lines: i16,
+        duration_ms: u16,
+        delay: &mut DELAY,
+    ) -> Result {
+        let steps = (duration_ms / STEP_MS).max(1);
+        self.animate_steps(scroller, lines, steps, || {
+            delay.delay_ms(STEP_MS);
+            Ok(())
+        })
+    }
+
+    /// Like [Self::animate_scroll], stepping once per frame right after the
+    /// TE pin signals the vertical blanking
+    ///
+    /// The scroll start then never changes while the panel is refreshed,
+    /// which avoids shearing. The TE output has to be enabled with
+    /// [Self::set_tearing_effect].
+    pub fn animate_scroll_vsync<TE: InputPin>(
+        &mut self,
+        scroller: &mut Scroller,
+        lines: i16,
+        frames: u16,
+        te: &mut TE,
+    ) -> Result {
+        self.animate_steps(scroller, lines, frames.max(1), || poll_vsync(te))
+    }
+
+    /// Scroll by `lines` in `steps`, calling `wait` before each step
+    fn animate_steps<F>(
+        &mut self,
+        scroller: &mut Scroller,
+        lines: i16,
+        steps: u16,
+        mut wait: F,
+    ) -> Result
+    where
+        F: FnMut() -> Result,
+    {
+        let scroll_lines = scroller.scroll_lines() as i32;
+        let start = (scroller.top_offset - scroller.fixed_top_lines) as i32;
+        for step in 1..=steps as i32 {
+            wait()?;
+            let line = start + lines as i32 * step / steps as i32;
+            self.set_scroll_position(scroller, line.rem_euclid(scroll_lines) as u16)?;
+        }
+        Ok(())
+    }
+}
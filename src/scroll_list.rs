@@ -0,0 +1,295 @@
+//! Scrollable list rendered with the hardware scroll
+
+use display_interface::{DisplayError, WriteOnlyDataCommand};
+use embedded_graphics_core::{
+    draw_target::DrawTarget,
+    geometry::{OriginDimensions, Point, Size},
+    primitives::{PointsIter, Rectangle},
+    Pixel,
+};
+
+use crate::{Ili9342C, PixelFormat, Result, Scroller};
+
+/// Vertically scrollable list of equally tall items
+///
+/// The list occupies the hardware scroll area between the fixed top and
+/// bottom lines. Scrolling moves the scroll start address and only redraws
+/// the band of lines that became visible, so long lists scroll smoothly even
+/// over a slow bus.
+///
+/// Items are drawn by a callback receiving the item index and a
+/// [ListItemTarget] in item coordinates. The item area is cleared with the
+/// background color before the callback runs.
+///
+/// The display has to be in a landscape orientation, see
+/// [Ili9342C::configure_vertical_scroll].
+pub struct ScrollList<C> {
+    scroller: Scroller,
+    item_height: u16,
+    item_count: usize,
+    position: u32,
+    background: C,
+}
+
+impl<C: PixelFormat> ScrollList<C> {
+    /// Set up the scroll area and an empty list position
+    ///
+    /// Nothing is drawn until [ScrollList::redraw] is called.
+    pub fn new<IFACE>(
+        display: &mut Ili9342C<IFACE, C>,
+        fixed_top_lines: u16,
+        fixed_bottom_lines: u16,
+        item_height: u16,
+        item_count: usize,
+        background: C,
+    ) -> Result<Self>
+    where
+        IFACE: WriteOnlyDataCommand,
+    {
+        if item_height == 0 {
+            return Err(DisplayError::OutOfBoundsError);
+        }
+        let mut scroller =
+            display.configure_vertical_scroll(fixed_top_lines, fixed_bottom_lines)?;
+        display.scroll_vertically(&mut scroller, 0)?;
+        Ok(Self {
+            scroller,
+            item_height,
+            item_count,
+            position: 0,
+            background,
+        })
+    }
+
+    /// Content line shown at the top of the list
+    pub fn position(&self) -> u32 {
+        self.position
+    }
+
+    /// Largest position, where the last item is at the bottom of the list
+    pub fn max_position(&self) -> u32 {
+        (self.item_count as u32 * self.item_height as u32)
+            .saturating_sub(self.scroller.scroll_lines() as u32)
+    }
+
+    /// Change the number of items, the visible items are not redrawn
+    pub fn set_item_count(&mut self, item_count: usize) {
+        self.item_count = item_count;
+        self.position = self.position.min(self.max_position());
+    }
+
+    /// Index of the first visible item
+    pub fn first_visible_item(&self) -> usize {
+        (self.position / self.item_height as u32) as usize
+    }
+
+    /// Redraw all visible items
+    pub fn redraw<IFACE, F>(&mut self, display: &mut Ili9342C<IFACE, C>, draw_item: F) -> Result
+    where
+        IFACE: WriteOnlyDataCommand,
+        F: FnMut(usize, &mut ListItemTarget<'_, IFACE, C>) -> Result,
+    {
+        let visible = self.scroller.scroll_lines() as u32;
+        self.draw_band(display, self.position, self.position + visible, draw_item)
+    }
+
+    /// Scroll by a number of lines, negative values scroll up
+    pub fn scroll_by<IFACE, F>(
+        &mut self,
+        display: &mut Ili9342C<IFACE, C>,
+        lines: i32,
+        draw_item: F,
+    ) -> Result
+    where
+        IFACE: WriteOnlyDataCommand,
+        F: FnMut(usize, &mut ListItemTarget<'_, IFACE, C>) -> Result,
+    {
+        let position = (self.position as i64 + lines as i64).max(0) as u32;
+        self.scroll_to(display, position, draw_item)
+    }
+
+    /// Scroll to a position, redrawing only the newly visible lines
+    pub fn scroll_to<IFACE, F>(
+        &mut self,
+        display: &mut Ili9342C<IFACE, C>,
+        position: u32,
+        draw_item: F,
+    ) -> Result
+    where
+        IFACE: WriteOnlyDataCommand,
+        F: FnMut(usize, &mut ListItemTarget<'_, IFACE, C>) -> Result,
+    {
+        let position = position.min(self.max_position());
+        let old = self.position;
+        if position == old {
+            return Ok(());
+        }
+        let visible = self.scroller.scroll_lines() as u32;
+        self.position = position;
+
+        let start = self.scroller.fixed_top_lines() as u32 + position % visible;
+        let delta = (start + visible - self.scroller.top_offset() as u32) % visible;
+        display.scroll_vertically(&mut self.scroller, delta as u16)?;
+
+        if position.abs_diff(old) >= visible {
+            self.draw_band(display, position, position + visible, draw_item)
+        } else if position > old {
+            self.draw_band(display, old + visible, position + visible, draw_item)
+        } else {
+            self.draw_band(display, position, old, draw_item)
+        }
+    }
+
+    /// Draw the content lines `start..end`
+    fn draw_band<IFACE, F>(
+        &mut self,
+        display: &mut Ili9342C<IFACE, C>,
+        start: u32,
+        end: u32,
+        mut draw_item: F,
+    ) -> Result
+    where
+        IFACE: WriteOnlyDataCommand,
+        F: FnMut(usize, &mut ListItemTarget<'_, IFACE, C>) -> Result,
+    {
+        let item_height = self.item_height as u32;
+        let width = display.width() as u32;
+        let mut index = start / item_height;
+        while index * item_height < end {
+            let item_top = index * item_height;
+            let mut target = ListItemTarget {
+                display: &mut *display,
+                width,
+                item_top,
+                item_height,
+                clip_start: start.max(item_top),
+                clip_end: end.min(item_top + item_height),
+                ring_top: self.scroller.fixed_top_lines() as u32,
+                ring_lines: self.scroller.scroll_lines() as u32,
+            };
+            target.fill_solid(
+                &Rectangle::new(Point::zero(), Size::new(width, item_height)),
+                self.background,
+            )?;
+            if (index as usize) < self.item_count {
+                draw_item(index as usize, &mut target)?;
+            }
+            index += 1;
+        }
+        Ok(())
+    }
+}
+
+/// [DrawTarget] for one item of a [ScrollList]
+///
+/// Coordinates are relative to the top left corner of the item, drawing is
+/// clipped to the item and to the lines being redrawn.
+pub struct ListItemTarget<'a, IFACE, C> {
+    display: &'a mut Ili9342C<IFACE, C>,
+    width: u32,
+    item_top: u32,
+    item_height: u32,
+    clip_start: u32,
+    clip_end: u32,
+    ring_top: u32,
+    ring_lines: u32,
+}
+
+impl<IFACE, C> ListItemTarget<'_, IFACE, C> {
+    /// Memory line of a content line
+    fn memory_line(&self, line: u32) -> i32 {
+        (self.ring_top + line % self.ring_lines) as i32
+    }
+
+    /// Content lines of an area in item coordinates, clipped to the redrawn
+    /// lines and to the width of the item
+    fn clip(&self, area: &Rectangle) -> Option<(Rectangle, u32, u32)> {
+        let area = area.intersection(&Rectangle::new(
+            Point::zero(),
+            Size::new(self.width, self.item_height),
+        ));
+        let bottom_right = area.bottom_right()?;
+        let start = (self.item_top + area.top_left.y as u32).max(self.clip_start);
+        let end = (self.item_top + bottom_right.y as u32 + 1).min(self.clip_end);
+        (start < end).then_some((area, start, end))
+    }
+}
+
+impl<IFACE, C> OriginDimensions for ListItemTarget<'_, IFACE, C> {
+    fn size(&self) -> Size {
+        Size::new(self.width, self.item_height)
+    }
+}
+
+impl<IFACE, C> DrawTarget for ListItemTarget<'_, IFACE, C>
+where
+    IFACE: WriteOnlyDataCommand,
+    C: PixelFormat,
+{
+    type Color = C;
+
+    type Error = DisplayError;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            if point.x < 0 || point.y < 0 || point.x as u32 >= self.width {
+                continue;
+            }
+            let line = self.item_top + point.y as u32;
+            if point.y as u32 >= self.item_height || line < self.clip_start || line >= self.clip_end
+            {
+                continue;
+            }
+            let point = Point::new(point.x, self.memory_line(line));
+            self.display
+                .draw_iter(core::iter::once(Pixel(point, color)))?;
+        }
+        Ok(())
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        match self.clip(area) {
+            Some((clipped, start, end))
+                if clipped == *area
+                    && self.item_top + area.top_left.y as u32 == start
+                    && self.item_top + clipped.size.height + area.top_left.y as u32 == end
+                    && start / self.ring_lines == (end - 1) / self.ring_lines =>
+            {
+                // Fully drawn and not wrapping around the scroll area
+                let top_left = Point::new(area.top_left.x, self.memory_line(start));
+                self.display
+                    .fill_contiguous(&Rectangle::new(top_left, area.size), colors)
+            }
+            Some(_) => self.draw_iter(
+                area.points()
+                    .zip(colors)
+                    .map(|(point, color)| Pixel(point, color)),
+            ),
+            None => Ok(()),
+        }
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let Some((area, start, end)) = self.clip(area) else {
+            return Ok(());
+        };
+        // Split the lines where they wrap around the scroll area
+        let mut line = start;
+        while line < end {
+            let lines = (end - line).min(self.ring_lines - line % self.ring_lines);
+            let top_left = Point::new(area.top_left.x, self.memory_line(line));
+            self.display.fill_solid(
+                &Rectangle::new(top_left, Size::new(area.size.width, lines)),
+                color,
+            )?;
+            line += lines;
+        }
+        Ok(())
+    }
+}
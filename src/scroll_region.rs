@@ -0,0 +1,141 @@
+//! Draw target following the hardware scroll
+
+use display_interface::{DisplayError, WriteOnlyDataCommand};
+use embedded_graphics_core::{
+    draw_target::DrawTarget,
+    geometry::{OriginDimensions, Point, Size},
+    primitives::{PointsIter, Rectangle},
+    Pixel,
+};
+
+use crate::{Ili9342C, PixelFormat, Scroller};
+
+/// [DrawTarget] for the scroll area of a [Scroller], see
+/// [Ili9342C::scroll_region]
+///
+/// Line 0 is the line currently shown at the top of the scroll area, the
+/// lines are mapped to memory through the scroll offset and wrap around at
+/// the end of the area. Drawing is clipped to the scroll area.
+pub struct ScrollRegion<'a, IFACE, C> {
+    display: &'a mut Ili9342C<IFACE, C>,
+    top: u32,
+    lines: u32,
+    offset: u32,
+}
+
+impl<IFACE, C> Ili9342C<IFACE, C>
+where
+    IFACE: WriteOnlyDataCommand,
+    C: PixelFormat,
+{
+    /// Draw target for the scroll area at its current scroll position
+    ///
+    /// The display has to be in a landscape orientation, see
+    /// [Self::configure_vertical_scroll].
+    ///
+    /// ```ignore
+    /// // Append a line to a log and scroll it into view
+    /// display.scroll_vertically(&mut scroller, 10)?;
+    /// let mut log = display.scroll_region(&scroller);
+    /// let bottom = log.size().height as i32 - 10;
+    /// Text::new(line, Point::new(0, bottom + 8), style).draw(&mut log)?;
+    /// ```
+    pub fn scroll_region(&mut self, scroller: &Scroller) -> ScrollRegion<'_, IFACE, C> {
+        let top = scroller.fixed_top_lines() as u32;
+        ScrollRegion {
+            display: self,
+            top,
+            lines: scroller.scroll_lines() as u32,
+            offset: scroller.top_offset() as u32 - top,
+        }
+    }
+}
+
+impl<IFACE, C> ScrollRegion<'_, IFACE, C> {
+    /// Memory line of a logical line
+    fn memory_line(&self, line: u32) -> i32 {
+        (self.top + (self.offset + line) % self.lines) as i32
+    }
+
+    /// Number of logical lines from `line` until the memory lines wrap
+    fn lines_until_wrap(&self, line: u32) -> u32 {
+        self.lines - (self.offset + line) % self.lines
+    }
+}
+
+impl<IFACE, C> OriginDimensions for ScrollRegion<'_, IFACE, C> {
+    fn size(&self) -> Size {
+        Size::new(self.display.width() as u32, self.lines)
+    }
+}
+
+impl<IFACE, C> DrawTarget for ScrollRegion<'_, IFACE, C>
+where
+    IFACE: WriteOnlyDataCommand,
+    C: PixelFormat,
+{
+    type Color = C;
+
+    type Error = DisplayError;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let size = self.size();
+        for Pixel(point, color) in pixels {
+            if point.x < 0 || point.y < 0 || point.y as u32 >= size.height {
+                continue;
+            }
+            let point = Point::new(point.x, self.memory_line(point.y as u32));
+            self.display
+                .draw_iter(core::iter::once(Pixel(point, color)))?;
+        }
+        Ok(())
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        let bounds = Rectangle::new(Point::zero(), self.size());
+        if area.intersection(&bounds) != *area || area.size.height == 0 {
+            return self.draw_iter(
+                area.points()
+                    .zip(colors)
+                    .map(|(point, color)| Pixel(point, color)),
+            );
+        }
+        // Split the area where it wraps around the scroll area
+        let mut colors = colors.into_iter();
+        let mut line = area.top_left.y as u32;
+        let end = line + area.size.height;
+        while line < end {
+            let lines = (end - line).min(self.lines_until_wrap(line));
+            let top_left = Point::new(area.top_left.x, self.memory_line(line));
+            let count = (area.size.width * lines) as usize;
+            self.display.fill_contiguous(
+                &Rectangle::new(top_left, Size::new(area.size.width, lines)),
+                colors.by_ref().take(count),
+            )?;
+            line += lines;
+        }
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let area = area.intersection(&Rectangle::new(Point::zero(), self.size()));
+        let mut line = area.top_left.y as u32;
+        let end = line + area.size.height;
+        while line < end {
+            let lines = (end - line).min(self.lines_until_wrap(line));
+            let top_left = Point::new(area.top_left.x, self.memory_line(line));
+            self.display.fill_solid(
+                &Rectangle::new(top_left, Size::new(area.size.width, lines)),
+                color,
+            )?;
+            line += lines;
+        }
+        Ok(())
+    }
+}
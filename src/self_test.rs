@@ -0,0 +1,74 @@
+//! Self test for manufacturing and diagnostics
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_graphics_core::pixelcolor::{Rgb888, RgbColor};
+use embedded_hal_0_2::blocking::delay::DelayMs;
+
+use crate::{Ili9342C, PixelFormat, ReadDataCommand, Result};
+
+/// Colors shown by the self test, in order
+const COLORS: [Rgb888; 4] = [Rgb888::RED, Rgb888::GREEN, Rgb888::BLUE, Rgb888::WHITE];
+
+/// Time each color of the self test stays on screen
+const HOLD_MS: u16 = 250;
+
+/// Result of [Ili9342C::self_test]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SelfTestReport {
+    /// ID bytes read back, `None` if the interface can't read
+    pub id: Option<[u8; 3]>,
+    /// Duration of each full screen fill with red, green, blue and white in
+    /// microseconds, `None` without a clock
+    pub fill_us: [Option<u64>; 4],
+}
+
+impl SelfTestReport {
+    /// Whether every fill was timed and took at most `limit_us`
+    pub fn fills_within(&self, limit_us: u64) -> bool {
+        self.fill_us
+            .iter()
+            .all(|fill| fill.is_some_and(|us| us <= limit_us))
+    }
+}
+
+impl<IFACE, C> Ili9342C<IFACE, C>
+where
+    IFACE: WriteOnlyDataCommand,
+    C: PixelFormat,
+{
+    /// Run the init sequence again, then show red, green, blue and white and
+    /// end with a black screen
+    ///
+    /// The fills are timed with the clock set by [Self::set_clock]. Any
+    /// interface error aborts the test. Use [Self::self_test_with_id] to
+    /// also read the ID of the panel.
+    pub fn self_test<DELAY: DelayMs<u16>>(&mut self, delay: &mut DELAY) -> Result<SelfTestReport> {
+        self.rerun_init(delay)?;
+        let mut fill_us = [None; 4];
+        for (color, fill) in COLORS.iter().zip(&mut fill_us) {
+            self.clear_screen(C::from_rgb888(*color).to_raw())?;
+            *fill = self.last_transfer_us();
+            delay.delay_ms(HOLD_MS);
+        }
+        self.clear_screen(C::from_rgb888(Rgb888::BLACK).to_raw())?;
+        Ok(SelfTestReport { id: None, fill_us })
+    }
+}
+
+impl<IFACE, C> Ili9342C<IFACE, C>
+where
+    IFACE: ReadDataCommand,
+    C: PixelFormat,
+{
+    /// Like [Self::self_test], reading the ID with [Self::read_id] after
+    /// the init sequence
+    pub fn self_test_with_id<DELAY: DelayMs<u16>>(
+        &mut self,
+        delay: &mut DELAY,
+    ) -> Result<SelfTestReport> {
+        let mut report = self.self_test(delay)?;
+        report.id = Some(self.read_id()?);
+        Ok(report)
+    }
+}
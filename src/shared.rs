@@ -0,0 +1,110 @@
+//! Display shared between tasks, see the `critical-section` feature
+
+use core::cell::RefCell;
+
+use critical_section::Mutex;
+use display_interface::{DisplayError, WriteOnlyDataCommand};
+use embedded_graphics_core::{
+    draw_target::DrawTarget, pixelcolor::Rgb565, primitives::Rectangle, Drawable,
+};
+
+use crate::{Ili9342C, PixelFormat};
+
+/// Error of an operation on a [SharedIli9342C]
+#[derive(Debug)]
+pub enum SharedError {
+    /// No display was stored with [SharedIli9342C::init]
+    NotInitialized,
+    /// The display failed
+    Display(DisplayError),
+}
+
+impl From<DisplayError> for SharedError {
+    fn from(error: DisplayError) -> Self {
+        Self::Display(error)
+    }
+}
+
+/// Display that can be stored in a static and used from several tasks or
+/// interrupt priorities
+///
+/// Every operation runs in a critical section, so a drawing operation is
+/// never interleaved with one from another task. Keep the operations short,
+/// interrupts are disabled while they run.
+///
+/// ```ignore
+/// static DISPLAY: SharedIli9342C<Iface> = SharedIli9342C::new();
+///
+/// DISPLAY.init(display);
+/// DISPLAY.draw(&status_bar)?;
+/// ```
+pub struct SharedIli9342C<IFACE, C = Rgb565> {
+    display: Mutex<RefCell<Option<Ili9342C<IFACE, C>>>>,
+}
+
+impl<IFACE, C> SharedIli9342C<IFACE, C> {
+    /// Create an empty handle, usable in a static
+    pub const fn new() -> Self {
+        Self {
+            display: Mutex::new(RefCell::new(None)),
+        }
+    }
+
+    /// Store the display, returning the previous one
+    pub fn init(&self, display: Ili9342C<IFACE, C>) -> Option<Ili9342C<IFACE, C>> {
+        critical_section::with(|cs| self.display.borrow_ref_mut(cs).replace(display))
+    }
+
+    /// Take the display out of the handle
+    pub fn take(&self) -> Option<Ili9342C<IFACE, C>> {
+        critical_section::with(|cs| self.display.borrow_ref_mut(cs).take())
+    }
+
+    /// Run a closure with exclusive access to the display
+    ///
+    /// Returns `None` if no display was stored.
+    pub fn lock<R>(&self, f: impl FnOnce(&mut Ili9342C<IFACE, C>) -> R) -> Option<R> {
+        critical_section::with(|cs| self.display.borrow_ref_mut(cs).as_mut().map(f))
+    }
+}
+
+impl<IFACE, C> Default for SharedIli9342C<IFACE, C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<IFACE, C> SharedIli9342C<IFACE, C>
+where
+    IFACE: WriteOnlyDataCommand,
+    C: PixelFormat,
+{
+    /// Run a closure returning a result, failing with
+    /// [SharedError::NotInitialized] if no display was stored
+    fn try_lock<R>(
+        &self,
+        f: impl FnOnce(&mut Ili9342C<IFACE, C>) -> Result<R, DisplayError>,
+    ) -> Result<R, SharedError> {
+        self.lock(f)
+            .ok_or(SharedError::NotInitialized)?
+            .map_err(SharedError::Display)
+    }
+
+    /// Draw a drawable
+    pub fn draw<D>(&self, drawable: &D) -> Result<D::Output, SharedError>
+    where
+        D: Drawable<Color = C>,
+    {
+        self.try_lock(|display| drawable.draw(display))
+    }
+
+    /// Fill a rectangle with a color
+    pub fn fill_solid(&self, area: &Rectangle, color: C) -> Result<(), SharedError> {
+        self.try_lock(|display| display.fill_solid(area, color))
+    }
+
+    /// Fill the screen with a color
+    pub fn clear(&self, color: C) -> Result<(), SharedError> {
+        self.try_lock(|display| DrawTarget::clear(display, color))
+    }
+}
@@ -0,0 +1,284 @@
+//! In-memory panel for developing without hardware, see the `simulator`
+//! feature
+
+use alloc::{vec, vec::Vec};
+
+use display_interface::{DataFormat, DisplayError, WriteOnlyDataCommand};
+use embedded_graphics_core::{
+    geometry::{OriginDimensions, Point, Size},
+    pixelcolor::{raw::RawU16, Rgb565, Rgb888, RgbColor},
+    prelude::RawData,
+    Pixel,
+};
+
+use crate::{Command, Result};
+
+const WIDTH: usize = 320;
+const HEIGHT: usize = 240;
+
+/// MemoryAccessControl bits
+const MY: u8 = 0x80;
+const MX: u8 = 0x40;
+const MV: u8 = 0x20;
+
+/// Interface decoding the command stream into a framebuffer
+///
+/// Window, memory write, memory access control, pixel format and display
+/// on/off commands are decoded, so the framebuffer shows what the panel
+/// would. Use [Simulator::pixels] to draw it to an
+/// `embedded-graphics-simulator` display or [Simulator::to_argb] for a
+/// `minifb` window.
+///
+/// The color order and inversion set during init are taken as the settings
+/// giving correct colors on the panel.
+pub struct Simulator {
+    memory: Vec<Rgb888>,
+    command: Option<u8>,
+    args: Vec<u8>,
+    pixel: Vec<u8>,
+    madctl: u8,
+    bytes_per_pixel: usize,
+    /// 12 bit format, two pixels in 3 bytes
+    packed: bool,
+    columns: (u16, u16),
+    pages: (u16, u16),
+    cursor: (u16, u16),
+    display_on: bool,
+    sleeping: bool,
+}
+
+impl Default for Simulator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Simulator {
+    /// Create a panel in its reset state, with black memory
+    pub fn new() -> Self {
+        Self {
+            memory: vec![Rgb888::BLACK; WIDTH * HEIGHT],
+            command: None,
+            args: Vec::new(),
+            pixel: Vec::new(),
+            madctl: 0,
+            bytes_per_pixel: 3,
+            packed: false,
+            columns: (0, WIDTH as u16 - 1),
+            pages: (0, HEIGHT as u16 - 1),
+            cursor: (0, 0),
+            display_on: false,
+            sleeping: true,
+        }
+    }
+
+    /// Size of the screen in the current orientation
+    fn logical_size(&self) -> (usize, usize) {
+        if self.madctl & MV != 0 {
+            (HEIGHT, WIDTH)
+        } else {
+            (WIDTH, HEIGHT)
+        }
+    }
+
+    /// Memory index of a pixel in the current orientation
+    fn index(&self, x: usize, y: usize) -> Option<usize> {
+        let (width, height) = self.logical_size();
+        if x >= width || y >= height {
+            return None;
+        }
+        let x = if self.madctl & MX != 0 {
+            width - 1 - x
+        } else {
+            x
+        };
+        let y = if self.madctl & MY != 0 {
+            height - 1 - y
+        } else {
+            y
+        };
+        let (x, y) = if self.madctl & MV != 0 {
+            (y, x)
+        } else {
+            (x, y)
+        };
+        Some(y * WIDTH + x)
+    }
+
+    /// Color shown at a pixel in the current orientation
+    pub fn pixel(&self, x: usize, y: usize) -> Option<Rgb888> {
+        let index = self.index(x, y)?;
+        if self.display_on && !self.sleeping {
+            Some(self.memory[index])
+        } else {
+            Some(Rgb888::BLACK)
+        }
+    }
+
+    /// All pixels shown, in the current orientation
+    pub fn pixels(&self) -> impl Iterator<Item = Pixel<Rgb888>> + '_ {
+        let (width, height) = self.logical_size();
+        (0..height).flat_map(move |y| {
+            (0..width).map(move |x| {
+                let color = self.pixel(x, y).unwrap_or(Rgb888::BLACK);
+                Pixel(Point::new(x as i32, y as i32), color)
+            })
+        })
+    }
+
+    /// CRC32 of the pixels shown, converted to RGB565, see
+    /// [Crc32](crate::Crc32)
+    pub fn frame_crc(&self) -> u32 {
+        let mut crc = crate::Crc32::new();
+        for Pixel(_, color) in self.pixels() {
+            crc.update(&RawU16::from(Rgb565::from(color)).into_inner().to_be_bytes());
+        }
+        crc.finish()
+    }
+
+    /// Write the pixels shown as a BMP image in chunks, see
+    /// [encode_bmp](crate::encode_bmp)
+    pub fn screenshot_bmp<F: FnMut(&[u8])>(&self, write: &mut F) {
+        let (width, height) = self.logical_size();
+        let pixels = self.pixels().map(|Pixel(_, color)| Rgb565::from(color));
+        crate::encode_bmp(width, height, pixels, write)
+    }
+
+    /// Pixels shown as `0RGB` words in rows, the buffer format of `minifb`
+    pub fn to_argb(&self) -> Vec<u32> {
+        self.pixels()
+            .map(|Pixel(_, c)| (c.r() as u32) << 16 | (c.g() as u32) << 8 | c.b() as u32)
+            .collect()
+    }
+
+    fn start_command(&mut self, command: u8) {
+        self.command = Some(command);
+        self.args.clear();
+        self.pixel.clear();
+        match command {
+            c if c == Command::MemoryWrite as u8 => {
+                self.cursor = (self.columns.0, self.pages.0);
+            }
+            c if c == Command::DisplayOn as u8 => self.display_on = true,
+            c if c == Command::DisplayOff as u8 => self.display_on = false,
+            c if c == Command::SleepModeOn as u8 => self.sleeping = true,
+            c if c == Command::SleepModeOff as u8 => self.sleeping = false,
+            c if c == Command::SoftwareReset as u8 => *self = Self::new(),
+            _ => {}
+        }
+    }
+
+    fn data(&mut self, byte: u8) {
+        let Some(command) = self.command else {
+            return;
+        };
+        if command == Command::MemoryWrite as u8 {
+            self.pixel.push(byte);
+            if self.packed {
+                // Assumes the linear Color Set table written during init
+                let expand = |raw: u16| {
+                    let channel = |shift: u16| ((raw >> shift) & 0xf) as u8 * 0x11;
+                    Rgb888::new(channel(8), channel(4), channel(0))
+                };
+                match self.pixel[..] {
+                    [high, low] => self.put(expand(u16::from_be_bytes([high, low]) >> 4)),
+                    [_, middle, low] => {
+                        self.put(expand(u16::from_be_bytes([middle & 0x0f, low])));
+                        self.pixel.clear();
+                    }
+                    _ => {}
+                }
+            } else if self.pixel.len() == self.bytes_per_pixel {
+                self.write_pixel();
+            }
+            return;
+        }
+        self.args.push(byte);
+        let args = &self.args[..];
+        match (command, args) {
+            (c, &[a, b, c2, d]) if c == Command::ColumnAddressSet as u8 => {
+                self.columns = (u16::from_be_bytes([a, b]), u16::from_be_bytes([c2, d]));
+            }
+            (c, &[a, b, c2, d]) if c == Command::PageAddressSet as u8 => {
+                self.pages = (u16::from_be_bytes([a, b]), u16::from_be_bytes([c2, d]));
+            }
+            (c, &[value]) if c == Command::MemoryAccessControl as u8 => self.madctl = value,
+            (c, &[value]) if c == Command::PixelFormatSet as u8 => {
+                self.bytes_per_pixel = if value & 0x0f == 0x05 { 2 } else { 3 };
+                self.packed = value & 0x0f == 0x03;
+            }
+            _ => {}
+        }
+    }
+
+    fn write_pixel(&mut self) {
+        let color = match self.pixel[..] {
+            [high, low] => {
+                let raw = u16::from_be_bytes([high, low]);
+                let expand =
+                    |value: u16, bits: u32| ((value as u32 * 255) / ((1 << bits) - 1)) as u8;
+                Rgb888::new(
+                    expand(raw >> 11, 5),
+                    expand((raw >> 5) & 0x3f, 6),
+                    expand(raw & 0x1f, 5),
+                )
+            }
+            [r, g, b] => {
+                let expand = |value: u8| ((value >> 2) as u32 * 255 / 63) as u8;
+                Rgb888::new(expand(r), expand(g), expand(b))
+            }
+            _ => Rgb888::BLACK,
+        };
+        self.pixel.clear();
+        self.put(color);
+    }
+
+    /// Store a pixel at the cursor and advance it
+    fn put(&mut self, color: Rgb888) {
+        let (x, y) = self.cursor;
+        if let Some(index) = self.index(x as usize, y as usize) {
+            self.memory[index] = color;
+        }
+        self.cursor = if x >= self.columns.1 {
+            let y = if y >= self.pages.1 {
+                self.pages.0
+            } else {
+                y + 1
+            };
+            (self.columns.0, y)
+        } else {
+            (x + 1, y)
+        };
+    }
+}
+
+impl OriginDimensions for Simulator {
+    fn size(&self) -> Size {
+        let (width, height) = self.logical_size();
+        Size::new(width as u32, height as u32)
+    }
+}
+
+fn for_each_byte(data: DataFormat<'_>, mut f: impl FnMut(u8)) -> Result {
+    match data {
+        DataFormat::U8(slice) => slice.iter().copied().for_each(f),
+        DataFormat::U16(slice) => slice.iter().flat_map(|v| v.to_ne_bytes()).for_each(f),
+        DataFormat::U16BE(slice) => slice.iter().flat_map(|v| v.to_be_bytes()).for_each(f),
+        DataFormat::U16LE(slice) => slice.iter().flat_map(|v| v.to_le_bytes()).for_each(f),
+        DataFormat::U8Iter(iter) => iter.for_each(f),
+        DataFormat::U16BEIter(iter) => iter.flat_map(u16::to_be_bytes).for_each(&mut f),
+        DataFormat::U16LEIter(iter) => iter.flat_map(u16::to_le_bytes).for_each(&mut f),
+        _ => return Err(DisplayError::DataFormatNotImplemented),
+    }
+    Ok(())
+}
+
+impl WriteOnlyDataCommand for Simulator {
+    fn send_commands(&mut self, cmd: DataFormat<'_>) -> Result {
+        for_each_byte(cmd, |command| self.start_command(command))
+    }
+
+    fn send_data(&mut self, buf: DataFormat<'_>) -> Result {
+        for_each_byte(buf, |byte| self.data(byte))
+    }
+}
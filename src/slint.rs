@@ -0,0 +1,79 @@
+//! Line buffer for the Slint software renderer, see the `slint` feature
+
+use display_interface::{DisplayError, WriteOnlyDataCommand};
+use embedded_graphics_core::pixelcolor::Rgb565;
+use slint::platform::software_renderer::{LineBufferProvider, Rgb565Pixel};
+
+use crate::{Ili9342C, Result};
+
+/// [LineBufferProvider] rendering Slint lines into a buffer and streaming
+/// each line to its window on the display
+///
+/// `N` is the length of the line buffer and has to be at least the width of
+/// the display, longer lines are skipped with
+/// [DisplayError::OutOfBoundsError]. Errors can't be returned from the
+/// renderer, the first one is kept for [SlintLineBuffer::finish].
+///
+/// ```ignore
+/// window.draw_if_needed(|renderer| {
+///     let mut buffer = SlintLineBuffer::<_, 320>::new(&mut display);
+///     renderer.render_by_line(&mut buffer);
+///     buffer.finish().unwrap();
+/// });
+/// ```
+pub struct SlintLineBuffer<'a, IFACE, const N: usize = 320> {
+    display: &'a mut Ili9342C<IFACE, Rgb565>,
+    line: [Rgb565Pixel; N],
+    result: Result,
+}
+
+impl<'a, IFACE, const N: usize> SlintLineBuffer<'a, IFACE, N>
+where
+    IFACE: WriteOnlyDataCommand,
+{
+    /// Render to a display
+    pub fn new(display: &'a mut Ili9342C<IFACE, Rgb565>) -> Self {
+        Self {
+            display,
+            line: [Rgb565Pixel(0); N],
+            result: Ok(()),
+        }
+    }
+
+    /// Result of the lines written so far
+    pub fn finish(self) -> Result {
+        self.result
+    }
+}
+
+impl<IFACE, const N: usize> LineBufferProvider for &mut SlintLineBuffer<'_, IFACE, N>
+where
+    IFACE: WriteOnlyDataCommand,
+{
+    type TargetPixel = Rgb565Pixel;
+
+    fn process_line(
+        &mut self,
+        line: usize,
+        range: core::ops::Range<usize>,
+        render_fn: impl FnOnce(&mut [Rgb565Pixel]),
+    ) {
+        let Some(pixels) = self.line.get_mut(range.clone()) else {
+            if self.result.is_ok() {
+                self.result = Err(DisplayError::OutOfBoundsError);
+            }
+            return;
+        };
+        render_fn(pixels);
+        if self.result.is_err() || range.is_empty() {
+            return;
+        }
+        self.result = self.display.draw_raw_iter(
+            range.start as u16,
+            line as u16,
+            range.end as u16 - 1,
+            line as u16,
+            pixels.iter().map(|pixel| pixel.0),
+        );
+    }
+}
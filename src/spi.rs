@@ -0,0 +1,245 @@
+//! SPI interface built on [embedded_hal::spi::SpiDevice]
+
+use display_interface::{DataFormat, DisplayError, WriteOnlyDataCommand};
+use embedded_hal::{
+    digital::OutputPin,
+    spi::{SpiBus, SpiDevice},
+};
+
+use crate::Result;
+
+/// SPI interface with a data/command pin
+///
+/// Data is written in [SpiDevice] transactions, so chip select is handled
+/// by the device and other devices on the same bus can be used in between.
+/// Data is staged in a buffer of `BUF` bytes and a transaction never writes
+/// more than `BUF` bytes, so long transfers don't block the bus for other
+/// devices.
+///
+/// An [SpiDevice] transaction can't switch the data/command pin, so the
+/// command byte and its parameters are separate transactions. Use
+/// [SpiBusInterface] to keep chip select asserted for a whole command.
+///
+/// ```ignore
+/// let spi = ExclusiveDevice::new(spi_bus, cs, delay)?;
+/// let iface = SpiInterface::<_, _>::new(spi, dc);
+/// ```
+pub struct SpiInterface<SPI, DC, const BUF: usize = 512> {
+    spi: SPI,
+    dc: DC,
+    buffer: [u8; BUF],
+}
+
+impl<SPI, DC, const BUF: usize> SpiInterface<SPI, DC, BUF>
+where
+    SPI: SpiDevice,
+    DC: OutputPin,
+{
+    /// Create an interface from a SPI device and the data/command pin
+    pub fn new(spi: SPI, dc: DC) -> Self {
+        const { assert!(BUF >= 2, "the buffer must hold at least 2 bytes") };
+
+        Self {
+            spi,
+            dc,
+            buffer: [0; BUF],
+        }
+    }
+
+    /// Release the SPI device and the data/command pin
+    pub fn release(self) -> (SPI, DC) {
+        (self.spi, self.dc)
+    }
+
+    pub(crate) fn spi_mut(&mut self) -> &mut SPI {
+        &mut self.spi
+    }
+
+    pub(crate) fn dc_mut(&mut self) -> &mut DC {
+        &mut self.dc
+    }
+
+    /// Write a slice in transactions of at most `BUF` bytes
+    fn write_slice(&mut self, data: &[u8]) -> Result {
+        for chunk in data.chunks(BUF) {
+            self.spi
+                .write(chunk)
+                .map_err(|_| DisplayError::BusWriteError)?;
+        }
+        Ok(())
+    }
+
+    /// Write bytes through the buffer in transactions of at most `BUF` bytes
+    fn write_iter(&mut self, data: &mut dyn Iterator<Item = u8>) -> Result {
+        let mut len = 0;
+        for byte in data {
+            self.buffer[len] = byte;
+            len += 1;
+            if len == BUF {
+                self.flush_buffer(len)?;
+                len = 0;
+            }
+        }
+        self.flush_buffer(len)
+    }
+
+    fn flush_buffer(&mut self, len: usize) -> Result {
+        if len == 0 {
+            return Ok(());
+        }
+        self.spi
+            .write(&self.buffer[..len])
+            .map_err(|_| DisplayError::BusWriteError)
+    }
+
+    fn write(&mut self, data: DataFormat<'_>) -> Result {
+        match data {
+            DataFormat::U8(slice) => self.write_slice(slice),
+            data => for_each_byte(data, |bytes| self.write_iter(bytes)),
+        }
+    }
+}
+
+/// Pass the bytes of a [DataFormat] to `write`, in bus order
+fn for_each_byte(
+    data: DataFormat<'_>,
+    mut write: impl FnMut(&mut dyn Iterator<Item = u8>) -> Result,
+) -> Result {
+    match data {
+        DataFormat::U8(slice) => write(&mut slice.iter().copied()),
+        DataFormat::U16(slice) => write(&mut slice.iter().flat_map(|value| value.to_ne_bytes())),
+        DataFormat::U16BE(slice) => write(&mut slice.iter().flat_map(|value| value.to_be_bytes())),
+        DataFormat::U16LE(slice) => write(&mut slice.iter().flat_map(|value| value.to_le_bytes())),
+        DataFormat::U8Iter(iter) => write(iter),
+        DataFormat::U16BEIter(iter) => write(&mut iter.flat_map(u16::to_be_bytes)),
+        DataFormat::U16LEIter(iter) => write(&mut iter.flat_map(u16::to_le_bytes)),
+        _ => Err(DisplayError::DataFormatNotImplemented),
+    }
+}
+
+impl<SPI, DC, const BUF: usize> WriteOnlyDataCommand for SpiInterface<SPI, DC, BUF>
+where
+    SPI: SpiDevice,
+    DC: OutputPin,
+{
+    fn send_commands(&mut self, cmd: DataFormat<'_>) -> Result {
+        self.dc.set_low().map_err(|_| DisplayError::DCError)?;
+        self.write(cmd)
+    }
+
+    fn send_data(&mut self, buf: DataFormat<'_>) -> Result {
+        self.dc.set_high().map_err(|_| DisplayError::DCError)?;
+        self.write(buf)
+    }
+}
+
+/// SPI interface on an [SpiBus] with a chip select and a data/command pin
+///
+/// Chip select is asserted once per command, across the command byte and its
+/// parameters, and released when the parameters are sent. Pixel data is
+/// written in transfers of at most `BUF` bytes, each with chip select
+/// asserted again.
+///
+/// ```ignore
+/// let iface = SpiBusInterface::<_, _, _>::new(spi_bus, cs, dc);
+/// ```
+pub struct SpiBusInterface<SPI, CS, DC, const BUF: usize = 512> {
+    spi: SPI,
+    cs: CS,
+    dc: DC,
+    selected: bool,
+    buffer: [u8; BUF],
+}
+
+impl<SPI, CS, DC, const BUF: usize> SpiBusInterface<SPI, CS, DC, BUF>
+where
+    SPI: SpiBus,
+    CS: OutputPin,
+    DC: OutputPin,
+{
+    /// Create an interface from a SPI bus, the chip select and the
+    /// data/command pin
+    pub fn new(spi: SPI, cs: CS, dc: DC) -> Self {
+        const { assert!(BUF >= 2, "the buffer must hold at least 2 bytes") };
+
+        Self {
+            spi,
+            cs,
+            dc,
+            selected: false,
+            buffer: [0; BUF],
+        }
+    }
+
+    /// Release the SPI bus, the chip select and the data/command pin
+    pub fn release(mut self) -> Result<(SPI, CS, DC)> {
+        self.deselect()?;
+        Ok((self.spi, self.cs, self.dc))
+    }
+
+    fn select(&mut self) -> Result {
+        if !self.selected {
+            self.cs.set_low().map_err(|_| DisplayError::CSError)?;
+            self.selected = true;
+        }
+        Ok(())
+    }
+
+    /// Wait for the bus and release chip select
+    fn deselect(&mut self) -> Result {
+        if self.selected {
+            self.spi.flush().map_err(|_| DisplayError::BusWriteError)?;
+            self.cs.set_high().map_err(|_| DisplayError::CSError)?;
+            self.selected = false;
+        }
+        Ok(())
+    }
+
+    /// Write bytes through the buffer, releasing chip select after every
+    /// `BUF` bytes
+    fn write_iter(&mut self, data: &mut dyn Iterator<Item = u8>) -> Result {
+        let mut len = 0;
+        for byte in data {
+            self.buffer[len] = byte;
+            len += 1;
+            if len == BUF {
+                self.flush_buffer(len)?;
+                self.deselect()?;
+                len = 0;
+            }
+        }
+        self.flush_buffer(len)
+    }
+
+    fn flush_buffer(&mut self, len: usize) -> Result {
+        if len == 0 {
+            return Ok(());
+        }
+        self.select()?;
+        self.spi
+            .write(&self.buffer[..len])
+            .map_err(|_| DisplayError::BusWriteError)
+    }
+}
+
+impl<SPI, CS, DC, const BUF: usize> WriteOnlyDataCommand for SpiBusInterface<SPI, CS, DC, BUF>
+where
+    SPI: SpiBus,
+    CS: OutputPin,
+    DC: OutputPin,
+{
+    fn send_commands(&mut self, cmd: DataFormat<'_>) -> Result {
+        self.deselect()?;
+        self.select()?;
+        self.dc.set_low().map_err(|_| DisplayError::DCError)?;
+        for_each_byte(cmd, |bytes| self.write_iter(bytes))?;
+        // The pin may only change once the command byte is out
+        self.spi.flush().map_err(|_| DisplayError::BusWriteError)
+    }
+
+    fn send_data(&mut self, buf: DataFormat<'_>) -> Result {
+        self.dc.set_high().map_err(|_| DisplayError::DCError)?;
+        for_each_byte(buf, |bytes| self.write_iter(bytes))?;
+        self.deselect()
+    }
+}
@@ -0,0 +1,65 @@
+//! Full screen images stored in flash
+
+use display_interface::{DisplayError, WriteOnlyDataCommand};
+use embedded_graphics_core::pixelcolor::{raw::RawU16, Rgb565};
+
+use crate::{Ili9342C, PixelFormat, Result};
+
+/// Encoding of a splash screen image, see [Ili9342C::show_splash]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ImageFormat {
+    /// Big endian RGB565 pixels, row by row
+    Rgb565,
+    /// Runs of big endian RGB565 pixels, each encoded as a count byte of
+    /// 1 to 255 followed by the pixel
+    Rgb565Rle,
+}
+
+/// Decode run length encoded pixels, stopping at a truncated run
+pub(crate) fn decode_rle(image: &[u8]) -> impl Iterator<Item = u16> + '_ {
+    image
+        .chunks_exact(3)
+        .flat_map(|run| core::iter::repeat_n(u16::from_be_bytes([run[1], run[2]]), run[0] as usize))
+}
+
+impl<IFACE, C> Ili9342C<IFACE, C>
+where
+    IFACE: WriteOnlyDataCommand,
+    C: PixelFormat,
+{
+    /// Stream an image covering the whole screen, without a framebuffer or
+    /// the embedded-graphics image types
+    ///
+    /// Meant to show a logo right after init. Pixels are converted to the
+    /// pixel format of the display on the fly. Fails with
+    /// [DisplayError::OutOfBoundsError] if the image does not have exactly
+    /// one pixel per screen pixel.
+    ///
+    /// ```ignore
+    /// static LOGO: &[u8] = include_bytes!("logo.rle");
+    /// display.show_splash(LOGO, ImageFormat::Rgb565Rle)?;
+    /// ```
+    pub fn show_splash(&mut self, image: &'static [u8], format: ImageFormat) -> Result {
+        let pixels = self.state.width * self.state.height;
+        let convert = |raw: u16| C::from_rgb888(Rgb565::from(RawU16::new(raw)).into()).to_raw();
+        let (x1, y1) = (self.state.width as u16 - 1, self.state.height as u16 - 1);
+        match format {
+            ImageFormat::Rgb565 => {
+                if image.len() != pixels * 2 {
+                    return Err(DisplayError::OutOfBoundsError);
+                }
+                let data = image
+                    .chunks_exact(2)
+                    .map(|pixel| convert(u16::from_be_bytes([pixel[0], pixel[1]])));
+                self.draw_raw_iter(0, 0, x1, y1, data)
+            }
+            ImageFormat::Rgb565Rle => {
+                if decode_rle(image).count() != pixels {
+                    return Err(DisplayError::OutOfBoundsError);
+                }
+                self.draw_raw_iter(0, 0, x1, y1, decode_rle(image).map(convert))
+            }
+        }
+    }
+}
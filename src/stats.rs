@@ -0,0 +1,77 @@
+//! Transfer statistics, see the `stats` feature
+
+#[cfg(feature = "stats")]
+use display_interface::WriteOnlyDataCommand;
+
+#[cfg(feature = "stats")]
+use crate::{Command, Ili9342C, PixelFormat};
+
+/// Counters of the traffic sent to the display
+#[cfg(feature = "stats")]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Stats {
+    /// Command, parameter and pixel bytes sent
+    pub bytes_written: u64,
+    /// Commands sent
+    pub commands: u32,
+    /// Drawing windows set
+    pub windows: u32,
+    /// Memory writes to a window covering the whole screen
+    pub frames: u32,
+}
+
+/// Counters kept by the driver, empty without the `stats` feature
+#[derive(Clone, Copy, Default)]
+pub(crate) struct StatsState {
+    #[cfg(feature = "stats")]
+    stats: Stats,
+    #[cfg(feature = "stats")]
+    full_window: bool,
+}
+
+#[cfg_attr(not(feature = "stats"), allow(unused_variables))]
+impl StatsState {
+    pub(crate) fn command(&mut self, cmd: u8, args: usize) {
+        #[cfg(feature = "stats")]
+        {
+            self.stats.commands += 1;
+            self.stats.bytes_written += 1 + args as u64;
+            if cmd == Command::MemoryWrite as u8 && self.full_window {
+                self.stats.frames += 1;
+            }
+        }
+    }
+
+    pub(crate) fn window(&mut self, full_screen: bool) {
+        #[cfg(feature = "stats")]
+        {
+            self.stats.windows += 1;
+            self.full_window = full_screen;
+        }
+    }
+
+    pub(crate) fn pixel_bytes(&mut self, bytes: usize) {
+        #[cfg(feature = "stats")]
+        {
+            self.stats.bytes_written += bytes as u64;
+        }
+    }
+}
+
+#[cfg(feature = "stats")]
+impl<IFACE, C> Ili9342C<IFACE, C>
+where
+    IFACE: WriteOnlyDataCommand,
+    C: PixelFormat,
+{
+    /// Counters of the traffic sent since init or [Self::reset_stats]
+    pub fn stats(&self) -> Stats {
+        self.state.stats.stats
+    }
+
+    /// Reset the traffic counters
+    pub fn reset_stats(&mut self) {
+        self.state.stats.stats = Stats::default();
+    }
+}
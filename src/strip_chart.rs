@@ -0,0 +1,140 @@
+//! Real-time plot adding one column per sample
+
+use display_interface::{DisplayError, WriteOnlyDataCommand};
+use embedded_graphics_core::{
+    draw_target::DrawTarget,
+    geometry::{Point, Size},
+    primitives::Rectangle,
+};
+
+use crate::{Ili9342C, PixelFormat, Result, Scroller};
+
+/// Plot of a signal, drawing one column per sample
+///
+/// Every [StripChart::push] writes a single column, connecting the sample to
+/// the previous one. A chart created with [StripChart::new] sweeps across its
+/// area and wraps around like an oscilloscope.
+/// [StripChart::with_hardware_scroll] scrolls the whole chart instead, with
+/// the newest sample always at the right edge.
+pub struct StripChart<C> {
+    area: Rectangle,
+    min: i32,
+    max: i32,
+    color: C,
+    background: C,
+    cursor: u32,
+    last: Option<u32>,
+    scroller: Option<Scroller>,
+}
+
+impl<C: PixelFormat> StripChart<C> {
+    /// Chart sweeping across `area`, plotting values from `min` to `max`
+    pub fn new(area: Rectangle, min: i32, max: i32, color: C, background: C) -> Self {
+        Self {
+            area,
+            min,
+            max,
+            color,
+            background,
+            cursor: 0,
+            last: None,
+            scroller: None,
+        }
+    }
+
+    /// Chart scrolled by the hardware, between the fixed columns at the left
+    /// and right edge of the screen
+    ///
+    /// The display has to be in the [Portrait](crate::Orientation::Portrait)
+    /// orientation, where the memory lines of the hardware scroll are the
+    /// columns of the screen.
+    pub fn with_hardware_scroll<IFACE>(
+        display: &mut Ili9342C<IFACE, C>,
+        fixed_left: u16,
+        fixed_right: u16,
+        min: i32,
+        max: i32,
+        color: C,
+        background: C,
+    ) -> Result<Self>
+    where
+        IFACE: WriteOnlyDataCommand,
+    {
+        let mut scroller = display.configure_vertical_scroll(fixed_left, fixed_right)?;
+        display.scroll_vertically(&mut scroller, 0)?;
+        let area = Rectangle::new(
+            Point::new(fixed_left as i32, 0),
+            Size::new(scroller.scroll_lines() as u32, display.height() as u32),
+        );
+        Ok(Self {
+            scroller: Some(scroller),
+            ..Self::new(area, min, max, color, background)
+        })
+    }
+
+    /// Clear the chart area
+    pub fn clear<IFACE>(&mut self, display: &mut Ili9342C<IFACE, C>) -> Result
+    where
+        IFACE: WriteOnlyDataCommand,
+    {
+        self.cursor = 0;
+        self.last = None;
+        display.fill_solid(&self.area, self.background)
+    }
+
+    /// Row of the chart showing a value
+    fn row(&self, value: i32) -> u32 {
+        let rows = self.area.size.height.saturating_sub(1) as i64;
+        let range = (self.max as i64 - self.min as i64).max(1);
+        let value = (value as i64).clamp(self.min as i64, self.max as i64);
+        ((self.max as i64 - value) * rows / range) as u32
+    }
+
+    /// Plot the next sample
+    pub fn push<IFACE>(&mut self, display: &mut Ili9342C<IFACE, C>, value: i32) -> Result
+    where
+        IFACE: WriteOnlyDataCommand,
+    {
+        if self.area.size.width == 0 {
+            return Err(DisplayError::OutOfBoundsError);
+        }
+        let x = match &mut self.scroller {
+            Some(scroller) => {
+                // The column leaving at the left edge comes back at the right
+                let column = scroller.top_offset();
+                display.scroll_vertically(scroller, 1)?;
+                column as i32
+            }
+            None => {
+                let column = self.area.top_left.x + self.cursor as i32;
+                self.cursor += 1;
+                if self.cursor == self.area.size.width {
+                    self.cursor = 0;
+                }
+                if self.cursor == 1 {
+                    // Don't connect across the wrap
+                    self.last = None;
+                }
+                column
+            }
+        };
+        let row = self.row(value);
+        let (top, bottom) = match self.last {
+            Some(last) => (last.min(row), last.max(row)),
+            None => (row, row),
+        };
+        self.last = Some(row);
+        let colors = (0..self.area.size.height).map(|y| {
+            if (top..=bottom).contains(&y) {
+                self.color
+            } else {
+                self.background
+            }
+        });
+        let column = Rectangle::new(
+            Point::new(x, self.area.top_left.y),
+            Size::new(1, self.area.size.height),
+        );
+        display.fill_contiguous(&column, colors)
+    }
+}
@@ -0,0 +1,49 @@
+//! Tearing effect output
+
+use display_interface::{DisplayError, WriteOnlyDataCommand};
+use embedded_hal::digital::InputPin;
+
+use crate::{Command, Ili9342C, PixelFormat, Result};
+
+/// Blanking periods signalled on the TE pin
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TearingEffect {
+    /// High during vertical blanking
+    VBlank = 0,
+    /// High during vertical and horizontal blanking
+    VAndHBlank = 1,
+}
+
+impl<IFACE, C> Ili9342C<IFACE, C>
+where
+    IFACE: WriteOnlyDataCommand,
+    C: PixelFormat,
+{
+    /// Enable the TE pin output, or disable it with `None`
+    pub fn set_tearing_effect(&mut self, mode: Option<TearingEffect>) -> Result {
+        match mode {
+            Some(mode) => self.command(Command::TearingEffectOn, &[mode as u8]),
+            None => self.command(Command::TearingEffectOff, &[]),
+        }
+    }
+
+    /// Signal the TE pin when the panel refresh reaches `line` instead of at
+    /// the start of the vertical blanking
+    ///
+    /// Takes effect with [TearingEffect::VBlank], line 0 restores the
+    /// default.
+    pub fn set_tear_scanline(&mut self, line: u16) -> Result {
+        self.command(Command::SetTearScanline, &line.to_be_bytes())
+    }
+}
+
+/// Busy wait for the start of the vertical blanking on the TE pin, errors of
+/// the pin are returned as [DisplayError::BusWriteError]
+pub(crate) fn poll_vsync<TE: InputPin>(te: &mut TE) -> Result {
+    let mut high = || te.is_high().map_err(|_| DisplayError::BusWriteError);
+    while high()? {}
+    while !high()? {}
+    Ok(())
+}
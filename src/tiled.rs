@@ -0,0 +1,196 @@
+//! Several panels combined into one draw target
+
+use display_interface::{DisplayError, WriteOnlyDataCommand};
+use embedded_graphics_core::{
+    draw_target::DrawTarget,
+    geometry::{OriginDimensions, Point, Size},
+    primitives::Rectangle,
+    Pixel,
+};
+
+use crate::{Ili9342C, PixelFormat, Result};
+
+/// Grid of equally sized panels drawn as one large [DrawTarget]
+///
+/// Tiles are given row by row, starting at the top left. Every draw call is
+/// split across the tiles it touches, in the coordinates of each tile.
+/// Contiguous fills crossing tiles horizontally take one window per row and
+/// tile, as the colors arrive row by row.
+///
+/// ```ignore
+/// // Two panels side by side, 640x240 in total
+/// let mut wall = TiledDisplay::new([left, right], 2)?;
+/// Rectangle::new(Point::new(300, 100), Size::new(40, 40))
+///     .into_styled(PrimitiveStyle::with_fill(Rgb565::RED))
+///     .draw(&mut wall)?;
+/// ```
+pub struct TiledDisplay<IFACE, C, const N: usize> {
+    tiles: [Ili9342C<IFACE, C>; N],
+    columns: usize,
+    tile_size: Size,
+}
+
+impl<IFACE, C, const N: usize> TiledDisplay<IFACE, C, N>
+where
+    IFACE: WriteOnlyDataCommand,
+    C: PixelFormat,
+{
+    /// Arrange the tiles in rows of `columns` tiles
+    ///
+    /// The tiles must all have the same size and fill complete rows.
+    pub fn new(tiles: [Ili9342C<IFACE, C>; N], columns: usize) -> Result<Self> {
+        if N == 0 || columns == 0 || !N.is_multiple_of(columns) {
+            return Err(DisplayError::OutOfBoundsError);
+        }
+        let tile_size = tiles[0].size();
+        if tiles.iter().any(|tile| tile.size() != tile_size) {
+            return Err(DisplayError::OutOfBoundsError);
+        }
+        Ok(Self {
+            tiles,
+            columns,
+            tile_size,
+        })
+    }
+
+    /// Area covered by a tile, in the coordinates of the grid
+    pub fn tile_area(&self, index: usize) -> Rectangle {
+        let column = (index % self.columns) as u32;
+        let row = (index / self.columns) as u32;
+        let top_left = Point::new(
+            (column * self.tile_size.width) as i32,
+            (row * self.tile_size.height) as i32,
+        );
+        Rectangle::new(top_left, self.tile_size)
+    }
+
+    /// Tile containing a point
+    fn tile_at(&self, point: Point) -> Option<usize> {
+        if point.x < 0 || point.y < 0 {
+            return None;
+        }
+        let column = point.x as u32 / self.tile_size.width;
+        let row = point.y as u32 / self.tile_size.height;
+        let index = (row as usize) * self.columns + column as usize;
+        ((column as usize) < self.columns && index < N).then_some(index)
+    }
+
+    /// Fill an area within one tile with the next colors, the colors of
+    /// areas outside of the grid are skipped
+    fn fill_tile<I>(&mut self, area: &Rectangle, colors: &mut I) -> Result
+    where
+        I: Iterator<Item = C>,
+    {
+        let mut colors = colors.take((area.size.width * area.size.height) as usize);
+        if let Some(index) = self.tile_at(area.top_left) {
+            let top_left = area.top_left - self.tile_area(index).top_left;
+            self.tiles[index].fill_contiguous(&Rectangle::new(top_left, area.size), &mut colors)?;
+        }
+        colors.for_each(drop);
+        Ok(())
+    }
+
+    /// Access a tile, e.g. to send commands to one panel
+    pub fn tile_mut(&mut self, index: usize) -> Option<&mut Ili9342C<IFACE, C>> {
+        self.tiles.get_mut(index)
+    }
+
+    /// All tiles
+    pub fn tiles_mut(&mut self) -> &mut [Ili9342C<IFACE, C>; N] {
+        &mut self.tiles
+    }
+
+    /// Release the tiles
+    pub fn into_inner(self) -> [Ili9342C<IFACE, C>; N] {
+        self.tiles
+    }
+}
+
+impl<IFACE, C, const N: usize> OriginDimensions for TiledDisplay<IFACE, C, N> {
+    fn size(&self) -> Size {
+        let rows = (N / self.columns) as u32;
+        Size::new(
+            self.tile_size.width * self.columns as u32,
+            self.tile_size.height * rows,
+        )
+    }
+}
+
+impl<IFACE, C, const N: usize> DrawTarget for TiledDisplay<IFACE, C, N>
+where
+    IFACE: WriteOnlyDataCommand,
+    C: PixelFormat,
+{
+    type Color = C;
+
+    type Error = DisplayError;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            if let Some(index) = self.tile_at(point) {
+                let point = point - self.tile_area(index).top_left;
+                self.tiles[index].draw_iter(core::iter::once(Pixel(point, color)))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        let Some(bottom_right) = area.bottom_right() else {
+            return Ok(());
+        };
+        let (width, height) = (self.tile_size.width as i32, self.tile_size.height as i32);
+        let mut colors = colors.into_iter();
+        let mut y = area.top_left.y;
+        while y <= bottom_right.y {
+            // The rows within one row of tiles
+            let band_bottom = ((y.div_euclid(height) + 1) * height - 1).min(bottom_right.y);
+            if area.top_left.x.div_euclid(width) == bottom_right.x.div_euclid(width) {
+                let band = Rectangle::with_corners(
+                    Point::new(area.top_left.x, y),
+                    Point::new(bottom_right.x, band_bottom),
+                );
+                self.fill_tile(&band, &mut colors)?;
+            } else {
+                // Each row is split where it crosses into the next tile
+                for row in y..=band_bottom {
+                    let mut x = area.top_left.x;
+                    while x <= bottom_right.x {
+                        let right = ((x.div_euclid(width) + 1) * width - 1).min(bottom_right.x);
+                        let part =
+                            Rectangle::with_corners(Point::new(x, row), Point::new(right, row));
+                        self.fill_tile(&part, &mut colors)?;
+                        x = right + 1;
+                    }
+                }
+            }
+            y = band_bottom + 1;
+        }
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        for index in 0..N {
+            let tile_area = self.tile_area(index);
+            let part = area.intersection(&tile_area);
+            if part.size != Size::zero() {
+                let top_left = part.top_left - tile_area.top_left;
+                self.tiles[index].fill_solid(&Rectangle::new(top_left, part.size), color)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        for tile in &mut self.tiles {
+            tile.clear(color)?;
+        }
+        Ok(())
+    }
+}
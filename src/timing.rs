@@ -0,0 +1,162 @@
+//! Panel driving timing settings
+
+use display_interface::WriteOnlyDataCommand;
+
+use crate::{Command, Ili9342C, PixelFormat, Result};
+
+/// Driver Timing Control A (0xE8) parameters
+///
+/// Gate driver non-overlap, EQ and pre-charge timings, in the register layout
+/// of the datasheet. A common module value is `[0x85, 0x00, 0x78]`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DriverTimingA(pub [u8; 3]);
+
+/// Driver Timing Control B (0xEA) parameters
+///
+/// Gate driver timing, in the register layout of the datasheet. A common
+/// module value is `[0x00, 0x00]`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DriverTimingB(pub [u8; 2]);
+
+/// Frame rate set by the RTNA clocks per line of Frame Rate Control
+///
+/// The rates are the ones of the undivided internal clock.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FrameRate {
+    Hz119 = 0x10,
+    Hz112 = 0x11,
+    Hz106 = 0x12,
+    Hz100 = 0x13,
+    Hz95 = 0x14,
+    Hz90 = 0x15,
+    Hz86 = 0x16,
+    Hz83 = 0x17,
+    /// The default after reset
+    Hz79 = 0x18,
+    Hz76 = 0x19,
+    Hz73 = 0x1a,
+    Hz70 = 0x1b,
+    Hz68 = 0x1c,
+    Hz65 = 0x1d,
+    Hz63 = 0x1e,
+    Hz61 = 0x1f,
+}
+
+/// Division ratio of the internal clock (DIVA), dividing the frame rate
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FrameDivision {
+    Div1 = 0b00,
+    Div2 = 0b01,
+    Div4 = 0b10,
+    Div8 = 0b11,
+}
+
+/// Scan mode of the non-display area (PTG bits of Display Function Control)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NonDisplayScan {
+    /// Normal scan
+    Normal = 0b00,
+    /// Interval scan, every `scan_cycle` frames
+    Interval = 0b10,
+}
+
+/// Display Function Control (0xB6) settings
+///
+/// The defaults produce the `0x08, 0x82, 0x27` bytes of the vendor init.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DisplayFunctionConfig {
+    /// Scan mode of the non-display area
+    pub non_display_scan: NonDisplayScan,
+    /// Source and VCOM output level in the non-display area (PT), 2 bits
+    pub non_display_level: u8,
+    /// Normally white liquid crystal (REV)
+    pub normally_white: bool,
+    /// Scan the gate outputs from the last to the first line (GS)
+    pub reverse_gate_scan: bool,
+    /// Scan the source outputs from the last to the first column (SS)
+    pub reverse_source_scan: bool,
+    /// Interlaced gate scan (SM)
+    pub interlaced_gate_scan: bool,
+    /// Scan cycle of the non-display area in interval scan mode (ISC), 4 bits
+    pub scan_cycle: u8,
+    /// Number of lines to drive, a multiple of 8 (NL)
+    pub lines: u16,
+}
+
+impl Default for DisplayFunctionConfig {
+    fn default() -> Self {
+        Self {
+            non_display_scan: NonDisplayScan::Interval,
+            non_display_level: 0,
+            normally_white: true,
+            reverse_gate_scan: false,
+            reverse_source_scan: false,
+            interlaced_gate_scan: false,
+            scan_cycle: 2,
+            lines: 320,
+        }
+    }
+}
+
+impl DisplayFunctionConfig {
+    /// Register parameters of this configuration
+    pub fn to_bytes(&self) -> [u8; 3] {
+        [
+            ((self.non_display_scan as u8) << 2) | (self.non_display_level & 0x03),
+            ((self.normally_white as u8) << 7)
+                | ((self.reverse_gate_scan as u8) << 6)
+                | ((self.reverse_source_scan as u8) << 5)
+                | ((self.interlaced_gate_scan as u8) << 4)
+                | (self.scan_cycle & 0x0f),
+            ((self.lines / 8).saturating_sub(1) as u8) & 0x3f,
+        ]
+    }
+}
+
+impl<IFACE, C> Ili9342C<IFACE, C>
+where
+    IFACE: WriteOnlyDataCommand,
+    C: PixelFormat,
+{
+    /// Set the frame rate of normal mode with Frame Rate Control (0xB1)
+    pub fn set_frame_rate(&mut self, division: FrameDivision, rate: FrameRate) -> Result {
+        self.command(Command::FrameControl, &[division as u8, rate as u8])
+    }
+
+    /// Set the frame rate of idle mode with Frame Rate Control (0xB2)
+    pub fn set_idle_frame_rate(&mut self, division: FrameDivision, rate: FrameRate) -> Result {
+        self.command(Command::IdleModeFrameRate, &[division as u8, rate as u8])
+    }
+
+    /// Set the frame rate of partial mode with Frame Rate Control (0xB3)
+    pub fn set_partial_frame_rate(&mut self, division: FrameDivision, rate: FrameRate) -> Result {
+        self.command(Command::PartialModeFrameRate, &[division as u8, rate as u8])
+    }
+
+    /// Write Display Function Control
+    pub fn set_display_function(&mut self, config: &DisplayFunctionConfig) -> Result {
+        self.command(Command::DisplayFunctionControl, &config.to_bytes())
+    }
+
+    /// Write Driver Timing Control A
+    pub fn set_driver_timing_a(&mut self, timing: DriverTimingA) -> Result {
+        self.command(Command::DriverTimingControlA, &timing.0)
+    }
+
+    /// Write Driver Timing Control B
+    pub fn set_driver_timing_b(&mut self, timing: DriverTimingB) -> Result {
+        self.command(Command::DriverTimingControlB, &timing.0)
+    }
+}
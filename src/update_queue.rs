@@ -0,0 +1,156 @@
+//! Scheduling redraws of dirty rectangles
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_graphics_core::{geometry::Point, primitives::Rectangle};
+
+use crate::{Ili9342C, PixelFormat, Result};
+
+/// Limit of the redraws of one [UpdateQueue::flush]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum UpdateBudget {
+    /// Pixel bytes sent for the redrawn rectangles
+    Bytes(usize),
+    /// Microseconds spent redrawing, measured with the clock of
+    /// [Ili9342C::set_clock]. Without a clock all rectangles are redrawn.
+    Micros(u64),
+}
+
+/// Queue of dirty rectangles redrawn within a budget per frame
+///
+/// Rectangles are redrawn in the order they were added. A rectangle inside
+/// a queued one is dropped, queued ones inside a new one are replaced by it.
+/// When all `N` slots are used, the new rectangle is merged with the last
+/// one. At least one rectangle is redrawn per flush, whatever the budget.
+///
+/// ```ignore
+/// let mut queue = UpdateQueue::<16>::new();
+/// queue.push(clock_widget.area());
+/// queue.push(chart_widget.area());
+/// // Once per frame, about 8ms of the bus at 40MHz
+/// queue.flush(&mut display, UpdateBudget::Bytes(40_000), |display, area| {
+///     ui.draw_area(display, area)
+/// })?;
+/// ```
+pub struct UpdateQueue<const N: usize> {
+    areas: [Rectangle; N],
+    len: usize,
+}
+
+impl<const N: usize> Default for UpdateQueue<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> UpdateQueue<N> {
+    /// Create an empty queue
+    pub const fn new() -> Self {
+        Self {
+            areas: [Rectangle::zero(); N],
+            len: 0,
+        }
+    }
+
+    /// Queued rectangles, next first
+    pub fn areas(&self) -> &[Rectangle] {
+        &self.areas[..self.len]
+    }
+
+    /// Number of queued rectangles
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether no rectangle is queued
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Drop all queued rectangles
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Queue a rectangle to redraw
+    pub fn push(&mut self, area: Rectangle) {
+        let Some(bottom_right) = area.bottom_right() else {
+            return;
+        };
+        if self.areas().iter().any(|queued| contains(queued, &area)) {
+            return;
+        }
+        let mut kept = 0;
+        for index in 0..self.len {
+            if !contains(&area, &self.areas[index]) {
+                self.areas[kept] = self.areas[index];
+                kept += 1;
+            }
+        }
+        self.len = kept;
+        if self.len < N {
+            self.areas[self.len] = area;
+            self.len += 1;
+        } else if let Some(last) = self.areas.last_mut() {
+            let last_corner = last.bottom_right().unwrap_or(last.top_left);
+            *last = Rectangle::with_corners(
+                Point::new(
+                    last.top_left.x.min(area.top_left.x),
+                    last.top_left.y.min(area.top_left.y),
+                ),
+                Point::new(
+                    last_corner.x.max(bottom_right.x),
+                    last_corner.y.max(bottom_right.y),
+                ),
+            );
+        }
+    }
+
+    /// Redraw queued rectangles with `redraw` until the budget is used,
+    /// returning the number redrawn
+    ///
+    /// The rest stays queued for the next flush.
+    pub fn flush<IFACE, C, F>(
+        &mut self,
+        display: &mut Ili9342C<IFACE, C>,
+        budget: UpdateBudget,
+        mut redraw: F,
+    ) -> Result<usize>
+    where
+        IFACE: WriteOnlyDataCommand,
+        C: PixelFormat,
+        F: FnMut(&mut Ili9342C<IFACE, C>, Rectangle) -> Result,
+    {
+        let start = display.state.timing.now();
+        let mut bytes = 0;
+        let mut done = 0;
+        while done < self.len {
+            let area = self.areas[done];
+            let cost = C::data_len((area.size.width * area.size.height) as usize);
+            let over = match budget {
+                UpdateBudget::Bytes(limit) => bytes + cost > limit,
+                UpdateBudget::Micros(limit) => match (start, display.state.timing.now()) {
+                    (Some(start), Some(now)) => now.saturating_sub(start) >= limit,
+                    _ => false,
+                },
+            };
+            if over && done > 0 {
+                break;
+            }
+            redraw(display, area)?;
+            bytes += cost;
+            done += 1;
+        }
+        self.areas.copy_within(done..self.len, 0);
+        self.len -= done;
+        Ok(done)
+    }
+}
+
+/// Whether `outer` covers `inner`
+fn contains(outer: &Rectangle, inner: &Rectangle) -> bool {
+    match inner.bottom_right() {
+        Some(corner) => outer.contains(inner.top_left) && outer.contains(corner),
+        None => true,
+    }
+}
@@ -0,0 +1,56 @@
+//! Watchdog hook for long transfers
+
+use display_interface::WriteOnlyDataCommand;
+
+use crate::{Ili9342C, PixelFormat};
+
+/// Hook called at least every `bytes` bytes of a transfer
+#[derive(Clone, Copy)]
+pub(crate) struct Watchdog {
+    pub(crate) hook: fn(),
+    pub(crate) bytes: usize,
+}
+
+impl<IFACE, C> Ili9342C<IFACE, C>
+where
+    IFACE: WriteOnlyDataCommand,
+    C: PixelFormat,
+{
+    /// Call `hook` during long transfers, at least every `interval_ms` at a
+    /// bus clock of `bus_hz`
+    ///
+    /// Transfers are split into chunks of the bytes sent in `interval_ms`, or
+    /// smaller ones with [Self::set_max_transfer_size], and the hook is
+    /// called before each chunk after the first. Pass the time left for the
+    /// watchdog after other work as the interval. `None` removes the hook.
+    ///
+    /// ```ignore
+    /// // Full screen clears take about 30ms at 40MHz, pet a 50ms watchdog
+    /// display.set_watchdog_hook(Some(pet_watchdog), 20, 40_000_000);
+    /// ```
+    pub fn set_watchdog_hook(&mut self, hook: Option<fn()>, interval_ms: u32, bus_hz: u32) {
+        let bytes = bus_hz as u64 / 8 * interval_ms as u64 / 1000;
+        self.state.watchdog = hook.map(|hook| Watchdog {
+            hook,
+            bytes: (bytes as usize).max(C::BYTES_PER_PACK),
+        });
+    }
+
+    /// Largest number of bytes sent per interface call
+    pub(crate) fn transfer_limit(&self) -> Option<usize> {
+        match (self.state.max_transfer, self.state.watchdog) {
+            (Some(max), Some(watchdog)) => Some(max.min(watchdog.bytes)),
+            (max, watchdog) => max.or(watchdog.map(|watchdog| watchdog.bytes)),
+        }
+    }
+
+    /// Run the hooks between the interface calls of a transfer
+    pub(crate) fn between_chunks(&self) {
+        if let Some(hook) = self.state.yield_hook {
+            hook();
+        }
+        if let Some(watchdog) = self.state.watchdog {
+            (watchdog.hook)();
+        }
+    }
+}